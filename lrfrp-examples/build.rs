@@ -0,0 +1,4 @@
+fn main() {
+    lrfrpc::build::compile("lrfrp/counter.lrfrp");
+    lrfrpc::build::compile("lrfrp/regulator.lrfrp");
+}
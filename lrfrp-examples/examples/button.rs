@@ -0,0 +1,51 @@
+use lrfrp_macros::frp;
+
+use std::{thread, time::Duration};
+
+frp! {
+    mod Button;
+
+    Args {
+        limit: i32,
+    }
+
+    In {
+        press: Event<i32>,
+        level: i32,
+    }
+
+    Out {
+        strong: Event<i32>,
+        alarm: Event<i32>,
+        last: i32,
+        doubled: i32,
+    }
+
+    fn is_strong(x: i32) -> bool = x >= 5;
+    fn double(x: i32) -> i32 = x * 2;
+
+    let strong = filter(is_strong, press);
+    let alarm = merge(strong, when(level > limit, level));
+    let last = hold(0, press);
+    let doubled = hold(0, map(double, alarm));
+}
+
+fn main() {
+    let args = Button::Args { limit: 90 };
+    let mut frp = Button::FRP::new(args);
+
+    for i in 0..20 {
+        let input = Button::In {
+            press: if i % 3 == 0 { Some(i) } else { None },
+            level: i * 7,
+        };
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!(
+            "press={:?}, level={}, strong={:?}, alarm={:?}, last={}, doubled={}",
+            input.press, input.level, output.strong, output.alarm, output.last, output.doubled
+        );
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,55 @@
+use lrfrp_macros::frp;
+
+use std::{thread, time::Duration};
+
+frp! {
+    mod EdgeDetector;
+
+    In {
+        level: bool,
+        x: f32,
+    }
+
+    Out {
+        rising: bool,
+        delta: f32,
+        delta2: f32,
+        count: i32,
+        start: f32,
+    }
+
+    let rising = level && !(False -> pre level);
+    let delta = 0.0 -> x - pre x;
+    // the change over two instants
+    let delta2 = 0.0 -> x - pre (x -> pre x);
+    let count = 0 -> pre count + if rising then 1 else 0;
+    // the initial value comes from the first input sample
+    let start = x -> pre start;
+}
+
+fn main() {
+    let mut frp = EdgeDetector::FRP::new();
+    let mut input = EdgeDetector::In {
+        level: false,
+        x: 0.0,
+    };
+
+    for i in 0..20 {
+        input.level = (i / 3) % 2 == 1;
+        input.x = 10.0 + (i as f32 * 0.4).sin();
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!(
+            "level={:5}, x={:2.2}, rising={:5}, delta={:+2.2}, delta2={:+2.2}, count={}, start={:2.2}",
+            input.level,
+            input.x,
+            output.rising,
+            output.delta,
+            output.delta2,
+            output.count,
+            output.start
+        );
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,51 @@
+use lrfrp_macros::frp;
+
+use std::{thread, time::Duration};
+
+frp! {
+    mod Envelope;
+
+    Args {
+        init: f32,
+    }
+
+    In {
+        x: f32,
+    }
+
+    Out {
+        lo: f32,
+        hi: f32,
+        range: f32,
+    }
+
+    fn widen(x: f32, (lo, hi): (f32, f32)) -> (f32, f32)
+        = (if x < lo then x else lo, if x > hi then x else hi);
+
+    fn width((lo, hi): (f32, f32)) -> f32 = hi - lo;
+
+    let (lo, hi) = widen(x, bounds);
+    let range = {
+        let (l, h) = (lo, hi);
+        width((l, h))
+    };
+    let bounds: (f32, f32) <- delay (init, init) -< (lo, hi);
+}
+
+fn main() {
+    let args = Envelope::Args { init: 0.0 };
+    let mut frp = Envelope::FRP::new(args);
+    let mut input = Envelope::In { x: 0.0 };
+
+    for i in 0.. {
+        input.x = (i as f32 * 0.3).sin() * (i as f32 * 0.05);
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!(
+            "x={:2.2}, lo={:2.2}, hi={:2.2}, range={:2.2}",
+            input.x, output.lo, output.hi, output.range
+        );
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,74 @@
+use std::{thread, time::Duration};
+
+use lrfrp_macros::frp;
+
+frp! {
+    mod FanController;
+
+    Args { fan_init: bool }
+    In { tmp: f32, hmd: f32 }
+    Out { fan: bool, di: f32 }
+
+    fn calc_di(tmp: f32, hmd: f32) -> f32
+        = 0.81 * tmp + 0.01 * hmd * (0.99 * tmp - 14.3) + 46.3;
+
+    let di = calc_di(tmp, hmd);
+    let fan = di >= th;
+    let fan_delayed: bool <- delay fan_init -< fan;
+    let th = 75.0 + if fan_delayed then -0.5 else 0.5;
+}
+
+frp! {
+    mod Counter;
+
+    In { tick: bool }
+    Out { count: u32 }
+
+    let count = (0 -> pre count) + if tick then 1 else 0;
+}
+
+frp! {
+    mod Room;
+
+    Args { fan_init: bool }
+    In { tmp: f32, hmd: f32 }
+    Out { fan: bool, di: f32, switches: u32 }
+
+    let (fan, di) = FanController(tmp, hmd) with { fan_init };
+    let switched = fan != (fan_init -> pre fan);
+    let switches = Counter(switched);
+}
+
+fn main() {
+    let args = Room::Args { fan_init: false };
+    let mut frp = Room::FRP::new(args);
+
+    let mut input = Room::In::new(30.0, 60.0);
+    let (mut dt, mut dh) = (0.5, 1.0);
+
+    for _ in 0..60 {
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!(
+            "tmp={:2.2}, hmd={:2.2}, fan: {:-3}, di: {:2.2}, switches: {}",
+            input.tmp,
+            input.hmd,
+            if output.fan { "ON" } else { "OFF" },
+            output.di,
+            output.switches,
+        );
+
+        thread::sleep(Duration::from_millis(33));
+
+        if input.tmp > 35.0 || input.tmp < 20.0 {
+            dt = -dt;
+        }
+        if input.hmd > 80.0 || input.hmd < 50.0 {
+            dh = -dh;
+        }
+
+        input.tmp += dt;
+        input.hmd += dh;
+    }
+}
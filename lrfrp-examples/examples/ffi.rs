@@ -0,0 +1,48 @@
+// `#[ffi]` exports the program to C as `Limiter_new`, `Limiter_run`, `Limiter_sample`
+// and `Limiter_size`, declared by the header of `lrfrpc --emit header`. They are called
+// here the way C calls them, on storage whose layout is unknown to the caller.
+use lrfrp_macros::frp;
+
+use std::alloc::{self, Layout};
+
+frp! {
+    #[ffi]
+    mod Limiter;
+
+    Args {
+        limit: i32,
+    }
+
+    In {
+        value: i32,
+    }
+
+    Out {
+        limited: i32,
+        clipped: bool,
+    }
+
+    let limited = if value > limit then limit else if value < -limit then -limit else value;
+    let clipped = limited != value;
+}
+
+fn main() {
+    unsafe {
+        // `malloc` on the C side
+        let layout = Layout::from_size_align(Limiter::Limiter_size(), 16).unwrap();
+        let frp = alloc::alloc(layout) as *mut Limiter::FRP;
+
+        Limiter::Limiter_new(frp, &Limiter::Args { limit: 10 });
+        assert!(Limiter::Limiter_sample(frp).is_null());
+        for &value in [3, 12, -7, -30].iter() {
+            Limiter::Limiter_run(frp, &Limiter::In { value });
+            let output = &*Limiter::Limiter_sample(frp);
+            println!(
+                "{} -> {} (clipped: {})",
+                value, output.limited, output.clipped
+            );
+        }
+
+        alloc::dealloc(frp as *mut u8, layout);
+    }
+}
@@ -0,0 +1,17 @@
+// the program is written in `lrfrp/counter.lrfrp` instead of a `frp!` macro
+include!(concat!(env!("OUT_DIR"), "/counter.rs"));
+
+fn main() {
+    let mut counter = Counter::FRP::new(Counter::Args { step: 2 });
+    let inputs = [
+        (true, false),
+        (true, false),
+        (false, false),
+        (true, true),
+        (true, false),
+    ];
+    for &(up, reset) in inputs.iter() {
+        counter.run(&Counter::In::new(up, reset));
+        println!("{:?}", counter.sample().unwrap().values());
+    }
+}
@@ -0,0 +1,55 @@
+use lrfrp::{frp, FrpProgram};
+
+frp! {
+    mod Accumulator;
+
+    Args { init: i32 }
+    In { x: i32 }
+    Out { sum: i32 }
+
+    let sum = x + sum_delayed;
+    let sum_delayed: i32 <- delay init -< sum;
+}
+
+frp! {
+    mod Toggle;
+
+    In { press: bool }
+    Out { on: bool }
+
+    let on = if press then !on_delayed else on_delayed;
+    let on_delayed: bool <- delay False -< on;
+}
+
+// runs any program over the inputs and collects the outputs of every instant
+fn drive<P>(program: &mut P, inputs: &[P::In]) -> Vec<P::Out>
+where
+    P: FrpProgram,
+    P::Out: Clone,
+{
+    inputs
+        .iter()
+        .map(|input| {
+            program.run(input);
+            program.sample().unwrap().clone()
+        })
+        .collect()
+}
+
+fn main() {
+    let mut accumulator = <Accumulator::FRP as FrpProgram>::new(Accumulator::Args { init: 10 });
+    let inputs: Vec<_> = (1..=5).map(Accumulator::In::new).collect();
+    println!("{:?}", drive(&mut accumulator, &inputs));
+
+    // the same inputs give the same outputs after reset
+    accumulator.reset();
+    println!("{:?}", drive(&mut accumulator, &inputs));
+
+    let mut toggle = <Toggle::FRP as FrpProgram>::new(());
+    let inputs: Vec<_> = [true, false, true, true, false]
+        .iter()
+        .copied()
+        .map(Toggle::In::new)
+        .collect();
+    println!("{:?}", drive(&mut toggle, &inputs));
+}
@@ -0,0 +1,46 @@
+use lrfrp_macros::frp;
+
+use std::{thread, time::Duration};
+
+frp! {
+    mod MovingAverage;
+
+    In {
+        x: f32,
+    }
+
+    Out {
+        average: f32,
+        fir: f32,
+        delayed: f32,
+    }
+
+    // mean of the last four samples
+    let average = (x + x1 + x2 + x3) / 4.0;
+    let x1: f32 <- delay 0.0 -< x;
+    let x2: f32 <- delay 2 0.0 -< x;
+    let x3: f32 <- delay 3 0.0 -< x;
+
+    // 3-tap FIR filter
+    let fir = 0.5 * x + 0.3 * x1 + 0.2 * x2;
+
+    let delayed = x8;
+    let x8: f32 <- delay 8 0.0 -< x;
+}
+
+fn main() {
+    let mut frp = MovingAverage::FRP::new();
+    let mut input = MovingAverage::In { x: 0.0 };
+
+    for i in 0..20 {
+        input.x = i as f32;
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!(
+            "x={:2.1}, average={:2.2}, fir={:2.2}, delayed={:2.1}",
+            input.x, output.average, output.fir, output.delayed
+        );
+        thread::sleep(Duration::from_millis(100));
+    }
+}
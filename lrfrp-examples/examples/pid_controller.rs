@@ -0,0 +1,65 @@
+use lrfrp_macros::frp;
+
+use std::{thread, time::Duration};
+
+frp! {
+    mod PidController;
+
+    struct PidState {
+        integral: f32,
+        prev_error: f32,
+    }
+
+    Args {
+        kp: f32,
+        ki: f32,
+        kd: f32,
+        dt: f32,
+    }
+
+    In {
+        setpoint: f32,
+        measured: f32,
+    }
+
+    Out {
+        control: f32,
+        state: PidState,
+    }
+
+    let error = setpoint - measured;
+    let state = PidState {
+        integral: prev.integral + error * dt,
+        prev_error: error,
+    };
+    let control = kp * error + ki * state.integral + kd * (error - prev.prev_error) / dt;
+    let prev: PidState <- delay PidState { integral: 0.0, prev_error: 0.0 } -< state;
+}
+
+fn main() {
+    let args = PidController::Args {
+        kp: 0.6,
+        ki: 0.2,
+        kd: 0.05,
+        dt: 0.1,
+    };
+    let mut frp = PidController::FRP::new(args);
+    let mut input = PidController::In {
+        setpoint: 50.0,
+        measured: 20.0,
+    };
+
+    for _ in 0..100 {
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!(
+            "measured={:2.2}, control={:2.2}, integral={:2.2}",
+            input.measured, output.control, output.state.integral
+        );
+        thread::sleep(Duration::from_millis(100));
+
+        // plant: first-order response to the control value
+        input.measured += 0.1 * output.control;
+    }
+}
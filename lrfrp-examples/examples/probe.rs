@@ -0,0 +1,51 @@
+// A dashboard reads the error and the integrator state of a PI controller through
+// `probe()`, while `Out` carries only the command.
+use lrfrp_macros::frp;
+
+frp! {
+    mod PiController;
+
+    Args {
+        kp: f32,
+        ki: f32,
+    }
+
+    In {
+        target: f32,
+        measured: f32,
+    }
+
+    Out {
+        command: f32,
+    }
+
+    Probe {
+        error: f32,
+        integral_delayed: f32,
+    }
+
+    let error = target - measured;
+    let integral = integral_delayed + error;
+    let command = kp * error + ki * integral;
+    let integral_delayed: f32 <- delay 0.0 -< integral;
+}
+
+fn main() {
+    let mut frp = PiController::FRP::new(PiController::Args { kp: 0.6, ki: 0.2 });
+    assert!(frp.probe().is_none());
+
+    let mut measured = 15.0;
+    for _ in 0..10 {
+        frp.run(&PiController::In {
+            target: 20.0,
+            measured,
+        });
+        let command = frp.sample().unwrap().command;
+        let probe = frp.probe().unwrap();
+        println!(
+            "measured = {:5.2}, command = {:5.2} | error = {:5.2}, integral = {:5.2}",
+            measured, command, probe.error, probe.integral_delayed
+        );
+        measured += command * 0.5;
+    }
+}
@@ -0,0 +1,74 @@
+// Records the inputs of a heater controller in the field as a CSV log, then replays
+// the log through a retuned controller and prints its outputs as CSV.
+use lrfrp::replay::{self, CsvRecorder, ReplayError};
+use lrfrp_macros::frp;
+
+use std::io::{self, Cursor};
+
+frp! {
+    mod Heater;
+
+    enum Mode {
+        Off,
+        Eco,
+        Comfort,
+    }
+
+    Args {
+        hysteresis: f32,
+    }
+
+    In {
+        mode: Mode,
+        tmp: f32,
+        window_open: Event<bool>,
+    }
+
+    Out {
+        heating: bool,
+        target: f32,
+    }
+
+    fn target_of(mode: Mode) -> f32 = match mode {
+        Mode::Off => 5.0,
+        Mode::Eco => 18.0,
+        Mode::Comfort => 21.0,
+    };
+
+    let window = hold(False, window_open);
+    let target = if window then 5.0 else target_of(mode);
+    let heating = if tmp < target - hysteresis then True
+        else if tmp > target + hysteresis then False
+        else heating_delayed;
+    let heating_delayed: bool <- delay False -< heating;
+}
+
+fn main() -> Result<(), ReplayError> {
+    // the log written in the field, with the arguments of the deployed controller
+    let mut recorder = CsvRecorder::new(Vec::new())?;
+    let modes = [Heater::Mode::Eco, Heater::Mode::Comfort, Heater::Mode::Off];
+    for i in 0..12 {
+        recorder.record(&Heater::In {
+            mode: modes[i / 5],
+            tmp: 17.0 + i as f32 * 0.4,
+            window_open: match i {
+                6 => Some(true),
+                8 => Some(false),
+                _ => None,
+            },
+        })?;
+    }
+    let log = String::from_utf8(recorder.finish()?).unwrap();
+    println!("{}", log);
+
+    let stdout = io::stdout();
+    let args = Heater::Args { hysteresis: 0.2 };
+    let instants = replay::replay::<Heater::FRP>(Cursor::new(&log), stdout.lock(), Some(args))?;
+    println!("replayed {} instants", instants);
+
+    // the columns are checked against the fields of `In` and `Args`
+    let log = "tmp,mode,window\n18.0,Eco,\n";
+    let error = replay::replay::<Heater::FRP>(Cursor::new(log), io::sink(), None).unwrap_err();
+    println!("{}", error);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// A supervisor switches the gain of an embedded controller by reinitializing it in
+// place with `reset_with`, without rebuilding the supervisor.
+use lrfrp_macros::frp;
+
+frp! {
+    mod Integrator;
+
+    Args {
+        gain: i32,
+    }
+
+    In {
+        error: i32,
+    }
+
+    Out {
+        integral: i32,
+        last_errors: i32,
+    }
+
+    let integral = integral_delayed + gain * error;
+    let integral_delayed: i32 <- delay 0 -< integral;
+
+    let last_errors = error + error1 + error2;
+    let error1: i32 <- delay 0 -< error;
+    let error2: i32 <- delay 2 0 -< error;
+}
+
+struct Supervisor {
+    controller: Integrator::FRP,
+    steps: u32,
+}
+
+impl Supervisor {
+    fn step(&mut self, error: i32) -> (i32, i32) {
+        self.steps += 1;
+        if self.steps == 5 {
+            println!("-- switching to the fine mode");
+            self.controller.reset_with(Integrator::Args { gain: 1 });
+        }
+        self.controller.run(&Integrator::In { error });
+        self.controller.sample().unwrap().values()
+    }
+}
+
+fn main() {
+    let mut supervisor = Supervisor {
+        controller: Integrator::FRP::new(Integrator::Args { gain: 10 }),
+        steps: 0,
+    };
+    for &error in [3, 2, 2, 1, 1, 1, 0, -1].iter() {
+        let (integral, last_errors) = supervisor.step(error);
+        println!(
+            "error = {:2}: integral = {:3}, last errors = {}",
+            error, integral, last_errors
+        );
+    }
+
+    // `reset` keeps the arguments given last
+    supervisor.controller.reset();
+    assert!(supervisor.controller.sample().is_none());
+    supervisor.controller.run(&Integrator::In { error: 4 });
+    assert_eq!(supervisor.controller.sample().unwrap().values(), (4, 4));
+}
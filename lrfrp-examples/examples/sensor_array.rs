@@ -0,0 +1,57 @@
+use lrfrp_macros::frp;
+
+use std::{thread, time::Duration};
+
+frp! {
+    mod SensorArray;
+
+    Args {
+        gain: [f32; 4],
+    }
+
+    In {
+        raw: [i16; 4],
+    }
+
+    Out {
+        calibrated: [f32; 4],
+        total: f32,
+        peak: f32,
+        first: f32,
+    }
+
+    fn to_volts(x: i16) -> f32 = x as f32 * 0.001;
+    fn scale((x, g): (f32, f32)) -> f32 = x * g;
+    fn add(acc: f32, x: f32) -> f32 = acc + x;
+    fn max(acc: f32, x: f32) -> f32 = if x > acc then x else acc;
+
+    let volts = map(to_volts, raw);
+    let calibrated = map(scale, zip(volts, gain));
+    let total = fold(add, 0.0, calibrated);
+    let peak = fold(max, last_peak, calibrated);
+    let first = calibrated[0];
+    let last_peak = peaks[0];
+    let peaks: [f32; 2] <- delay [0.0; 2] -< [peak, last_peak];
+}
+
+fn main() {
+    let args = SensorArray::Args {
+        gain: [1.0, 2.0, 0.5, 1.5],
+    };
+    let mut frp = SensorArray::FRP::new(args);
+    let mut input = SensorArray::In { raw: [0; 4] };
+
+    for i in 0..20 {
+        for (ch, raw) in input.raw.iter_mut().enumerate() {
+            *raw = ((i * 37 + ch as i16 * 101) % 200) - 100;
+        }
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!(
+            "raw={:?}, calibrated={:?}, total={:2.3}, peak={:2.3}, first={:2.3}",
+            input.raw, output.calibrated, output.total, output.peak, output.first
+        );
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,56 @@
+// The state of a program is checkpointed as JSON and restored into a fresh program,
+// which carries on exactly where the first one was.
+use lrfrp_macros::frp;
+
+frp! {
+    mod Window;
+
+    Args {
+        threshold: i32,
+    }
+
+    In {
+        x: i32,
+    }
+
+    Out {
+        sum: i32,
+        over: bool,
+    }
+
+    // the sum of the last 40 inputs
+    let sum = sum_delayed + x - x40;
+    let sum_delayed: i32 <- delay 0 -< sum;
+    let x40: i32 <- delay 40 0 -< x;
+    let over = sum > threshold;
+}
+
+fn main() {
+    let args = Window::Args { threshold: 1000 };
+    let mut frp = Window::FRP::new(args.clone());
+    for x in 0..50 {
+        frp.run(&Window::In { x });
+    }
+
+    let json = serde_json::to_string(&frp.snapshot()).unwrap();
+    println!("checkpoint: {} bytes", json.len());
+
+    let mut restored = Window::FRP::new(args);
+    let state: Window::State = serde_json::from_str(&json).unwrap();
+    restored.restore(state).unwrap();
+    for x in 50..60 {
+        frp.run(&Window::In { x });
+        restored.run(&Window::In { x });
+        let (sum, over) = restored.sample().unwrap().values();
+        assert_eq!(frp.sample().unwrap().values(), (sum, over));
+        println!("x = {}: sum = {}, over = {}", x, sum, over);
+    }
+
+    // a checkpoint of another version of the program is rejected
+    let mut state = frp.snapshot();
+    state.version ^= 1;
+    match restored.restore(state) {
+        Ok(()) => unreachable!(),
+        Err(e) => println!("{}", e),
+    }
+}
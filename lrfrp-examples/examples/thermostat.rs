@@ -0,0 +1,71 @@
+use lrfrp_macros::frp;
+
+use std::{thread, time::Duration};
+
+frp! {
+    mod Thermostat;
+
+    enum Mode {
+        Idle,
+        Heating,
+        Cooling,
+    }
+
+    Args {
+        target: f32,
+        band: f32,
+    }
+
+    In {
+        tmp: f32,
+    }
+
+    Out {
+        mode: Mode,
+        heater: bool,
+        cooler: bool,
+    }
+
+    let mode = match prev {
+        Mode::Idle if tmp < target - band => Mode::Heating,
+        Mode::Idle if tmp > target + band => Mode::Cooling,
+        Mode::Heating if tmp >= target => Mode::Idle,
+        Mode::Cooling if tmp <= target => Mode::Idle,
+        m => m,
+    };
+    let heater = match mode {
+        Mode::Heating => True,
+        _ => False,
+    };
+    let cooler = match mode {
+        Mode::Cooling => True,
+        Mode::Idle | Mode::Heating => False,
+    };
+    let prev: Mode <- delay Mode::Idle -< mode;
+}
+
+fn main() {
+    let args = Thermostat::Args {
+        target: 22.0,
+        band: 1.5,
+    };
+    let mut frp = Thermostat::FRP::new(args);
+    let mut input = Thermostat::In { tmp: 18.0 };
+
+    loop {
+        frp.run(&input);
+        let output = frp.sample().unwrap();
+
+        println!("tmp={:2.2}, mode: {:?}", input.tmp, output.mode);
+        thread::sleep(Duration::from_millis(200));
+
+        // room: heats up or cools down depending on the actuators
+        input.tmp += if output.heater {
+            0.4
+        } else if output.cooler {
+            -0.4
+        } else {
+            0.1
+        };
+    }
+}
@@ -0,0 +1,67 @@
+// Records every signal of a thermostat per instant, as CSV on the standard output and
+// as a VCD file to be opened in a waveform viewer such as GTKWave.
+use lrfrp::trace::{CsvWriter, VcdWriter};
+use lrfrp_macros::frp;
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+frp! {
+    mod Thermostat;
+
+    enum Mode {
+        Idle,
+        Heating,
+    }
+
+    struct Reading {
+        tmp: f32,
+        rising: bool,
+    }
+
+    In {
+        tmp: f32,
+        alarm: Event<i32>,
+    }
+
+    Out {
+        mode: Mode,
+        heater: bool,
+        severe: Event<i32>,
+        last_alarm: i32,
+    }
+
+    fn is_severe(level: i32) -> bool = level > 5;
+
+    let reading = Reading { tmp: tmp, rising: tmp > prev_tmp };
+    let mode = if reading.tmp < 19.0 then Mode::Heating else if reading.tmp > 21.0 then Mode::Idle else prev_mode;
+    let heater = mode == Mode::Heating;
+    let severe = filter(is_severe, alarm);
+    let last_alarm = hold(0, alarm);
+    let prev_tmp: f32 <- delay 20.0 -< tmp;
+    let prev_mode: Mode <- delay Mode::Idle -< mode;
+}
+
+fn main() -> io::Result<()> {
+    let path = std::env::temp_dir().join("thermostat.vcd");
+    let mut vcd = VcdWriter::with_timescale(BufWriter::new(File::create(&path)?), "1 s");
+    let mut csv = CsvWriter::new(io::stdout());
+
+    // one program per writer, fed the same inputs
+    let mut frp_vcd = Thermostat::FRP::new();
+    let mut frp_csv = Thermostat::FRP::new();
+    let tmps = [20.0, 19.5, 18.5, 18.9, 19.6, 20.4, 21.3, 21.0, 20.2, 19.1];
+    for (i, &tmp) in tmps.iter().enumerate() {
+        let input = Thermostat::In {
+            tmp,
+            alarm: if i % 4 == 3 { Some(i as i32) } else { None },
+        };
+        frp_vcd.run_traced(&input, &mut vcd);
+        frp_csv.run_traced(&input, &mut csv);
+    }
+
+    csv.finish()?;
+    vcd.finish()?;
+    eprintln!("wrote {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,71 @@
+// Checks programs with `assert_trace!`, including the report of a diverging instant.
+
+use lrfrp::assert_trace;
+use lrfrp::testing::approx;
+use lrfrp_macros::frp;
+
+frp! {
+    mod Accumulator;
+
+    Args {
+        init: i32,
+    }
+
+    In {
+        input: i32,
+    }
+
+    Out {
+        output: i32,
+    }
+
+    let output = input + output_delayed;
+    let output_delayed: i32 <- delay init -< output;
+}
+
+frp! {
+    mod Smoother;
+
+    In {
+        x: f32,
+        hold: bool,
+    }
+
+    Out {
+        y: f32,
+        rising: bool,
+    }
+
+    let y = if hold then y_delayed else y_delayed + 0.3 * (x - y_delayed);
+    let rising = y > y_delayed;
+    let y_delayed: f32 <- delay 0.0 -< y;
+}
+
+#[test]
+fn accumulator_sums_its_inputs() {
+    assert_trace!(Accumulator, Accumulator::Args { init: 10 }, [
+        in { input: 1 } => out { output: 11 },
+        in { input: 2 } => out { output: 13 },
+        in { input: -5 } => out { output: 8 },
+    ]);
+}
+
+#[test]
+fn smoother_within_tolerance() {
+    assert_trace!(Smoother, (), [
+        in { x: 1.0 } => out { y: approx(0.3, 1e-6), rising: true },
+        in { x: 1.0 } => out { y: approx(0.51, 1e-6) },
+        in { x: 0.0, hold: true } => out { y: approx(0.51, 1e-6), rising: false },
+        in { x: 0.0 } => out { rising: false },
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "the trace diverged at instant 1")]
+fn divergence_is_reported() {
+    assert_trace!(Accumulator, Accumulator::Args { init: 0 }, [
+        in { input: 1 } => out { output: 1 },
+        in { input: 1 } => out { output: 3 },
+        in { input: 1 } => out { output: 3 },
+    ]);
+}
@@ -0,0 +1,188 @@
+// Runs `lrfrp/regulator.lrfrp` compiled by both backends on the same inputs and
+// compares the outputs, probes and failed `assert` items of every instant. It is skipped
+// without a C compiler, which is `cc` or `$CC`.
+
+include!(concat!(env!("OUT_DIR"), "/regulator.rs"));
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const DRIVER: &str = r#"
+#include <stdio.h>
+#include <string.h>
+
+#include "regulator.h"
+
+int main(void) {
+    Regulator_Args args = { .gain = 1.5f, .limit = 4 };
+    Regulator_FRP frp;
+    Regulator_init(&frp, &args);
+
+    char line[256];
+    while (fgets(line, sizeof(line), stdin)) {
+        if (strncmp(line, "reset", 5) == 0) {
+            Regulator_reset(&frp);
+            continue;
+        }
+        Regulator_In in;
+        int enable;
+        unsigned mask;
+        sscanf(line, "%f %f %d %u", &in.target, &in.measured, &enable, &mask);
+        in.enable = enable;
+        in.mask = (uint8_t)mask;
+        Regulator_step(&frp, &in);
+
+        const Regulator_Out *out = Regulator_sample(&frp);
+        const Regulator_Probe *probe = Regulator_probe(&frp);
+        printf("%.6f %d %d %u %d %d %.6f %d %llu\n", out->command, out->mode_code,
+               out->saturated, out->bits, out->trend, out->age, probe->error, probe->time_delayed,
+               (unsigned long long)Regulator_violations(&frp));
+    }
+    return 0;
+}
+"#;
+
+// a linear congruential generator, so that the inputs are the same on every run
+struct Lcg(u32);
+
+impl Lcg {
+    fn next(&mut self) -> u32 {
+        self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        self.0 >> 8
+    }
+
+    fn float(&mut self) -> f32 {
+        (self.next() % 2000) as f32 / 100.0 - 10.0
+    }
+}
+
+enum Step {
+    Run(Regulator::In),
+    Reset,
+}
+
+fn steps() -> Vec<Step> {
+    let mut lcg = Lcg(7);
+    (0..200)
+        .map(|i| {
+            if i == 120 {
+                return Step::Reset;
+            }
+            Step::Run(Regulator::In::new(
+                lcg.float(),
+                lcg.float(),
+                lcg.next() % 5 > 0,
+                lcg.next() as u8,
+            ))
+        })
+        .collect()
+}
+
+fn run_rust(steps: &[Step]) -> String {
+    let mut frp = Regulator::FRP::new(Regulator::Args {
+        gain: 1.5,
+        limit: 4,
+    });
+    let mut outputs = String::new();
+    for step in steps {
+        let input = match step {
+            Step::Run(input) => input,
+            Step::Reset => {
+                frp.reset();
+                continue;
+            }
+        };
+        frp.run(input);
+        let out = frp.sample().unwrap();
+        let probe = frp.probe().unwrap();
+        writeln!(
+            outputs,
+            "{:.6} {} {} {} {} {} {:.6} {} {}",
+            out.command,
+            out.mode_code,
+            out.saturated as i32,
+            out.bits,
+            out.trend,
+            out.age,
+            probe.error,
+            probe.time_delayed,
+            frp.violations().0
+        )
+        .unwrap();
+    }
+    outputs
+}
+
+fn run_c(cc: &str, steps: &[Step], dir: &Path) -> String {
+    let program = Path::new(env!("CARGO_MANIFEST_DIR")).join("lrfrp/regulator.lrfrp");
+    let c_source = lrfrpc::compile_c_file(&program, "regulator.h").unwrap();
+    fs::write(dir.join("regulator.h"), c_source.header).unwrap();
+    fs::write(dir.join("regulator.c"), c_source.source).unwrap();
+    fs::write(dir.join("driver.c"), DRIVER).unwrap();
+
+    let exe = dir.join("regulator");
+    let status = Command::new(cc)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-o"])
+        .arg(&exe)
+        .arg(dir.join("regulator.c"))
+        .arg(dir.join("driver.c"))
+        .arg("-lm")
+        .status()
+        .unwrap();
+    assert!(status.success(), "the generated C does not compile");
+
+    let mut inputs = String::new();
+    for step in steps {
+        match step {
+            Step::Run(input) => writeln!(
+                inputs,
+                "{} {} {} {}",
+                input.target, input.measured, input.enable as i32, input.mask
+            ),
+            Step::Reset => writeln!(inputs, "reset"),
+        }
+        .unwrap();
+    }
+
+    let mut child = Command::new(&exe)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(inputs.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn c_backend_agrees_with_rust_backend() {
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    if Command::new(&cc).arg("--version").output().is_err() {
+        eprintln!(
+            "skipped: no C compiler `{}`, set CC to test the C backend",
+            cc
+        );
+        return;
+    }
+
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_backend");
+    fs::create_dir_all(&dir).unwrap();
+
+    let steps = steps();
+    let rust = run_rust(&steps);
+    let c = run_c(&cc, &steps, &dir);
+    for (i, (r, c)) in rust.lines().zip(c.lines()).enumerate() {
+        assert_eq!(r, c, "the outputs differ at instant {}", i);
+    }
+    assert_eq!(rust.lines().count(), c.lines().count());
+}
@@ -0,0 +1,61 @@
+// Casts enums whose variants have no fields to their discriminants as Rust does, and
+// rejects casts of enums with fields.
+
+use lrfrp_macros::frp;
+
+frp! {
+    mod Level;
+
+    enum Mode {
+        Low,
+        Mid,
+        High,
+    }
+
+    In {
+        x: i32,
+    }
+
+    Out {
+        code: i32,
+    }
+
+    let mode = if x < 0 then Mode::Low else if x < 10 then Mode::Mid else Mode::High;
+    let code = mode as i32;
+}
+
+#[test]
+fn fieldless_enums_are_cast_to_integers() {
+    let mut level = Level::FRP::new();
+    let codes: Vec<i32> = [-1, 5, 20]
+        .iter()
+        .map(|&x| {
+            level.run(&Level::In::new(x));
+            level.sample().unwrap().code
+        })
+        .collect();
+    assert_eq!(codes, [0, 1, 2]);
+}
+
+#[test]
+fn enums_with_fields_are_not_cast() {
+    let source = "
+mod M;
+enum Reading { Idle, Sample(i32) }
+In { x: i32 }
+Out { y: i32 }
+let y = Reading::Sample(x) as i32;
+";
+    let diagnostics = lrfrpc::compile_str(source).unwrap_err();
+    let errors: Vec<_> = diagnostics
+        .into_iter()
+        .map(|diagnostic| (diagnostic.message, diagnostic.location))
+        .collect();
+    assert_eq!(
+        errors,
+        [(
+            "expected primitive type, found `Reading`".to_owned(),
+            Some((6, 18))
+        )]
+    );
+}
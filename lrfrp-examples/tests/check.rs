@@ -0,0 +1,162 @@
+// Proves the properties of a saturating counter for every input stream, and finds the
+// shortest input stream counting past the limit of a counter with an off-by-one bug.
+// Programs with 32-bit inputs are checked over a range of them.
+
+use lrfrp::check::{assert_check, check, check_inputs, CheckError};
+use lrfrp_macros::frp;
+
+frp! {
+    mod Counter;
+
+    enum Mode {
+        Idle,
+        Counting,
+        Full,
+    }
+
+    Args {
+        limit: i32,
+    }
+
+    In {
+        tick: bool,
+        clear: bool,
+    }
+
+    Out {
+        count: i32,
+        mode: Mode,
+    }
+
+    let count = if clear then 0 else if tick && count_delayed < limit then count_delayed + 1 else count_delayed;
+    let count_delayed: i32 <- delay 0 -< count;
+    let mode = if count == 0 then Mode::Idle else if count == limit then Mode::Full else Mode::Counting;
+
+    assert count >= 0;
+    property bounded = count <= limit;
+    property monotone = clear || count >= (0 -> pre count);
+    property full_at_limit = mode != Mode::Full || count == limit;
+}
+
+frp! {
+    mod OffByOne;
+
+    Args {
+        limit: i32,
+    }
+
+    In {
+        tick: bool,
+        clear: bool,
+    }
+
+    Out {
+        count: i32,
+    }
+
+    let count = if clear then 0 else if tick && count_delayed <= limit then count_delayed + 1 else count_delayed;
+    let count_delayed: i32 <- delay 0 -< count;
+
+    property bounded = count <= limit;
+}
+
+frp! {
+    mod Inverter;
+
+    In {
+        level: bool,
+    }
+
+    Out {
+        low: bool,
+    }
+
+    let low = !level;
+
+    assert low != level;
+}
+
+frp! {
+    mod Thermostat;
+
+    In {
+        temperature: i32,
+    }
+
+    Out {
+        heating: bool,
+    }
+
+    let heating = if temperature < 18 then True
+        else if temperature > 22 then False
+        else heating_delayed;
+    let heating_delayed: bool <- delay False -< heating;
+
+    assert !heating || temperature <= 22;
+}
+
+frp! {
+    mod Analog;
+
+    In {
+        level: f32,
+    }
+
+    Out {
+        high: bool,
+    }
+
+    let high = level > 0.5;
+
+    assert !high || level > 0.0;
+}
+
+#[test]
+fn counter_is_proven_for_every_instant() {
+    let proof = assert_check::<Counter::FRP>(Counter::Args { limit: 3 }, 20);
+    println!("{}", proof);
+    assert!(proof.complete);
+    assert_eq!(
+        <Counter::FRP as lrfrp::check::Check>::PROPERTIES,
+        &["count >= 0", "bounded", "monotone", "full_at_limit"]
+    );
+}
+
+#[test]
+fn off_by_one_counts_past_the_limit() {
+    let error = match check::<OffByOne::FRP>(OffByOne::Args { limit: 3 }, 20) {
+        Err(CheckError::Violated(counterexample)) => counterexample,
+        result => panic!("expected a counterexample, found {:?}", result.map(|_| ())),
+    };
+    println!("{}", error);
+    assert_eq!(error.properties, ["bounded"]);
+    assert_eq!(error.inputs.len(), 4);
+    assert!(error.inputs.iter().all(|input| input.tick && !input.clear));
+}
+
+#[test]
+fn states_differing_in_outputs_only_are_explored_once() {
+    let proof = assert_check::<Inverter::FRP>((), 20);
+    assert!(proof.complete);
+    assert_eq!(proof.states, 1);
+}
+
+#[test]
+fn analog_inputs_are_not_enumerated() {
+    assert!(matches!(
+        check::<Analog::FRP>((), 1),
+        Err(CheckError::Infinite)
+    ));
+}
+
+#[test]
+fn wide_inputs_are_checked_over_a_range() {
+    assert!(matches!(
+        check::<Thermostat::FRP>((), 1),
+        Err(CheckError::Infinite)
+    ));
+    let inputs = (-100..=100).map(Thermostat::In::new).collect();
+    let proof = check_inputs::<Thermostat::FRP>((), 10, inputs).unwrap();
+    assert!(proof.complete);
+    assert_eq!(proof.states, 2);
+}
@@ -0,0 +1,44 @@
+// Delays by several instants from a negative initial value, which is parenthesized so
+// that it is not read as a subtraction from the number of instants.
+
+use lrfrp_macros::frp;
+
+frp! {
+    mod Lag;
+
+    In { x: i32 }
+    Out { y: i32 }
+
+    let y = lagged;
+    let lagged: i32 <- delay 3 (-5) -< x;
+}
+
+#[test]
+fn negative_initial_value_is_parenthesized() {
+    let mut lag = Lag::FRP::new();
+    let outputs: Vec<i32> = (1..=5)
+        .map(|x| {
+            lag.run(&Lag::In::new(x));
+            lag.sample().unwrap().y
+        })
+        .collect();
+    assert_eq!(outputs, [-5, -5, -5, 1, 2]);
+}
+
+#[test]
+fn minus_after_the_number_of_instants_is_ambiguous() {
+    let source = "
+        mod Lag;
+        In { x: i32 }
+        Out { y: i32 }
+        let y = lagged;
+        let lagged: i32 <- delay 3 -5 -< x;
+    ";
+    let diagnostics = lrfrpc::compile_str(source).unwrap_err();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(
+        diagnostics[0].message.starts_with("ambiguous `-`"),
+        "{}",
+        diagnostics[0].message
+    );
+}
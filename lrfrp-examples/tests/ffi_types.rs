@@ -0,0 +1,56 @@
+// `#[ffi]` modules are laid out for C, so `frp!` accepts the types of their `In` and
+// `Out` exactly when `lrfrpc --emit header` can declare them.
+
+use lrfrp_macros::frp;
+
+frp! {
+    #[ffi]
+    mod Gate;
+
+    In {
+        open: bool,
+        value: i32,
+    }
+
+    Out {
+        passed: i32,
+    }
+
+    let passed = if open then value else 0;
+}
+
+#[test]
+fn ffi_module_without_args_or_probe_runs() {
+    let mut frp = Gate::FRP::new();
+    unsafe {
+        Gate::Gate_run(&mut frp, &Gate::In::new(true, 7));
+        assert_eq!((*Gate::Gate_sample(&frp)).passed, 7);
+    }
+}
+
+fn messages(source: &str) -> Vec<String> {
+    let diagnostics = lrfrpc::compile_str(source).unwrap_err();
+    let header_diagnostics = lrfrpc::compile_header_str(source).unwrap_err();
+    let messages: Vec<String> = diagnostics.into_iter().map(|d| d.message).collect();
+    let header_messages: Vec<String> = header_diagnostics.into_iter().map(|d| d.message).collect();
+    assert_eq!(messages, header_messages);
+    messages
+}
+
+#[test]
+fn events_and_tuples_are_rejected_as_by_the_header() {
+    let source = "
+        #[ffi]
+        mod Edge;
+        In { value: Event<i32> }
+        Out { pair: (i32, bool) }
+        let pair = (hold(0, value), True);
+    ";
+    assert_eq!(
+        messages(source),
+        [
+            "`Event<i32>` is not supported by the C backend",
+            "tuples are not supported by the C backend",
+        ]
+    );
+}
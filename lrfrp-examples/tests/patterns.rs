@@ -0,0 +1,92 @@
+// Matches events with `Some` and `None` and destructures structs in `match` arms, and
+// reports patterns which do not fit the matched type.
+
+use lrfrp_macros::frp;
+
+frp! {
+    mod Window;
+
+    struct Range {
+        lo: i32,
+        hi: i32,
+    }
+
+    In {
+        sample: Event<i32>,
+    }
+
+    Out {
+        level: i32,
+        inside: bool,
+    }
+
+    let range = Range { lo: 0, hi: 10 };
+    let level = match sample {
+        Some(v) if v >= 0 => v,
+        Some(_) => 0,
+        None => -1,
+    };
+    let inside = match range {
+        Range { lo, .. } if level < lo => False,
+        Range { hi: upper, lo: _ } => level <= upper,
+    };
+}
+
+#[test]
+fn events_and_structs_are_matched() {
+    let mut window = Window::FRP::new();
+    let outputs: Vec<(i32, bool)> = [Some(3), None, Some(12), Some(-4)]
+        .iter()
+        .map(|&sample| {
+            window.run(&Window::In::new(sample));
+            let out = window.sample().unwrap();
+            (out.level, out.inside)
+        })
+        .collect();
+    assert_eq!(outputs, [(3, true), (-1, false), (12, false), (0, true)]);
+}
+
+fn errors(body: &str) -> Vec<(String, Option<(usize, usize)>)> {
+    let source = format!(
+        "mod M;\nstruct Range {{ lo: i32, hi: i32 }}\nIn {{ x: Event<i32> }}\nOut {{ y: i32 }}\n{}",
+        body
+    );
+    lrfrpc::compile_str(&source)
+        .unwrap_err()
+        .into_iter()
+        .map(|diagnostic| (diagnostic.message, diagnostic.location))
+        .collect()
+}
+
+#[test]
+fn missing_none_is_not_exhaustive() {
+    assert_eq!(
+        errors("let y = match x { Some(v) => v };"),
+        [(
+            "non-exhaustive patterns: `None` not covered".to_owned(),
+            Some((5, 9))
+        )]
+    );
+}
+
+#[test]
+fn unknown_variants_and_fields_are_reported() {
+    assert_eq!(
+        errors("let y = match x { Ok(v) => v, _ => 0 };"),
+        [(
+            "cannot find variant `Ok`, expected `Some`, `None` or a variant qualified by its enum"
+                .to_owned(),
+            Some((5, 19))
+        )]
+    );
+    assert_eq!(
+        errors("let r = Range { lo: 0, hi: 1 };\nlet y = match r { Range { lo, mid } => lo };"),
+        [
+            ("no field `mid` on type `Range`".to_owned(), Some((6, 31))),
+            (
+                "missing field `hi` in pattern of `Range`".to_owned(),
+                Some((6, 19))
+            ),
+        ]
+    );
+}
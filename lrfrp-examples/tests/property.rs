@@ -0,0 +1,67 @@
+// Checks that a fan controller never toggles its fan two instants in a row, over
+// random temperature streams, and that a controller without the hold is caught with a
+// minimal counterexample.
+
+use lrfrp::testing::{assert_property, check_property, Config};
+use lrfrp_macros::frp;
+
+frp! {
+    mod Fan;
+
+    In {
+        tmp: f32,
+        forced: Event<bool>,
+    }
+
+    Out {
+        fan: bool,
+    }
+
+    // a fan toggled at the last instant is held for one more
+    let want = hold(tmp > 25.0, forced);
+    let fan = if fan_delayed != fan_delayed2 then fan_delayed else want;
+    let fan_delayed: bool <- delay False -< fan;
+    let fan_delayed2: bool <- delay False -< fan_delayed;
+}
+
+frp! {
+    mod UnheldFan;
+
+    In {
+        tmp: f32,
+    }
+
+    Out {
+        fan: bool,
+    }
+
+    let fan = tmp > 25.0;
+}
+
+// the fan toggles at this instant and at the one before it
+fn toggles_twice(fan: bool, history: &[bool]) -> bool {
+    match history {
+        [.., a, b] => *a != *b && *b != fan,
+        _ => false,
+    }
+}
+
+#[test]
+fn fan_never_toggles_twice_in_a_row() {
+    assert_property::<Fan::FRP, _>(&Config::default(), (), |_, output, history| {
+        let fans: Vec<bool> = history.iter().map(|(_, output)| output.fan).collect();
+        !toggles_twice(output.fan, &fans)
+    });
+}
+
+#[test]
+fn unheld_fan_is_shrunk_to_three_instants() {
+    let result =
+        check_property::<UnheldFan::FRP, _>(&Config::default(), (), |_, output, history| {
+            let fans: Vec<bool> = history.iter().map(|(_, output)| output.fan).collect();
+            !toggles_twice(output.fan, &fans)
+        });
+    let counterexample = result.unwrap_err();
+    println!("{}", counterexample);
+    assert_eq!(counterexample.trace.len(), 3);
+}
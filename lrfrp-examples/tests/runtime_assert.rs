@@ -0,0 +1,99 @@
+// Runs a tank whose level must stay within its bounds under each policy of failing
+// `assert` items: recording them in `FRP`, calling a hook and panicking.
+
+use lrfrp_macros::frp;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+frp! {
+    #[assert(record)]
+    mod RecordedTank;
+
+    In { inflow: i32, outflow: i32 }
+    Out { level: i32 }
+
+    let level = level_delayed + inflow - outflow;
+    let level_delayed: i32 <- delay 0 -< level;
+
+    assert level >= 0;
+    assert level <= 100;
+    // only checked by `lrfrp::check`
+    property small_steps = inflow <= 10;
+}
+
+frp! {
+    #[assert(hook = on_violation)]
+    mod HookedTank;
+
+    In { inflow: i32, outflow: i32 }
+    Out { level: i32 }
+
+    let level = level_delayed + inflow - outflow;
+    let level_delayed: i32 <- delay 0 -< level;
+
+    assert level >= 0;
+    assert level <= 100;
+}
+
+frp! {
+    mod PanickingTank;
+
+    In { inflow: i32, outflow: i32 }
+    Out { level: i32 }
+
+    let level = level_delayed + inflow - outflow;
+    let level_delayed: i32 <- delay 0 -< level;
+
+    assert level >= 0;
+}
+
+static HOOKED: AtomicU64 = AtomicU64::new(0);
+
+fn on_violation(violations: HookedTank::Violations) {
+    HOOKED.fetch_or(violations.0, Ordering::SeqCst);
+}
+
+#[test]
+fn violations_are_recorded_until_reset() {
+    let mut tank = RecordedTank::FRP::new();
+    tank.run(&RecordedTank::In::new(50, 0));
+    tank.run(&RecordedTank::In::new(20, 0));
+    assert!(tank.violations().is_empty());
+
+    tank.run(&RecordedTank::In::new(40, 0));
+    tank.run(&RecordedTank::In::new(0, 50));
+    assert_eq!(tank.sample().unwrap().level, 60);
+    assert_eq!(
+        tank.violations().names().collect::<Vec<_>>(),
+        ["level <= 100"]
+    );
+
+    tank.run(&RecordedTank::In::new(0, 70));
+    assert_eq!(tank.violations().0, 0b11);
+    tank.clear_violations();
+    assert!(tank.violations().is_empty());
+
+    tank.run(&RecordedTank::In::new(0, 1));
+    let state = tank.snapshot();
+    tank.reset();
+    assert!(tank.violations().is_empty());
+    tank.restore(state).unwrap();
+    assert_eq!(tank.violations().0, 0b01);
+}
+
+#[test]
+fn hook_is_called_with_the_violations_of_the_instant() {
+    let mut tank = HookedTank::FRP::new();
+    tank.run(&HookedTank::In::new(0, 0));
+    assert_eq!(HOOKED.load(Ordering::SeqCst), 0);
+    tank.run(&HookedTank::In::new(0, 5));
+    assert_eq!(HOOKED.load(Ordering::SeqCst), 0b01);
+}
+
+#[test]
+#[should_panic(expected = "assertion `level >= 0` failed in `PanickingTank`")]
+fn failed_assert_panics_by_default() {
+    let mut tank = PanickingTank::FRP::new();
+    tank.run(&PanickingTank::In::new(1, 0));
+    tank.run(&PanickingTank::In::new(0, 2));
+}
@@ -0,0 +1,85 @@
+// Destructures tuples and variants with `..` standing for the elements in between, and
+// reports tuple patterns of the wrong length.
+
+use lrfrp_macros::frp;
+
+frp! {
+    mod Spread;
+
+    enum Reading {
+        Idle,
+        Sample(i32, i32, i32),
+    }
+
+    In {
+        x: i32,
+    }
+
+    Out {
+        first: i32,
+        last: i32,
+        middle: i32,
+    }
+
+    fn triple(x: i32) -> (i32, i32, i32) = (x, x * 2, x * 3);
+
+    let (first, ..) = triple(x);
+    let last = {
+        let (.., z) = triple(x);
+        z
+    };
+    let reading = if x > 0 then Reading::Sample(x, x + 1, x + 2) else Reading::Idle;
+    let middle = match reading {
+        Reading::Sample(_, y, ..) => y,
+        Reading::Idle => 0,
+    };
+}
+
+#[test]
+fn rest_patterns_skip_elements() {
+    let mut spread = Spread::FRP::new();
+    spread.run(&Spread::In::new(2));
+    let out = spread.sample().unwrap();
+    assert_eq!((out.first, out.last, out.middle), (2, 6, 3));
+}
+
+fn errors(body: &str) -> Vec<(String, Option<(usize, usize)>)> {
+    let source = format!(
+        "mod M;\nIn {{ x: i32 }}\nOut {{ y: i32 }}\nfn triple(x: i32) -> (i32, i32, i32) = (x, x, x);\n{}",
+        body
+    );
+    lrfrpc::compile_str(&source)
+        .unwrap_err()
+        .into_iter()
+        .map(|diagnostic| (diagnostic.message, diagnostic.location))
+        .collect()
+}
+
+#[test]
+fn tuple_patterns_of_the_wrong_length_are_reported() {
+    assert_eq!(
+        errors("let y = { let (a, .., b, c, d) = triple(x); a };"),
+        [(
+            "mismatched types: expected `(i32, i32, i32)`, found `(_, .., _, _, _)`".to_owned(),
+            Some((5, 15))
+        )]
+    );
+    assert_eq!(
+        errors("let y = { let (a, b) = triple(x); a };"),
+        [(
+            "mismatched types: expected `(i32, i32, i32)`, found `(_, _)`".to_owned(),
+            Some((5, 15))
+        )]
+    );
+}
+
+#[test]
+fn elements_after_rest_are_not_signals() {
+    assert_eq!(
+        errors("let (y, .., z) = triple(x);"),
+        [(
+            "elements after `..` are not allowed in signal definitions".to_owned(),
+            Some((5, 13))
+        )]
+    );
+}
@@ -0,0 +1,75 @@
+// Runs a program over 128-bit integers through the features generating code for the
+// types of its signals.
+
+use lrfrp::replay::{self, CsvRecorder};
+use lrfrp::trace::{CsvWriter, VcdWriter};
+use lrfrp_macros::frp;
+
+use std::io::Cursor;
+
+frp! {
+    mod Wide;
+
+    In {
+        x: i128,
+        n: u128,
+    }
+
+    Out {
+        sum: i128,
+        total: u128,
+    }
+
+    let sum = x + sum_delayed;
+    let total = n + total_delayed;
+    let sum_delayed: i128 <- delay 0 -< sum;
+    let total_delayed: u128 <- delay 0 -< total;
+}
+
+fn inputs() -> Vec<Wide::In> {
+    vec![
+        Wide::In::new(i128::MIN / 2, u128::MAX / 2),
+        Wide::In::new(-1, 1),
+    ]
+}
+
+#[test]
+fn wide_ints_are_traced() {
+    let mut frp = Wide::FRP::new();
+    let mut csv = CsvWriter::new(Vec::new());
+    let mut vcd = VcdWriter::new(Vec::new());
+    for input in inputs().iter() {
+        frp.run_traced(input, &mut csv);
+    }
+    frp.reset();
+    for input in inputs().iter() {
+        frp.run_traced(input, &mut vcd);
+    }
+
+    let csv = String::from_utf8(csv.finish().unwrap()).unwrap();
+    let last = csv.lines().last().unwrap();
+    assert!(last.contains(&(i128::MIN / 2 - 1).to_string()), "{}", csv);
+    assert!(last.contains(&(u128::MAX / 2 + 1).to_string()), "{}", csv);
+
+    let vcd = String::from_utf8(vcd.finish().unwrap()).unwrap();
+    assert!(vcd.contains("$var wire 128"), "{}", vcd);
+    assert!(vcd.contains(&format!("b1{} ", "0".repeat(127))), "{}", vcd);
+}
+
+#[test]
+fn wide_ints_are_replayed() {
+    let mut recorder = CsvRecorder::new(Vec::new()).unwrap();
+    for input in inputs().iter() {
+        recorder.record(input).unwrap();
+    }
+    let log = recorder.finish().unwrap();
+
+    let mut outputs = Vec::new();
+    let instants = replay::replay::<Wide::FRP>(Cursor::new(&log), &mut outputs, None).unwrap();
+    assert_eq!(instants, 2);
+    let outputs = String::from_utf8(outputs).unwrap();
+    assert_eq!(
+        outputs.lines().last().unwrap(),
+        format!("{},{}", i128::MIN / 2 - 1, u128::MAX / 2 + 1)
+    );
+}
@@ -0,0 +1,469 @@
+//! Bounded model checking of the `assert` and `property` items of programs.
+//!
+//! With the `check` feature of `lrfrp-macros`, the generated `FRP` implements [`Check`],
+//! and `In` and the declared types implement [`Finite`]. [`check`] runs the program on
+//! every input at every state reachable within a number of instants, so that the
+//! properties are proven up to that bound or violated by a shortest input trace. States
+//! are told apart by their cells, see [`StateKey`]. States reached twice are explored
+//! once, and the proof holds for any bound once no new state is reached.
+//!
+//! Only inputs of finite types are enumerated: `bool`, 8-bit integers, enums and
+//! structs, tuples, arrays and events of them, with at most [`MAX_VALUES`] values.
+//! Programs with wider integers or floats among their inputs are checked over given
+//! inputs by [`check_inputs`], such as the ones of a range. Arguments are given.
+//!
+//! ```ignore
+//! match lrfrp::check::check::<Counter::FRP>(Counter::Args { limit: 3 }, 10) {
+//!     Ok(proof) => println!("{}", proof),
+//!     Err(error) => println!("{}", error),
+//! }
+//! ```
+
+use crate::FrpProgram;
+
+use std::collections::HashSet;
+use std::error;
+use std::fmt::{self, Debug};
+
+// for the generated code, which may be in `no_std` crates
+pub use std::vec::Vec;
+
+/// The most values of a type which are enumerated.
+pub const MAX_VALUES: usize = 1 << 16;
+
+/// The types whose values can be enumerated, which the generated `In`, structs and enums
+/// implement with the `check` feature.
+pub trait Finite: Sized {
+    /// Every value of the type, or `None` if there are more than [`MAX_VALUES`].
+    fn values() -> Option<Vec<Self>>;
+}
+
+impl Finite for () {
+    fn values() -> Option<Vec<Self>> {
+        Some(std::vec![()])
+    }
+}
+
+impl Finite for bool {
+    fn values() -> Option<Vec<Self>> {
+        Some(std::vec![false, true])
+    }
+}
+
+macro_rules! impl_finite_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Finite for $ty {
+                fn values() -> Option<Vec<Self>> {
+                    Some((<$ty>::MIN..=<$ty>::MAX).collect())
+                }
+            }
+        )*
+    };
+}
+
+impl_finite_for_int!(i8, u8);
+
+macro_rules! impl_infinite {
+    ($($ty:ty),*) => {
+        $(
+            impl Finite for $ty {
+                fn values() -> Option<Vec<Self>> {
+                    None
+                }
+            }
+        )*
+    };
+}
+
+impl_infinite!(i16, i32, i64, i128, isize, u16, u32, u64, u128, usize, f32, f64);
+
+impl<T: Finite> Finite for Option<T> {
+    fn values() -> Option<Vec<Self>> {
+        let values = T::values()?;
+        if values.len() == MAX_VALUES {
+            return None;
+        }
+        Some(
+            core::iter::once(None)
+                .chain(values.into_iter().map(Some))
+                .collect(),
+        )
+    }
+}
+
+// the fields of structs and variants are enumerated as nested pairs `(a, (b, ()))`
+impl<A: Finite + Clone, B: Finite + Clone> Finite for (A, B) {
+    fn values() -> Option<Vec<Self>> {
+        let a = A::values()?;
+        let b = B::values()?;
+        if a.len().saturating_mul(b.len()) > MAX_VALUES {
+            return None;
+        }
+        Some(
+            a.iter()
+                .flat_map(|a| b.iter().map(move |b| (a.clone(), b.clone())))
+                .collect(),
+        )
+    }
+}
+
+macro_rules! impl_finite_for_tuple {
+    ($($ty:ident $value:ident),*) => {
+        impl<$($ty: Finite + Clone),*> Finite for ($($ty,)*) {
+            fn values() -> Option<Vec<Self>> {
+                let values = <impl_finite_for_tuple!(@nested $($ty)*)>::values()?;
+                Some(
+                    values
+                        .into_iter()
+                        .map(|impl_finite_for_tuple!(@nested $($value)*)| ($($value,)*))
+                        .collect(),
+                )
+            }
+        }
+    };
+    (@nested) => { () };
+    (@nested $head:ident $($tail:ident)*) => {
+        ($head, impl_finite_for_tuple!(@nested $($tail)*))
+    };
+}
+
+impl_finite_for_tuple!(A a);
+impl_finite_for_tuple!(A a, B b, C c);
+impl_finite_for_tuple!(A a, B b, C c, D d);
+impl_finite_for_tuple!(A a, B b, C c, D d, E e);
+impl_finite_for_tuple!(A a, B b, C c, D d, E e, F f);
+impl_finite_for_tuple!(A a, B b, C c, D d, E e, F f, G g);
+impl_finite_for_tuple!(A a, B b, C c, D d, E e, F f, G g, H h);
+
+impl<T: Finite + Clone, const N: usize> Finite for [T; N] {
+    fn values() -> Option<Vec<Self>> {
+        let elements = T::values()?;
+        let mut len: usize = 1;
+        for _ in 0..N {
+            len = len.saturating_mul(elements.len());
+        }
+        if len > MAX_VALUES {
+            return None;
+        }
+        // counts in base `elements.len()`, the first element changing the fastest
+        Some(
+            (0..len)
+                .map(|mut i| {
+                    core::array::from_fn(|_| {
+                        let element = elements[i % elements.len()].clone();
+                        i /= elements.len();
+                        element
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+/// The types of the cells of programs, whose values are written as bytes identifying
+/// them, so that [`check`] finds the states reached before by a hash set. The generated
+/// `FRP`, `Cell`, structs and enums implement it with the `check` feature.
+pub trait StateKey {
+    /// Appends the bytes of the value to `key`. Floats are written by their bits.
+    fn write_key(&self, key: &mut Vec<u8>);
+}
+
+impl StateKey for () {
+    fn write_key(&self, _: &mut Vec<u8>) {}
+}
+
+impl StateKey for bool {
+    fn write_key(&self, key: &mut Vec<u8>) {
+        key.push(*self as u8);
+    }
+}
+
+macro_rules! impl_state_key_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl StateKey for $ty {
+                fn write_key(&self, key: &mut Vec<u8>) {
+                    key.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_state_key_for_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_state_key_for_float {
+    ($($ty:ty),*) => {
+        $(
+            impl StateKey for $ty {
+                fn write_key(&self, key: &mut Vec<u8>) {
+                    key.extend_from_slice(&self.to_bits().to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_state_key_for_float!(f32, f64);
+
+impl<T: StateKey> StateKey for Option<T> {
+    fn write_key(&self, key: &mut Vec<u8>) {
+        match self {
+            Some(value) => {
+                key.push(1);
+                value.write_key(key);
+            }
+            None => key.push(0),
+        }
+    }
+}
+
+impl<T: StateKey, const N: usize> StateKey for [T; N] {
+    fn write_key(&self, key: &mut Vec<u8>) {
+        for value in self.iter() {
+            value.write_key(key);
+        }
+    }
+}
+
+macro_rules! impl_state_key_for_tuple {
+    ($($ty:ident $index:tt),*) => {
+        impl<$($ty: StateKey),*> StateKey for ($($ty,)*) {
+            fn write_key(&self, key: &mut Vec<u8>) {
+                $(self.$index.write_key(key);)*
+            }
+        }
+    };
+}
+
+impl_state_key_for_tuple!(A 0);
+impl_state_key_for_tuple!(A 0, B 1);
+impl_state_key_for_tuple!(A 0, B 1, C 2);
+impl_state_key_for_tuple!(A 0, B 1, C 2, D 3);
+impl_state_key_for_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_state_key_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_state_key_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_state_key_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// The programs with `assert` and `property` items, which the generated `FRP`
+/// implements with the `check` feature. The key of the state of a program is the one
+/// of its cells, since its outputs are calculated anew at every instant.
+pub trait Check: FrpProgram + StateKey {
+    /// The names of the properties in the order of their declarations. An `assert` is
+    /// named after its condition.
+    const PROPERTIES: &'static [&'static str];
+
+    /// Runs as `run` does, returning the properties violated at the instant as bits
+    /// by their positions in [`PROPERTIES`](Check::PROPERTIES).
+    fn run_checked(&mut self, input: &Self::In) -> u64;
+}
+
+/// The properties held at every instant up to `depth`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proof {
+    /// The number of instants checked.
+    pub depth: usize,
+    /// The number of distinct states reached.
+    pub states: usize,
+    /// Whether every reachable state was reached, so that the properties hold at any
+    /// instant.
+    pub complete: bool,
+}
+
+impl fmt::Display for Proof {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.complete {
+            write!(
+                f,
+                "the properties hold at every instant, {} states are reachable",
+                self.states
+            )
+        } else {
+            write!(
+                f,
+                "the properties hold up to instant {}, over {} states",
+                self.depth, self.states
+            )
+        }
+    }
+}
+
+/// A shortest stream of inputs violating properties at its last instant.
+pub struct Counterexample<I> {
+    /// The names of the violated properties.
+    pub properties: Vec<&'static str>,
+    pub inputs: Vec<I>,
+}
+
+impl<I: Debug> fmt::Display for Counterexample<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "violated at instant {}:", self.inputs.len() - 1)?;
+        for property in self.properties.iter() {
+            write!(f, " `{}`", property)?;
+        }
+        writeln!(f)?;
+        for (instant, input) in self.inputs.iter().enumerate() {
+            writeln!(f, "{:>5}: {:?}", instant, input)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I: Debug> Debug for Counterexample<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The error of checking a program.
+pub enum CheckError<I> {
+    /// `In` has too many values to be enumerated.
+    Infinite,
+    Violated(Counterexample<I>),
+}
+
+impl<I: Debug> fmt::Display for CheckError<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckError::Infinite => write!(
+                f,
+                "the inputs have more than {} values or types which cannot be enumerated",
+                MAX_VALUES
+            ),
+            CheckError::Violated(counterexample) => fmt::Display::fmt(counterexample, f),
+        }
+    }
+}
+
+impl<I: Debug> Debug for CheckError<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<I: Debug> error::Error for CheckError<I> {}
+
+// a reached state with the last input and the node of the state before it
+struct Node<S> {
+    state: S,
+    input: usize,
+    parent: Option<usize>,
+}
+
+/// Checks the properties of the program created from `args` at every instant up to
+/// `depth`, breadth first so that counterexamples are shortest.
+pub fn check<P>(args: P::Args, depth: usize) -> Result<Proof, CheckError<P::In>>
+where
+    P: Check,
+    P::In: Finite + Clone,
+    P::State: Clone,
+{
+    let inputs = P::In::values().ok_or(CheckError::Infinite)?;
+    check_inputs::<P>(args, depth, inputs)
+}
+
+/// Checks as [`check`] does, with only `inputs` given at every instant, so that the
+/// proof holds for the streams of those inputs.
+///
+/// ```ignore
+/// let inputs = (-100..=100).map(Thermostat::In::new).collect();
+/// lrfrp::check::check_inputs::<Thermostat::FRP>(args, 10, inputs)
+/// ```
+pub fn check_inputs<P>(
+    args: P::Args,
+    depth: usize,
+    inputs: Vec<P::In>,
+) -> Result<Proof, CheckError<P::In>>
+where
+    P: Check,
+    P::In: Clone,
+    P::State: Clone,
+{
+    let mut program = P::new(args);
+    let mut nodes = std::vec![Node {
+        state: program.snapshot(),
+        input: 0,
+        parent: None,
+    }];
+    let mut visited = HashSet::new();
+    visited.insert(state_key(&program));
+    let mut frontier = 0..1;
+    let mut instant = 0;
+    while instant < depth && !frontier.is_empty() {
+        let start = nodes.len();
+        for parent in frontier {
+            for (i, input) in inputs.iter().enumerate() {
+                // the snapshots come from the same program
+                let _ = program.restore(nodes[parent].state.clone());
+                let violated = program.run_checked(input);
+                if violated != 0 {
+                    return Err(CheckError::Violated(counterexample::<P>(
+                        &nodes, &inputs, parent, i, violated,
+                    )));
+                }
+                if visited.insert(state_key(&program)) {
+                    nodes.push(Node {
+                        state: program.snapshot(),
+                        input: i,
+                        parent: Some(parent),
+                    });
+                }
+            }
+        }
+        frontier = start..nodes.len();
+        instant += 1;
+    }
+    Ok(Proof {
+        depth: instant,
+        states: nodes.len(),
+        complete: frontier.is_empty(),
+    })
+}
+
+/// Panics with the counterexample or the error if the properties fail, see [`check`].
+#[track_caller]
+pub fn assert_check<P>(args: P::Args, depth: usize) -> Proof
+where
+    P: Check,
+    P::In: Finite + Clone + Debug,
+    P::State: Clone,
+{
+    match check::<P>(args, depth) {
+        Ok(proof) => proof,
+        Err(error) => panic!("{}", std::string::ToString::to_string(&error).trim_end()),
+    }
+}
+
+fn state_key<P: Check>(program: &P) -> Vec<u8> {
+    let mut key = Vec::new();
+    program.write_key(&mut key);
+    key
+}
+
+fn counterexample<P: Check>(
+    nodes: &[Node<P::State>],
+    inputs: &[P::In],
+    mut node: usize,
+    input: usize,
+    violated: u64,
+) -> Counterexample<P::In>
+where
+    P::In: Clone,
+{
+    let mut trace = std::vec![inputs[input].clone()];
+    while let Some(parent) = nodes[node].parent {
+        trace.push(inputs[nodes[node].input].clone());
+        node = parent;
+    }
+    trace.reverse();
+    Counterexample {
+        properties: P::PROPERTIES
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| violated & (1 << i) != 0)
+            .map(|(_, name)| *name)
+            .collect(),
+        inputs: trace,
+    }
+}
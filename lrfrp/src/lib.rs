@@ -1,3 +1,77 @@
+#![no_std]
+
 extern crate lrfrp_macros;
+// the writers of traces, the replay of logs, property testing and model checking
+#[cfg(any(
+    feature = "trace",
+    feature = "replay",
+    feature = "testing",
+    feature = "check"
+))]
+extern crate std;
+
+#[cfg(feature = "check")]
+pub mod check;
+#[cfg(feature = "replay")]
+pub mod replay;
+pub mod testing;
+pub mod trace;
 
 pub use lrfrp_macros::frp;
+
+use core::fmt;
+
+/// The interface of the `FRP` struct generated for every `frp!` module, so that
+/// drivers and test harnesses can be written once for any program.
+pub trait FrpProgram {
+    /// The `In` struct of the module.
+    type In;
+    /// The `Out` struct of the module.
+    type Out;
+    /// The `Args` struct of the module, or `()` if it has no `Args`.
+    type Args;
+    /// The `State` struct of the module.
+    type State;
+
+    /// Creates a program whose cells hold their initial values.
+    fn new(args: Self::Args) -> Self;
+
+    /// Calculates the outputs of the next instant from `input`.
+    fn run(&mut self, input: &Self::In);
+
+    /// Returns the outputs of the last instant, or `None` before the first `run`.
+    fn sample(&self) -> Option<&Self::Out>;
+
+    /// Brings the program back to the state right after `new`.
+    fn reset(&mut self);
+
+    /// Brings the program back to the state right after `new(args)`, keeping `args`
+    /// for later resets.
+    fn reset_with(&mut self, args: Self::Args);
+
+    /// Returns the whole state of the program, to be restored later.
+    fn snapshot(&self) -> Self::State;
+
+    /// Puts the program back into a state returned by `snapshot`, unless it was taken
+    /// from a program whose state is laid out differently.
+    fn restore(&mut self, state: Self::State) -> Result<(), VersionMismatch>;
+}
+
+/// The error of restoring a state whose schema version differs from the program's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionMismatch {
+    /// The `SCHEMA_VERSION` of the program.
+    pub expected: u64,
+    /// The version recorded in the state.
+    pub found: u64,
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the state has schema version {:#018x}, but the program expects {:#018x}",
+            self.found, self.expected
+        )
+    }
+}
@@ -0,0 +1,395 @@
+//! Replaying logs of inputs recorded as CSV through a program.
+//!
+//! With the `replay` feature of `lrfrp-macros`, the generated `In`, `Out` and `Args`
+//! implement [`Record`], whose columns are named after their fields. Fields of structs,
+//! tuples and arrays are spread over columns named `field.member`, enums are written by
+//! their variant names with the fields of variants in columns named `field.Variant.N`,
+//! and absent events are empty. Values are never quoted, so commas cannot appear in
+//! them.
+
+use crate::FrpProgram;
+
+use std::borrow::ToOwned;
+use std::error;
+use std::fmt;
+use std::format;
+use std::io::{self, BufRead, Write};
+use std::string::ToString;
+
+// for the generated code, which may be in `no_std` crates
+pub use std::string::String;
+pub use std::vec::Vec;
+
+/// The error of replaying a log.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The log has no header.
+    Empty,
+    /// A column of the header is not a field of `In` or `Args`.
+    UnknownColumn(String),
+    /// A field of `In` or `Args` has no column.
+    MissingColumn(String),
+    /// A row has a different number of fields than the header.
+    FieldCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A field cannot be read as the type of its column.
+    InvalidValue {
+        line: usize,
+        column: String,
+        value: String,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "{}", e),
+            ReplayError::Empty => write!(f, "the log has no header"),
+            ReplayError::UnknownColumn(column) => {
+                write!(f, "column `{}` is not a field of `In` or `Args`", column)
+            }
+            ReplayError::MissingColumn(column) => write!(f, "no column `{}` in the log", column),
+            ReplayError::FieldCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} fields, found {}",
+                line, expected, found
+            ),
+            ReplayError::InvalidValue {
+                line,
+                column,
+                value,
+            } => write!(
+                f,
+                "line {}: invalid value `{}` in column `{}`",
+                line, value, column
+            ),
+        }
+    }
+}
+
+impl error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(e: io::Error) -> Self {
+        ReplayError::Io(e)
+    }
+}
+
+/// A row of a log with the header naming its fields.
+pub struct Row<'a> {
+    header: &'a [String],
+    fields: Vec<&'a str>,
+    line: usize,
+}
+
+impl<'a> Row<'a> {
+    /// Returns the field of `column`.
+    pub fn get(&self, column: &str) -> Result<&'a str, ReplayError> {
+        self.header
+            .iter()
+            .position(|name| name == column)
+            .map(|i| self.fields[i])
+            .ok_or_else(|| ReplayError::MissingColumn(column.to_owned()))
+    }
+
+    /// The error of the field of `column` being invalid.
+    pub fn invalid(&self, column: &str) -> ReplayError {
+        ReplayError::InvalidValue {
+            line: self.line,
+            column: column.to_owned(),
+            value: self.get(column).unwrap_or_default().to_owned(),
+        }
+    }
+}
+
+/// The name of the column of `member` of the value in column `name`.
+pub fn column_name(name: &str, member: &str) -> String {
+    if name.is_empty() {
+        member.to_owned()
+    } else {
+        format!("{}.{}", name, member)
+    }
+}
+
+/// Appends an empty field for every column of a value of `T`, as for absent events.
+pub fn write_absent<T: Columns>(fields: &mut Vec<String>) {
+    let mut names = Vec::new();
+    T::names("", &mut names);
+    fields.extend(names.iter().map(|_| String::new()));
+}
+
+/// The types whose values are read from and written to the columns of a log.
+pub trait Columns: Sized {
+    /// Appends the names of the columns of a value named `name`.
+    fn names(name: &str, names: &mut Vec<String>);
+
+    /// Reads the value named `name` from `row`.
+    fn read(name: &str, row: &Row) -> Result<Self, ReplayError>;
+
+    /// Appends the fields of the value, in the order of `names`.
+    fn write(&self, fields: &mut Vec<String>);
+}
+
+/// The rows of a log, implemented by the generated `In`, `Out` and `Args`.
+pub trait Record: Columns {
+    /// The names of the columns, which are the names of the fields.
+    fn columns() -> Vec<String> {
+        let mut names = Vec::new();
+        Self::names("", &mut names);
+        names
+    }
+
+    /// Reads the fields from `row`, whose other columns are ignored.
+    fn from_record(row: &Row) -> Result<Self, ReplayError> {
+        Self::read("", row)
+    }
+
+    /// Returns the fields in the order of `columns`.
+    fn to_record(&self) -> Vec<String> {
+        let mut fields = Vec::new();
+        self.write(&mut fields);
+        fields
+    }
+}
+
+// the `Args` of programs without `Args`
+impl Columns for () {
+    fn names(_: &str, _: &mut Vec<String>) {}
+
+    fn read(_: &str, _: &Row) -> Result<Self, ReplayError> {
+        Ok(())
+    }
+
+    fn write(&self, _: &mut Vec<String>) {}
+}
+
+impl Record for () {}
+
+impl Columns for bool {
+    fn names(name: &str, names: &mut Vec<String>) {
+        names.push(name.to_owned());
+    }
+
+    fn read(name: &str, row: &Row) -> Result<Self, ReplayError> {
+        match row.get(name)? {
+            "1" | "true" | "True" => Ok(true),
+            "0" | "false" | "False" => Ok(false),
+            _ => Err(row.invalid(name)),
+        }
+    }
+
+    fn write(&self, fields: &mut Vec<String>) {
+        fields.push(if *self { "1" } else { "0" }.to_owned());
+    }
+}
+
+macro_rules! impl_columns_for_primitive {
+    ($($ty:ty),*) => {
+        $(
+            impl Columns for $ty {
+                fn names(name: &str, names: &mut Vec<String>) {
+                    names.push(name.to_owned());
+                }
+
+                fn read(name: &str, row: &Row) -> Result<Self, ReplayError> {
+                    row.get(name)?.parse().map_err(|_| row.invalid(name))
+                }
+
+                fn write(&self, fields: &mut Vec<String>) {
+                    fields.push(self.to_string());
+                }
+            }
+        )*
+    };
+}
+
+impl_columns_for_primitive!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+// absent when all of its columns are empty
+impl<T: Columns> Columns for Option<T> {
+    fn names(name: &str, names: &mut Vec<String>) {
+        T::names(name, names);
+    }
+
+    fn read(name: &str, row: &Row) -> Result<Self, ReplayError> {
+        let mut names = Vec::new();
+        T::names(name, &mut names);
+        for column in names.iter() {
+            if !row.get(column)?.is_empty() {
+                return T::read(name, row).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn write(&self, fields: &mut Vec<String>) {
+        match self {
+            Some(value) => value.write(fields),
+            None => write_absent::<T>(fields),
+        }
+    }
+}
+
+impl<T: Columns + Copy + Default, const N: usize> Columns for [T; N] {
+    fn names(name: &str, names: &mut Vec<String>) {
+        for i in 0..N {
+            T::names(&column_name(name, &i.to_string()), names);
+        }
+    }
+
+    fn read(name: &str, row: &Row) -> Result<Self, ReplayError> {
+        let mut array = [T::default(); N];
+        for (i, value) in array.iter_mut().enumerate() {
+            *value = T::read(&column_name(name, &i.to_string()), row)?;
+        }
+        Ok(array)
+    }
+
+    fn write(&self, fields: &mut Vec<String>) {
+        for value in self.iter() {
+            value.write(fields);
+        }
+    }
+}
+
+macro_rules! impl_columns_for_tuple {
+    ($($ty:ident $index:tt),*) => {
+        impl<$($ty: Columns),*> Columns for ($($ty,)*) {
+            fn names(name: &str, names: &mut Vec<String>) {
+                $($ty::names(&column_name(name, stringify!($index)), names);)*
+            }
+
+            fn read(name: &str, row: &Row) -> Result<Self, ReplayError> {
+                Ok(($($ty::read(&column_name(name, stringify!($index)), row)?,)*))
+            }
+
+            fn write(&self, fields: &mut Vec<String>) {
+                $(self.$index.write(fields);)*
+            }
+        }
+    };
+}
+
+impl_columns_for_tuple!(A 0);
+impl_columns_for_tuple!(A 0, B 1);
+impl_columns_for_tuple!(A 0, B 1, C 2);
+impl_columns_for_tuple!(A 0, B 1, C 2, D 3);
+impl_columns_for_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_columns_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_columns_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_columns_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// Writes values as the rows of a CSV log, after a header of their columns.
+///
+/// Logs of `In` written in the field can be replayed by [`replay`].
+pub struct CsvRecorder<W: Write, T: Record> {
+    out: W,
+    _record: core::marker::PhantomData<fn(&T)>,
+}
+
+impl<W: Write, T: Record> CsvRecorder<W, T> {
+    /// Writes the header to `out`.
+    pub fn new(mut out: W) -> io::Result<Self> {
+        writeln!(out, "{}", T::columns().join(","))?;
+        Ok(CsvRecorder {
+            out,
+            _record: core::marker::PhantomData,
+        })
+    }
+
+    /// Writes `value` as a row.
+    pub fn record(&mut self, value: &T) -> io::Result<()> {
+        writeln!(self.out, "{}", value.to_record().join(","))
+    }
+
+    /// Flushes the output and returns it.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Runs a program over the rows of a CSV log and writes its outputs as CSV, returning
+/// the number of instants.
+///
+/// The columns of the log must be fields of `In` or `Args`. The program is created
+/// with `args`, or with the `Args` read from the first row if `args` is `None`. Empty
+/// lines are skipped.
+pub fn replay<P>(
+    input: impl BufRead,
+    output: impl Write,
+    args: Option<P::Args>,
+) -> Result<usize, ReplayError>
+where
+    P: FrpProgram,
+    P::In: Record,
+    P::Out: Record,
+    P::Args: Record,
+{
+    let mut lines = input.lines();
+    let header: Vec<String> = match lines.next() {
+        Some(line) => line?.split(',').map(|s| s.trim().to_owned()).collect(),
+        None => return Err(ReplayError::Empty),
+    };
+    let known: Vec<String> = P::In::columns()
+        .into_iter()
+        .chain(P::Args::columns())
+        .collect();
+    if let Some(column) = header.iter().find(|column| !known.contains(column)) {
+        return Err(ReplayError::UnknownColumn(column.clone()));
+    }
+
+    let mut recorder = CsvRecorder::<_, P::Out>::new(output)?;
+    let mut args = args;
+    let mut program: Option<P> = None;
+    let mut instants = 0;
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        // the header is line 1
+        let line = i + 2;
+        if fields.len() != header.len() {
+            return Err(ReplayError::FieldCount {
+                line,
+                expected: header.len(),
+                found: fields.len(),
+            });
+        }
+        let row = Row {
+            header: &header,
+            fields,
+            line,
+        };
+
+        let program = match program {
+            Some(ref mut program) => program,
+            None => {
+                let args = match args.take() {
+                    Some(args) => args,
+                    None => P::Args::from_record(&row)?,
+                };
+                program.insert(P::new(args))
+            }
+        };
+        program.run(&P::In::from_record(&row)?);
+        if let Some(output) = program.sample() {
+            recorder.record(output)?;
+        }
+        instants += 1;
+    }
+    recorder.finish()?;
+    Ok(instants)
+}
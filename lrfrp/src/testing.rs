@@ -0,0 +1,173 @@
+//! Checking programs against expected traces and properties in tests.
+//!
+//! [`assert_trace!`] runs a program instant by instant and panics at the first instant
+//! whose outputs differ from the expected ones, showing the input and both outputs.
+//! The messages need `Debug` of `In` and `Out`, which the `impl-debug` feature of
+//! `lrfrp-macros` derives.
+//!
+//! The `testing` feature adds `check_property` and `assert_property`, which check a
+//! predicate over random input streams and shrink the first failing stream. With the
+//! `testing` feature of `lrfrp-macros`, the generated `In` implements `Arbitrary`.
+//!
+//! ```ignore
+//! use lrfrp::assert_trace;
+//! use lrfrp::testing::approx;
+//!
+//! assert_trace!(Filter, Filter::Args { k: 0.5 }, [
+//!     in { x: 1.0 } => out { y: approx(0.5, 1e-6), high: false },
+//!     // the fields left out of `in` are their defaults and those of `out` unchecked
+//!     in { x: 4.0, reset: true } => out { high: true },
+//! ]);
+//! ```
+
+#[cfg(feature = "testing")]
+mod property;
+
+#[cfg(feature = "testing")]
+pub use self::property::{
+    assert_property, check_property, Arbitrary, Config, Counterexample, Rng, Vec,
+};
+pub use crate::assert_trace;
+
+use core::fmt::{self, Debug};
+
+/// The expectations of the values of output fields.
+///
+/// Every type compares exactly with itself; [`approx`] compares floats with a tolerance.
+pub trait Expectation<T> {
+    fn matches(&self, actual: &T) -> bool;
+}
+
+impl<T: PartialEq> Expectation<T> for T {
+    fn matches(&self, actual: &T) -> bool {
+        self == actual
+    }
+}
+
+/// A float expected within a tolerance, made by [`approx`].
+#[derive(Clone, Copy)]
+pub struct Approx<T> {
+    value: T,
+    tolerance: T,
+}
+
+/// Expects a float within `tolerance` of `value`.
+pub fn approx<T>(value: T, tolerance: T) -> Approx<T> {
+    Approx { value, tolerance }
+}
+
+impl<T: Debug> Debug for Approx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} ± {:?}", self.value, self.tolerance)
+    }
+}
+
+macro_rules! impl_expectation_for_approx {
+    ($($ty:ty),*) => {
+        $(
+            impl Expectation<$ty> for Approx<$ty> {
+                fn matches(&self, actual: &$ty) -> bool {
+                    (self.value - *actual).abs() <= self.tolerance
+                }
+            }
+        )*
+    };
+}
+
+impl_expectation_for_approx!(f32, f64);
+
+/// An expected output field, its value and whether it matched.
+pub type Field<'a> = (&'static str, &'a dyn Debug, bool);
+
+// `out { a: 1, b: 2 }`
+struct Expected<'a>(&'a [Field<'a>]);
+
+impl Debug for Expected<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "out {{ ")?;
+        for (i, (name, value, _)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {:?}", name, value)?;
+        }
+        write!(f, " }}")
+    }
+}
+
+// the names of the fields which did not match
+struct Mismatched<'a>(&'a [Field<'a>]);
+
+impl Debug for Mismatched<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names = self.0.iter().filter(|(_, _, matched)| !matched);
+        if let Some((name, _, _)) = names.next() {
+            write!(f, "{}", name)?;
+        }
+        for (name, _, _) in names {
+            write!(f, ", {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the output fields of an instant, called by [`assert_trace!`].
+#[track_caller]
+pub fn check(instant: usize, input: &dyn Debug, expected: &[Field], actual: &dyn Debug) {
+    if expected.iter().all(|(_, _, matched)| *matched) {
+        return;
+    }
+    panic!(
+        "the trace diverged at instant {}\n    \
+         input: {:?}\n \
+         expected: {:?}\n   \
+         actual: {:?}\n \
+         mismatch: {:?}",
+        instant,
+        input,
+        Expected(expected),
+        actual,
+        Mismatched(expected)
+    );
+}
+
+/// Runs a program from `args` over the inputs and checks its outputs at every instant.
+///
+/// Every step is `in { field: value, .. } => out { field: expectation, .. }`. The
+/// input fields left out are `Default::default()`, and the output fields left out are
+/// not checked. An expectation is a value of the field or an [`Expectation`] of it
+/// such as [`approx`]. Instants are counted from 0. Programs without `Args` take `()`.
+#[macro_export]
+macro_rules! assert_trace {
+    ($program:ident, $args:expr, [$(
+        in { $($input:ident : $value:expr),* $(,)? } =>
+        out { $($output:ident : $expected:expr),* $(,)? }
+    ),* $(,)?]) => {{
+        let mut frp = <$program::FRP as $crate::FrpProgram>::new($args);
+        let mut instant: usize = 0;
+        $(
+            #[allow(clippy::needless_update)]
+            let input = $program::In {
+                $($input: $value,)*
+                ..::core::default::Default::default()
+            };
+            $crate::FrpProgram::run(&mut frp, &input);
+            let actual = $crate::FrpProgram::sample(&frp).unwrap();
+            // the expectations are bound to the names of their fields
+            match ($($expected,)*) {
+                ($($output,)*) => $crate::testing::check(
+                    instant,
+                    &input,
+                    &[$((
+                        stringify!($output),
+                        &$output as &dyn ::core::fmt::Debug,
+                        $crate::testing::Expectation::matches(&$output, &actual.$output),
+                    )),*],
+                    actual,
+                ),
+            }
+            instant += 1;
+        )*
+        let _ = instant;
+    }};
+}
@@ -0,0 +1,370 @@
+use crate::FrpProgram;
+
+use std::fmt::{self, Debug};
+use std::string::ToString;
+
+// for the generated code, which may be in `no_std` crates
+pub use std::vec::Vec;
+
+/// A pseudorandom generator of the inputs of properties, xorshift64*.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // the state must not be 0
+        Rng(seed ^ 0x9e37_79b9_7f4a_7c15 | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// A float in `lo..hi`.
+    pub fn float(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 * (hi - lo)
+    }
+}
+
+/// The types of the inputs of properties, which the generated `In`, structs and enums
+/// implement with the `testing` feature.
+pub trait Arbitrary: Sized {
+    fn arbitrary(rng: &mut Rng) -> Self;
+
+    /// Values simpler than this one, the simplest first.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+impl Arbitrary for bool {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        rng.below(2) == 1
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            std::vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// small values are as likely as any others, since they are at the edges of most
+// conditions
+macro_rules! impl_arbitrary_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Arbitrary for $ty {
+                #[allow(unused_comparisons)]
+                fn arbitrary(rng: &mut Rng) -> Self {
+                    if rng.below(2) == 0 {
+                        let small = rng.below(21) as i64 - 10;
+                        if small < 0 && <$ty>::MIN == 0 {
+                            (-small) as $ty
+                        } else {
+                            small as $ty
+                        }
+                    } else if <$ty>::BITS > 64 {
+                        ((u128::from(rng.next_u64()) << 64) | u128::from(rng.next_u64())) as $ty
+                    } else {
+                        rng.next_u64() as $ty
+                    }
+                }
+
+                #[allow(unused_comparisons)]
+                fn shrink(&self) -> Vec<Self> {
+                    let x = *self;
+                    let mut shrunk = Vec::new();
+                    if x != 0 {
+                        shrunk.push(0);
+                    }
+                    for y in [x / 2, if x < 0 { x + 1 } else { x.wrapping_sub(1) }] {
+                        if y != x && !shrunk.contains(&y) {
+                            shrunk.push(y);
+                        }
+                    }
+                    shrunk
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_for_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+// finite values only
+macro_rules! impl_arbitrary_for_float {
+    ($($ty:ty),*) => {
+        $(
+            impl Arbitrary for $ty {
+                fn arbitrary(rng: &mut Rng) -> Self {
+                    match rng.below(4) {
+                        0 => 0.0,
+                        1 => rng.float(-10.0, 10.0) as $ty,
+                        _ => rng.float(-1000.0, 1000.0) as $ty,
+                    }
+                }
+
+                fn shrink(&self) -> Vec<Self> {
+                    let x = *self;
+                    let mut shrunk = Vec::new();
+                    for y in [0.0, x.trunc(), (x / 2.0).trunc()] {
+                        if y != x && !shrunk.contains(&y) {
+                            shrunk.push(y);
+                        }
+                    }
+                    shrunk
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_for_float!(f32, f64);
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        if rng.below(2) == 0 {
+            None
+        } else {
+            Some(T::arbitrary(rng))
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            Some(value) => core::iter::once(None)
+                .chain(value.shrink().into_iter().map(Some))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<T: Arbitrary + Clone, const N: usize> Arbitrary for [T; N] {
+    fn arbitrary(rng: &mut Rng) -> Self {
+        core::array::from_fn(|_| T::arbitrary(rng))
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut shrunk = Vec::new();
+        for (i, value) in self.iter().enumerate() {
+            for value in value.shrink() {
+                let mut array = self.clone();
+                array[i] = value;
+                shrunk.push(array);
+            }
+        }
+        shrunk
+    }
+}
+
+macro_rules! impl_arbitrary_for_tuple {
+    ($($ty:ident $index:tt),*) => {
+        impl<$($ty: Arbitrary + Clone),*> Arbitrary for ($($ty,)*) {
+            fn arbitrary(rng: &mut Rng) -> Self {
+                ($($ty::arbitrary(rng),)*)
+            }
+
+            fn shrink(&self) -> Vec<Self> {
+                let mut shrunk = Vec::new();
+                $(
+                    for value in self.$index.shrink() {
+                        let mut tuple = self.clone();
+                        tuple.$index = value;
+                        shrunk.push(tuple);
+                    }
+                )*
+                shrunk
+            }
+        }
+    };
+}
+
+impl_arbitrary_for_tuple!(A 0);
+impl_arbitrary_for_tuple!(A 0, B 1);
+impl_arbitrary_for_tuple!(A 0, B 1, C 2);
+impl_arbitrary_for_tuple!(A 0, B 1, C 2, D 3);
+impl_arbitrary_for_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_arbitrary_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_arbitrary_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_arbitrary_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// How many input streams a property is checked over.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The number of input streams.
+    pub cases: usize,
+    /// The maximal number of instants of a stream.
+    pub max_len: usize,
+    /// The seed of the first stream, reported with counterexamples to rerun them.
+    pub seed: u64,
+    /// The maximal number of runs spent on shrinking a counterexample.
+    pub max_shrinks: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            cases: 256,
+            max_len: 64,
+            seed: 0,
+            max_shrinks: 4096,
+        }
+    }
+}
+
+/// A stream of inputs violating a property, with the outputs of every instant. The
+/// property fails at the last instant.
+pub struct Counterexample<I, O> {
+    /// The seed of the stream before shrinking.
+    pub seed: u64,
+    pub trace: Vec<(I, O)>,
+}
+
+impl<I: Debug, O: Debug> fmt::Display for Counterexample<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "the property fails at instant {} (seed {}):",
+            self.trace.len() - 1,
+            self.seed
+        )?;
+        for (instant, (input, output)) in self.trace.iter().enumerate() {
+            writeln!(f, "{:>5}: {:?} => {:?}", instant, input, output)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I: Debug, O: Debug> Debug for Counterexample<I, O> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+// the trace up to the first instant at which the property fails
+fn run<P, F>(args: &P::Args, inputs: &[P::In], property: &F) -> Option<Vec<(P::In, P::Out)>>
+where
+    P: FrpProgram,
+    P::In: Clone,
+    P::Out: Clone,
+    P::Args: Clone,
+    F: Fn(&P::In, &P::Out, &[(P::In, P::Out)]) -> bool,
+{
+    let mut program = P::new(args.clone());
+    let mut trace = Vec::with_capacity(inputs.len());
+    for input in inputs.iter() {
+        program.run(input);
+        let output = program.sample().expect("no outputs after `run`");
+        if !property(input, output, &trace) {
+            trace.push((input.clone(), output.clone()));
+            return Some(trace);
+        }
+        trace.push((input.clone(), output.clone()));
+    }
+    None
+}
+
+// greedily removes instants and simplifies inputs while the property keeps failing
+fn shrink<P, F>(
+    config: &Config,
+    args: &P::Args,
+    mut trace: Vec<(P::In, P::Out)>,
+    property: &F,
+) -> Vec<(P::In, P::Out)>
+where
+    P: FrpProgram,
+    P::In: Arbitrary + Clone,
+    P::Out: Clone,
+    P::Args: Clone,
+    F: Fn(&P::In, &P::Out, &[(P::In, P::Out)]) -> bool,
+{
+    let mut runs = 0;
+    'shrink: loop {
+        let inputs: Vec<P::In> = trace.iter().map(|(input, _)| input.clone()).collect();
+        let mut candidates: Vec<Vec<P::In>> = Vec::new();
+        // chunks of halving sizes, then single inputs
+        let mut size = inputs.len() / 2;
+        while size > 0 {
+            for start in (0..inputs.len()).step_by(size) {
+                let mut candidate = inputs.clone();
+                candidate.drain(start..(start + size).min(inputs.len()));
+                candidates.push(candidate);
+            }
+            size /= 2;
+        }
+        for (i, input) in inputs.iter().enumerate() {
+            for input in input.shrink() {
+                let mut candidate = inputs.clone();
+                candidate[i] = input;
+                candidates.push(candidate);
+            }
+        }
+
+        for candidate in candidates.iter() {
+            if runs == config.max_shrinks {
+                break 'shrink;
+            }
+            runs += 1;
+            if let Some(shrunk) = run::<P, F>(args, candidate, property) {
+                trace = shrunk;
+                continue 'shrink;
+            }
+        }
+        break;
+    }
+    trace
+}
+
+/// Checks `property` at every instant of random input streams, returning the
+/// shrunk stream of the first failure.
+///
+/// The property takes the input and output of an instant and the inputs and outputs
+/// of the instants before it. Every stream runs on a program created from `args`.
+pub fn check_property<P, F>(
+    config: &Config,
+    args: P::Args,
+    property: F,
+) -> Result<(), Counterexample<P::In, P::Out>>
+where
+    P: FrpProgram,
+    P::In: Arbitrary + Clone,
+    P::Out: Clone,
+    P::Args: Clone,
+    F: Fn(&P::In, &P::Out, &[(P::In, P::Out)]) -> bool,
+{
+    for case in 0..config.cases as u64 {
+        let seed = config.seed.wrapping_add(case);
+        let mut rng = Rng::new(seed);
+        let len = 1 + rng.below(config.max_len.max(1) as u64) as usize;
+        let inputs: Vec<P::In> = (0..len).map(|_| P::In::arbitrary(&mut rng)).collect();
+        if let Some(trace) = run::<P, F>(&args, &inputs, &property) {
+            let trace = shrink::<P, F>(config, &args, trace, &property);
+            return Err(Counterexample { seed, trace });
+        }
+    }
+    Ok(())
+}
+
+/// Panics with the shrunk counterexample if `property` fails, see [`check_property`].
+#[track_caller]
+pub fn assert_property<P, F>(config: &Config, args: P::Args, property: F)
+where
+    P: FrpProgram,
+    P::In: Arbitrary + Clone + Debug,
+    P::Out: Clone + Debug,
+    P::Args: Clone,
+    F: Fn(&P::In, &P::Out, &[(P::In, P::Out)]) -> bool,
+{
+    if let Err(counterexample) = check_property::<P, F>(config, args, property) {
+        panic!("{}", counterexample.to_string().trim_end());
+    }
+}
@@ -0,0 +1,213 @@
+//! Recording the values of every signal per instant.
+//!
+//! With the `trace` feature of `lrfrp-macros`, the generated `FRP` has
+//! `run_traced(&mut self, input, tracer)`, which runs the program as `run` does and
+//! passes the inputs, local signals, outputs and cells of the instant to a [`Tracer`].
+//! Instances of other programs are traced through the signals they define.
+//!
+//! The `trace` feature of this crate adds [`VcdWriter`] and [`CsvWriter`], which need
+//! `std`.
+
+#[cfg(feature = "trace")]
+mod csv;
+#[cfg(feature = "trace")]
+mod recorder;
+#[cfg(feature = "trace")]
+mod vcd;
+
+#[cfg(feature = "trace")]
+pub use self::csv::CsvWriter;
+#[cfg(feature = "trace")]
+pub use self::vcd::VcdWriter;
+
+/// A value of a signal at an instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    /// A signed integer of `bits` bits.
+    Int {
+        value: i128,
+        bits: u32,
+    },
+    /// An unsigned integer of `bits` bits.
+    UInt {
+        value: u128,
+        bits: u32,
+    },
+    Float(f64),
+    /// A variant of an enum, by its position in the declaration.
+    Enum {
+        index: u32,
+        name: &'static str,
+    },
+}
+
+/// Receives the values of an instant from `run_traced`.
+///
+/// Every instant passes the same signals in the same order, so that writers can lay
+/// out the signals at the first instant.
+pub trait Tracer {
+    /// Starts an instant.
+    fn begin(&mut self);
+
+    /// Enters a program, struct, array or tuple named `name`. The values up to the
+    /// matching `exit` are its fields.
+    fn enter(&mut self, name: &str);
+
+    /// Leaves the innermost scope.
+    fn exit(&mut self);
+
+    /// Records the value of `name`.
+    fn value(&mut self, name: &str, value: Value);
+
+    /// Records that the event `name` has no value at this instant. `kind` is a value
+    /// of its type.
+    fn absent(&mut self, name: &str, kind: Value);
+
+    /// Ends the instant.
+    fn end(&mut self);
+}
+
+/// The types whose values are passed to a [`Tracer`]. The generated structs and enums
+/// implement it with the `trace` feature.
+pub trait Trace {
+    /// Passes the value to `tracer` as `name`.
+    fn trace(&self, name: &str, tracer: &mut dyn Tracer);
+
+    /// Passes an absent value of this type to `tracer` as `name`, for events.
+    fn trace_absent(name: &str, tracer: &mut dyn Tracer)
+    where
+        Self: Sized;
+}
+
+impl Trace for bool {
+    fn trace(&self, name: &str, tracer: &mut dyn Tracer) {
+        tracer.value(name, Value::Bool(*self));
+    }
+
+    fn trace_absent(name: &str, tracer: &mut dyn Tracer) {
+        tracer.absent(name, Value::Bool(false));
+    }
+}
+
+macro_rules! impl_trace_for_int {
+    ($variant:ident, $repr:ty, $($ty:ty),*) => {
+        $(
+            impl Trace for $ty {
+                fn trace(&self, name: &str, tracer: &mut dyn Tracer) {
+                    tracer.value(name, Value::$variant {
+                        value: *self as $repr,
+                        bits: <$ty>::BITS,
+                    });
+                }
+
+                fn trace_absent(name: &str, tracer: &mut dyn Tracer) {
+                    tracer.absent(name, Value::$variant {
+                        value: 0,
+                        bits: <$ty>::BITS,
+                    });
+                }
+            }
+        )*
+    };
+}
+
+impl_trace_for_int!(Int, i128, i8, i16, i32, i64, i128, isize);
+impl_trace_for_int!(UInt, u128, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_trace_for_float {
+    ($($ty:ty),*) => {
+        $(
+            impl Trace for $ty {
+                fn trace(&self, name: &str, tracer: &mut dyn Tracer) {
+                    tracer.value(name, Value::Float(f64::from(*self)));
+                }
+
+                fn trace_absent(name: &str, tracer: &mut dyn Tracer) {
+                    tracer.absent(name, Value::Float(0.0));
+                }
+            }
+        )*
+    };
+}
+
+impl_trace_for_float!(f32, f64);
+
+impl<T: Trace> Trace for Option<T> {
+    fn trace(&self, name: &str, tracer: &mut dyn Tracer) {
+        match self {
+            Some(value) => value.trace(name, tracer),
+            None => T::trace_absent(name, tracer),
+        }
+    }
+
+    fn trace_absent(name: &str, tracer: &mut dyn Tracer) {
+        T::trace_absent(name, tracer);
+    }
+}
+
+// the names of elements are their indices
+struct Index([u8; 20], usize);
+
+impl Index {
+    fn new(mut i: usize) -> Self {
+        let mut index = Index([0; 20], 20);
+        loop {
+            index.1 -= 1;
+            index.0[index.1] = b'0' + (i % 10) as u8;
+            i /= 10;
+            if i == 0 {
+                return index;
+            }
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.0[self.1..]).unwrap()
+    }
+}
+
+impl<T: Trace, const N: usize> Trace for [T; N] {
+    fn trace(&self, name: &str, tracer: &mut dyn Tracer) {
+        tracer.enter(name);
+        for (i, value) in self.iter().enumerate() {
+            value.trace(Index::new(i).as_str(), tracer);
+        }
+        tracer.exit();
+    }
+
+    fn trace_absent(name: &str, tracer: &mut dyn Tracer) {
+        tracer.enter(name);
+        for i in 0..N {
+            T::trace_absent(Index::new(i).as_str(), tracer);
+        }
+        tracer.exit();
+    }
+}
+
+macro_rules! impl_trace_for_tuple {
+    ($($ty:ident $index:tt),*) => {
+        impl<$($ty: Trace),*> Trace for ($($ty,)*) {
+            fn trace(&self, name: &str, tracer: &mut dyn Tracer) {
+                tracer.enter(name);
+                $(self.$index.trace(stringify!($index), tracer);)*
+                tracer.exit();
+            }
+
+            fn trace_absent(name: &str, tracer: &mut dyn Tracer) {
+                tracer.enter(name);
+                $($ty::trace_absent(stringify!($index), tracer);)*
+                tracer.exit();
+            }
+        }
+    };
+}
+
+impl_trace_for_tuple!(A 0);
+impl_trace_for_tuple!(A 0, B 1);
+impl_trace_for_tuple!(A 0, B 1, C 2);
+impl_trace_for_tuple!(A 0, B 1, C 2, D 3);
+impl_trace_for_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_trace_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_trace_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_trace_for_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
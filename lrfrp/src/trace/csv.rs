@@ -0,0 +1,88 @@
+use super::recorder::Recorder;
+use super::{Tracer, Value};
+
+use std::io::{self, Write};
+use std::{write, writeln};
+
+/// Writes the traced signals as CSV, one row per instant after a header of the signal
+/// names. Enums are written by their variant names and absent events as empty fields.
+///
+/// Write errors stop the output and are returned by `finish`.
+pub struct CsvWriter<W: Write> {
+    out: W,
+    recorder: Recorder,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(out: W) -> Self {
+        CsvWriter {
+            out,
+            recorder: Recorder::default(),
+            error: None,
+        }
+    }
+
+    /// Flushes the output and returns it, or the first error of writing it.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn write_instant(&mut self, first: bool) -> io::Result<()> {
+        if first {
+            write!(self.out, "instant")?;
+            for (name, _) in self.recorder.signals() {
+                write!(self.out, ",{}", name)?;
+            }
+            writeln!(self.out)?;
+        }
+        write!(self.out, "{}", self.recorder.instant)?;
+        for value in self.recorder.values.iter() {
+            match value {
+                Some(Value::Bool(b)) => write!(self.out, ",{}", *b as u8)?,
+                Some(Value::Int { value, .. }) => write!(self.out, ",{}", value)?,
+                Some(Value::UInt { value, .. }) => write!(self.out, ",{}", value)?,
+                Some(Value::Float(f)) => write!(self.out, ",{}", f)?,
+                Some(Value::Enum { name, .. }) => write!(self.out, ",{}", name)?,
+                None => write!(self.out, ",")?,
+            }
+        }
+        writeln!(self.out)
+    }
+}
+
+impl<W: Write> Tracer for CsvWriter<W> {
+    fn begin(&mut self) {
+        self.recorder.begin();
+    }
+
+    fn enter(&mut self, name: &str) {
+        self.recorder.enter(name);
+    }
+
+    fn exit(&mut self) {
+        self.recorder.exit();
+    }
+
+    fn value(&mut self, name: &str, value: Value) {
+        self.recorder.value(name, Some(value), value);
+    }
+
+    fn absent(&mut self, name: &str, kind: Value) {
+        self.recorder.value(name, None, kind);
+    }
+
+    fn end(&mut self) {
+        let first = self.recorder.end();
+        if self.error.is_none() {
+            if let Err(e) = self.write_instant(first) {
+                self.error = Some(e);
+            }
+        }
+        self.recorder.instant += 1;
+    }
+}
@@ -0,0 +1,79 @@
+use super::Value;
+
+use std::borrow::ToOwned;
+use std::string::String;
+use std::vec::Vec;
+
+pub enum Layout {
+    Enter(String),
+    Exit,
+    // the name and a value of the type of a signal
+    Signal(String, Value),
+}
+
+// The signals are laid out at the first instant, and the values of the later instants
+// are matched with them by their order.
+#[derive(Default)]
+pub struct Recorder {
+    pub layout: Vec<Layout>,
+    // `None` for absent events
+    pub values: Vec<Option<Value>>,
+    pub instant: u64,
+    laid_out: bool,
+}
+
+impl Recorder {
+    pub fn begin(&mut self) {
+        self.values.clear();
+    }
+
+    pub fn enter(&mut self, name: &str) {
+        if !self.laid_out {
+            self.layout.push(Layout::Enter(name.to_owned()));
+        }
+    }
+
+    pub fn exit(&mut self) {
+        if !self.laid_out {
+            self.layout.push(Layout::Exit);
+        }
+    }
+
+    pub fn value(&mut self, name: &str, value: Option<Value>, kind: Value) {
+        if !self.laid_out {
+            self.layout.push(Layout::Signal(name.to_owned(), kind));
+        }
+        self.values.push(value);
+    }
+
+    // returns whether the instant was the first one
+    pub fn end(&mut self) -> bool {
+        let first = !self.laid_out;
+        self.laid_out = true;
+        first
+    }
+
+    // the signals with their names joined by `.`
+    pub fn signals(&self) -> Vec<(String, Value)> {
+        let mut scopes: Vec<&str> = Vec::new();
+        let mut signals = Vec::new();
+        for layout in self.layout.iter() {
+            match layout {
+                Layout::Enter(name) => scopes.push(name),
+                Layout::Exit => {
+                    scopes.pop();
+                }
+                Layout::Signal(name, kind) => {
+                    let mut path = String::new();
+                    for scope in scopes.iter() {
+                        path += scope;
+                        path += ".";
+                    }
+                    path += name;
+                    signals.push((path, *kind));
+                }
+            }
+        }
+        signals
+    }
+}
@@ -0,0 +1,181 @@
+use super::recorder::{Layout, Recorder};
+use super::{Tracer, Value};
+
+use core::fmt::Write as _;
+use std::borrow::ToOwned;
+use std::io::{self, Write};
+use std::string::String;
+use std::vec::Vec;
+use std::{format, write, writeln};
+
+/// Writes the traced signals as a Value Change Dump, which waveform viewers such as
+/// GTKWave read. Every instant is one unit of the timescale, and absent events are `x`.
+///
+/// Write errors stop the output and are returned by `finish`.
+pub struct VcdWriter<W: Write> {
+    out: W,
+    timescale: String,
+    recorder: Recorder,
+    // the last values written, to write only changes
+    last: Vec<String>,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> VcdWriter<W> {
+    /// Creates a writer whose instants are `1 ns` apart.
+    pub fn new(out: W) -> Self {
+        VcdWriter::with_timescale(out, "1 ns")
+    }
+
+    /// Creates a writer whose instants are `timescale` apart, e.g. `"10 ms"`.
+    pub fn with_timescale(out: W, timescale: &str) -> Self {
+        VcdWriter {
+            out,
+            timescale: timescale.to_owned(),
+            recorder: Recorder::default(),
+            last: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Flushes the output and returns it, or the first error of writing it.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        writeln!(self.out, "$version lrfrp $end")?;
+        writeln!(self.out, "$timescale {} $end", self.timescale)?;
+        let mut index = 0;
+        for layout in self.recorder.layout.iter() {
+            match layout {
+                Layout::Enter(name) => writeln!(self.out, "$scope module {} $end", name)?,
+                Layout::Exit => writeln!(self.out, "$upscope $end")?,
+                Layout::Signal(name, kind) => {
+                    let (ty, width) = match kind {
+                        Value::Bool(_) => ("wire", 1),
+                        Value::Int { bits, .. } | Value::UInt { bits, .. } => ("wire", *bits),
+                        Value::Float(_) => ("real", 64),
+                        Value::Enum { .. } => ("wire", 32),
+                    };
+                    writeln!(
+                        self.out,
+                        "$var {} {} {} {} $end",
+                        ty,
+                        width,
+                        identifier(index),
+                        name
+                    )?;
+                    index += 1;
+                }
+            }
+        }
+        writeln!(self.out, "$enddefinitions $end")
+    }
+
+    fn write_instant(&mut self, first: bool) -> io::Result<()> {
+        if first {
+            self.write_header()?;
+        }
+        writeln!(self.out, "#{}", self.recorder.instant)?;
+        if first {
+            writeln!(self.out, "$dumpvars")?;
+        }
+        let signals = self
+            .recorder
+            .layout
+            .iter()
+            .filter_map(|layout| match layout {
+                Layout::Signal(_, kind) => Some(kind),
+                _ => None,
+            });
+        for (index, (kind, value)) in signals.zip(self.recorder.values.iter()).enumerate() {
+            let code = format!("{}{}", dump(*kind, *value), identifier(index));
+            if self.last.len() <= index {
+                self.last.push(String::new());
+            }
+            if self.last[index] != code {
+                writeln!(self.out, "{}", code)?;
+                self.last[index] = code;
+            }
+        }
+        if first {
+            writeln!(self.out, "$end")?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Tracer for VcdWriter<W> {
+    fn begin(&mut self) {
+        self.recorder.begin();
+    }
+
+    fn enter(&mut self, name: &str) {
+        self.recorder.enter(name);
+    }
+
+    fn exit(&mut self) {
+        self.recorder.exit();
+    }
+
+    fn value(&mut self, name: &str, value: Value) {
+        self.recorder.value(name, Some(value), value);
+    }
+
+    fn absent(&mut self, name: &str, kind: Value) {
+        self.recorder.value(name, None, kind);
+    }
+
+    fn end(&mut self) {
+        let first = self.recorder.end();
+        if self.error.is_none() {
+            if let Err(e) = self.write_instant(first) {
+                self.error = Some(e);
+            }
+        }
+        self.recorder.instant += 1;
+    }
+}
+
+// identifiers are numbers in base 94 written with the printable ASCII characters
+fn identifier(mut index: usize) -> String {
+    let mut identifier = String::new();
+    loop {
+        identifier.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            return identifier;
+        }
+    }
+}
+
+// the value followed by a space for vectors and reals, to which the identifier is added
+fn dump(kind: Value, value: Option<Value>) -> String {
+    let mut code = String::new();
+    let _ = match (kind, value) {
+        (Value::Bool(_), Some(Value::Bool(b))) => write!(code, "{}", b as u8),
+        (Value::Bool(_), _) => write!(code, "x"),
+        (Value::Float(_), Some(Value::Float(f))) => write!(code, "r{} ", f),
+        (Value::Float(_), _) => write!(code, "rnan "),
+        (Value::Int { bits, .. }, Some(Value::Int { value, .. })) => {
+            write!(code, "b{:b} ", value as u128 & mask(bits))
+        }
+        (Value::UInt { .. }, Some(Value::UInt { value, .. })) => write!(code, "b{:b} ", value),
+        (Value::Enum { .. }, Some(Value::Enum { index, .. })) => write!(code, "b{:b} ", index),
+        _ => write!(code, "bx "),
+    };
+    code
+}
+
+fn mask(bits: u32) -> u128 {
+    if bits >= 128 {
+        !0
+    } else {
+        (1 << bits) - 1
+    }
+}
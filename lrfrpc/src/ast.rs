@@ -0,0 +1,1139 @@
+use syn::braced;
+use syn::parenthesized;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::{Pair, Punctuated};
+use syn::token::{Brace, Comma, Paren};
+use syn::{Ident, Result, Token};
+
+use quote::{quote, ToTokens};
+
+use proc_macro2::TokenStream;
+
+use std::borrow::Borrow;
+
+use crate::codegen::derive::{self, Shape};
+
+pub mod custom_keywords;
+pub mod custom_punctuations;
+pub mod expressions;
+pub mod literals;
+pub mod path;
+pub mod patterns;
+pub mod statements;
+pub mod types;
+
+#[derive(Debug)]
+pub struct Ast {
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug)]
+pub enum Item {
+    Mod(ItemMod),
+    In(ItemIn),
+    Out(ItemOut),
+    Args(ItemArgs),
+    Probe(ItemProbe),
+    Assert(ItemAssert),
+    Property(ItemProperty),
+    FrpStmt(ItemFrpStmt),
+    Declaration(ItemDeclaration),
+}
+
+impl Parse for Item {
+    fn parse(input: ParseStream) -> Result<Self> {
+        use Item::*;
+
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![mod]) || lookahead.peek(Token![#]) {
+            Ok(input.parse().map(Mod)?)
+        } else if lookahead.peek(custom_keywords::In) {
+            Ok(input.parse().map(In)?)
+        } else if lookahead.peek(custom_keywords::Out) {
+            Ok(input.parse().map(Out)?)
+        } else if lookahead.peek(custom_keywords::Args) {
+            Ok(input.parse().map(Args)?)
+        } else if lookahead.peek(custom_keywords::Probe) {
+            Ok(input.parse().map(Probe)?)
+        } else if lookahead.peek(custom_keywords::assert) {
+            Ok(input.parse().map(Assert)?)
+        } else if lookahead.peek(custom_keywords::property) {
+            Ok(input.parse().map(Property)?)
+        } else if lookahead.peek(Token![fn])
+            || lookahead.peek(Token![struct])
+            || lookahead.peek(Token![enum])
+        {
+            Ok(input.parse().map(Declaration)?)
+        } else if lookahead.peek(Token![let]) {
+            Ok(input.parse().map(FrpStmt)?)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+// Function declaration
+#[derive(Debug)]
+pub struct ItemFn {
+    pub fn_token: Token![fn],
+    pub ident: Ident,
+    pub paren_token: Paren,
+    pub inputs: Punctuated<FnArg, Token![,]>,
+    pub right_arrow_token: Token![->],
+    pub output: Box<types::Type>,
+    pub eq_token: Token![=],
+    pub expr: Box<expressions::Expr>,
+    pub semi_token: Token![;],
+}
+
+impl Parse for ItemFn {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(ItemFn {
+            fn_token: input.parse()?,
+            ident: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            inputs: content.parse_terminated(FnArg::parse)?,
+            right_arrow_token: input.parse()?,
+            output: input.parse()?,
+            eq_token: input.parse()?,
+            expr: input.parse()?,
+            semi_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ItemFn {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = &self.ident;
+        let inputs = &self.inputs;
+        let output = &self.output;
+        let expr = &self.expr;
+        tokens.extend(quote! {
+            fn #ident(#inputs) -> #output {
+                #expr
+            }
+        });
+    }
+}
+
+// Function arguments
+#[derive(Debug)]
+pub struct FnArg {
+    pub pat: patterns::Pat,
+    pub colon_token: Token![:],
+    pub ty: types::Type,
+}
+
+impl Parse for FnArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(FnArg {
+            pat: input.parse()?,
+            colon_token: input.parse()?,
+            ty: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for FnArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.pat.to_tokens(tokens);
+        self.colon_token.to_tokens(tokens);
+        self.ty.to_tokens(tokens);
+    }
+}
+
+// Module name declaration
+#[derive(Debug)]
+pub struct ItemMod {
+    pub attrs: Vec<syn::Attribute>,
+    pub assert_policy: AssertPolicy,
+    pub mod_token: Token![mod],
+    pub name: Ident,
+    pub semi_token: Token![;],
+}
+
+impl ItemMod {
+    // `#[ffi]` exports the program through the C ABI
+    pub fn is_ffi(&self) -> bool {
+        self.attrs.iter().any(|attr| attr.path.is_ident("ffi"))
+    }
+}
+
+// What `run` does when `assert` items fail, chosen by `#[assert(...)]` on the module.
+// `record` and `hook` do not panic, for `no_std` builds.
+#[derive(Debug)]
+pub enum AssertPolicy {
+    Panic,
+    // `Violations` accumulated in `FRP` until it is reset
+    Record,
+    // a function called with the `Violations` of the instant, resolved from the module
+    // containing the program
+    Hook(syn::Path),
+}
+
+impl Parse for AssertPolicy {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        if ident == "panic" {
+            Ok(AssertPolicy::Panic)
+        } else if ident == "record" {
+            Ok(AssertPolicy::Record)
+        } else if ident == "hook" {
+            input.parse::<Token![=]>()?;
+            Ok(AssertPolicy::Hook(input.parse()?))
+        } else {
+            Err(syn::Error::new_spanned(
+                ident,
+                "expected `panic`, `record` or `hook = path`",
+            ))
+        }
+    }
+}
+
+impl Parse for ItemMod {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let mut assert_policy = AssertPolicy::Panic;
+        for attr in attrs.iter() {
+            if attr.path.is_ident("assert") {
+                assert_policy = attr.parse_args()?;
+            } else if !attr.path.is_ident("ffi") || !attr.tokens.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "unknown attribute, expected `#[ffi]` or `#[assert(...)]`",
+                ));
+            }
+        }
+        Ok(ItemMod {
+            attrs,
+            assert_policy,
+            mod_token: input.parse()?,
+            name: input.parse()?,
+            semi_token: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ItemMod {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for attr in self.attrs.iter() {
+            attr.to_tokens(tokens);
+        }
+        self.mod_token.to_tokens(tokens);
+        self.name.to_tokens(tokens);
+        self.semi_token.to_tokens(tokens);
+    }
+}
+
+// Field declaration
+#[derive(Debug)]
+pub struct Field {
+    pub ident: Ident,
+    pub colon_token: Token![:],
+    pub ty: types::Type,
+}
+
+impl ToTokens for Field {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = &self.ident;
+        let colon_token = &self.colon_token;
+        let ty = &self.ty;
+        tokens.extend(quote! {
+            #ident #colon_token #ty
+        });
+    }
+}
+
+impl Parse for Field {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Field {
+            ident: input.parse()?,
+            colon_token: input.parse()?,
+            ty: input.parse()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ItemArgs {
+    pub args_token: custom_keywords::Args,
+    pub braced_token: Brace,
+    pub fields: Punctuated<Field, Comma>,
+}
+
+#[derive(Debug)]
+pub struct ItemOut {
+    pub out_token: custom_keywords::Out,
+    pub braced_token: Brace,
+    pub fields: Punctuated<Field, Comma>,
+}
+
+#[derive(Debug)]
+pub struct ItemIn {
+    pub in_token: custom_keywords::In,
+    pub braced_token: Brace,
+    pub fields: Punctuated<Field, Comma>,
+}
+
+// internal signals and cells readable through `FRP::probe` after `run`
+#[derive(Debug)]
+pub struct ItemProbe {
+    pub probe_token: custom_keywords::Probe,
+    pub braced_token: Brace,
+    pub fields: Punctuated<Field, Comma>,
+}
+
+macro_rules! impl_parse_for_key {
+    ($type:tt, $token_param:ident) => {
+        impl Parse for $type {
+            fn parse(input: ParseStream) -> Result<Self> {
+                let content;
+                let $token_param = input.parse()?;
+                let braced_token = braced!(content in input);
+                let fields = content.parse_terminated(Field::parse)?;
+
+                Ok(Self {
+                    $token_param,
+                    braced_token,
+                    fields,
+                })
+            }
+        }
+    }
+}
+
+impl_parse_for_key!(ItemIn, in_token);
+impl_parse_for_key!(ItemOut, out_token);
+impl_parse_for_key!(ItemArgs, args_token);
+impl_parse_for_key!(ItemProbe, probe_token);
+
+macro_rules! impl_to_tokens_for_key {
+    ($type:tt, $token_param:ident) => {
+        impl ToTokens for $type {
+            fn to_tokens(&self, tokens: &mut TokenStream) {
+                if cfg!(feature = "impl-debug") {
+                    tokens.extend(quote! {
+                        #[derive(Debug, Clone, Default)]
+                    });
+                } else {
+                    tokens.extend(quote! {
+                        #[derive(Clone, Default)]
+                    });
+                }
+                tokens.extend(derive::serde());
+                tokens.extend(quote! {
+                    pub struct
+                });
+                self.$token_param.to_tokens(tokens);
+                self.braced_token.surround(tokens, |tokens| {
+                    for pair in self.fields.pairs() {
+                        use Pair::*;
+
+                        let field;
+                        let comma;
+                        match pair {
+                            Punctuated(f, c) => {
+                                field = f;
+                                comma = Some(c);
+                            }
+                            End(f) => {
+                                field = f;
+                                comma = None;
+                            }
+                        }
+                        tokens.extend(quote! {
+                            pub #field #comma
+                        });
+                    }
+                });
+                let ident = &self.$token_param;
+                let shape = Shape::of_struct(ident, self.fields.iter());
+                tokens.extend(derive::trace(ident, &shape));
+                tokens.extend(derive::columns(ident, &shape, true));
+                tokens.extend(derive::arbitrary(ident, &shape, false));
+                tokens.extend(derive::finite(ident, &shape));
+            }
+        }
+    };
+}
+
+impl_to_tokens_for_key!(ItemIn, in_token);
+impl_to_tokens_for_key!(ItemOut, out_token);
+impl_to_tokens_for_key!(ItemArgs, args_token);
+impl_to_tokens_for_key!(ItemProbe, probe_token);
+
+// `assert expr;`, a condition holding at every instant, which `lrfrp::check` verifies
+// up to a bound. It is named after its source.
+#[derive(Debug)]
+pub struct ItemAssert {
+    pub assert_token: custom_keywords::assert,
+    pub name: String,
+    pub expr: expressions::Expr,
+    pub semi_token: Token![;],
+}
+
+impl Parse for ItemAssert {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let assert_token = input.parse()?;
+        let begin = input.cursor();
+        let expr = input.parse()?;
+        let mut source = TokenStream::new();
+        let mut cursor = begin;
+        while cursor != input.cursor() {
+            match cursor.token_tree() {
+                Some((token_tree, next)) => {
+                    source.extend(Some(token_tree));
+                    cursor = next;
+                }
+                None => break,
+            }
+        }
+        // `TokenStream` separates every token by spaces
+        let mut name = source.to_string();
+        for (spaced, tight) in [
+            (" :: ", "::"),
+            (" . ", "."),
+            (" ,", ","),
+            ("( ", "("),
+            (" )", ")"),
+            ("[ ", "["),
+            (" ]", "]"),
+            ("! ", "!"),
+        ] {
+            name = name.replace(spaced, tight);
+        }
+        Ok(ItemAssert {
+            assert_token,
+            name,
+            expr,
+            semi_token: input.parse()?,
+        })
+    }
+}
+
+impl ItemAssert {
+    // the hidden signal calculating the condition, numbered by the position of the item
+    pub fn desugar(self, index: usize) -> (String, FrpStmtDependency) {
+        let signal = Ident::new(&format!("__assert_{}", index), self.expr.span());
+        (
+            self.name,
+            FrpStmtDependency {
+                let_token: Token![let](self.assert_token.span),
+                path: path::Path::from(signal),
+                eq_token: Default::default(),
+                expr: self.expr,
+                semi_token: self.semi_token,
+            },
+        )
+    }
+}
+
+// `property name = expr;`, a named `assert`
+#[derive(Debug)]
+pub struct ItemProperty {
+    pub property_token: custom_keywords::property,
+    pub ident: Ident,
+    pub eq_token: Token![=],
+    pub expr: expressions::Expr,
+    pub semi_token: Token![;],
+}
+
+impl Parse for ItemProperty {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(ItemProperty {
+            property_token: input.parse()?,
+            ident: input.parse()?,
+            eq_token: input.parse()?,
+            expr: input.parse()?,
+            semi_token: input.parse()?,
+        })
+    }
+}
+
+impl ItemProperty {
+    pub fn desugar(self) -> (String, FrpStmtDependency) {
+        let signal = Ident::new(&format!("__property_{}", self.ident), self.expr.span());
+        (
+            self.ident.to_string(),
+            FrpStmtDependency {
+                let_token: Token![let](self.property_token.span),
+                path: path::Path::from(signal),
+                eq_token: self.eq_token,
+                expr: self.expr,
+                semi_token: self.semi_token,
+            },
+        )
+    }
+}
+
+// Struct declaration
+#[derive(Debug)]
+pub struct ItemStruct {
+    pub struct_token: Token![struct],
+    pub ident: Ident,
+    pub braced_token: Brace,
+    pub fields: Punctuated<Field, Comma>,
+}
+
+impl Parse for ItemStruct {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(ItemStruct {
+            struct_token: input.parse()?,
+            ident: input.parse()?,
+            braced_token: braced!(content in input),
+            fields: content.parse_terminated(Field::parse)?,
+        })
+    }
+}
+
+impl ToTokens for ItemStruct {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        // every value in the language is copied between signals and cells
+        if cfg!(feature = "impl-debug") {
+            tokens.extend(quote! {
+                #[derive(Debug, Clone, Copy, Default, PartialEq)]
+            });
+        } else {
+            tokens.extend(quote! {
+                #[derive(Clone, Copy, Default, PartialEq)]
+            });
+        }
+        tokens.extend(derive::serde());
+        let ident = &self.ident;
+        let fields = self.fields.iter();
+        tokens.extend(quote! {
+            pub struct #ident {
+                #(pub #fields),*
+            }
+        });
+        let shape = Shape::of_struct(ident, self.fields.iter());
+        tokens.extend(derive::trace(ident, &shape));
+        tokens.extend(derive::columns(ident, &shape, false));
+        tokens.extend(derive::arbitrary(ident, &shape, true));
+        tokens.extend(derive::finite(ident, &shape));
+        tokens.extend(derive::state_key(ident, &shape));
+    }
+}
+
+// Enum declaration
+#[derive(Debug)]
+pub struct ItemEnum {
+    pub enum_token: Token![enum],
+    pub ident: Ident,
+    pub braced_token: Brace,
+    pub variants: Punctuated<Variant, Comma>,
+}
+
+impl Parse for ItemEnum {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let item_enum = ItemEnum {
+            enum_token: input.parse()?,
+            ident: input.parse()?,
+            braced_token: braced!(content in input),
+            variants: content.parse_terminated(Variant::parse)?,
+        };
+        if item_enum.variants.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &item_enum.ident,
+                "enum must have at least one variant",
+            ));
+        }
+        Ok(item_enum)
+    }
+}
+
+impl ToTokens for ItemEnum {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if cfg!(feature = "impl-debug") {
+            tokens.extend(quote! {
+                #[derive(Debug, Clone, Copy, PartialEq)]
+            });
+        } else {
+            tokens.extend(quote! {
+                #[derive(Clone, Copy, PartialEq)]
+            });
+        }
+        tokens.extend(derive::serde());
+        let ident = &self.ident;
+        let variants = &self.variants;
+        tokens.extend(quote! {
+            pub enum #ident {
+                #variants
+            }
+        });
+
+        // the first variant is the initial value of cells and outputs
+        let first = &self.variants[0];
+        let variant = &first.ident;
+        let fields = first.fields.iter().map(|_| {
+            quote! { core::default::Default::default() }
+        });
+        let value = match first.paren_token {
+            Some(_) => quote! { #ident::#variant(#(#fields),*) },
+            None => quote! { #ident::#variant },
+        };
+        tokens.extend(quote! {
+            impl core::default::Default for #ident {
+                fn default() -> Self {
+                    #value
+                }
+            }
+        });
+
+        let shape = Shape::of_enum(self);
+        tokens.extend(derive::trace(ident, &shape));
+        tokens.extend(derive::columns(ident, &shape, false));
+        tokens.extend(derive::arbitrary(ident, &shape, true));
+        tokens.extend(derive::finite(ident, &shape));
+        tokens.extend(derive::state_key(ident, &shape));
+    }
+}
+
+// Enum variant, optionally with unnamed fields
+#[derive(Debug)]
+pub struct Variant {
+    pub ident: Ident,
+    pub paren_token: Option<Paren>,
+    pub fields: Punctuated<types::Type, Comma>,
+}
+
+impl Parse for Variant {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident = input.parse()?;
+        if input.peek(Paren) {
+            let content;
+            Ok(Variant {
+                ident,
+                paren_token: Some(parenthesized!(content in input)),
+                fields: content.parse_terminated(types::Type::parse)?,
+            })
+        } else {
+            Ok(Variant {
+                ident,
+                paren_token: None,
+                fields: Punctuated::new(),
+            })
+        }
+    }
+}
+
+impl ToTokens for Variant {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.ident.to_tokens(tokens);
+        if let Some(paren_token) = &self.paren_token {
+            paren_token.surround(tokens, |tokens| self.fields.to_tokens(tokens));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ItemDeclaration {
+    Struct(ItemStruct),
+    Enum(ItemEnum),
+    Fn(ItemFn),
+}
+
+impl Parse for ItemDeclaration {
+    fn parse(input: ParseStream) -> Result<Self> {
+        use ItemDeclaration::*;
+
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![struct]) {
+            input.parse().map(Struct)
+        } else if lookahead.peek(Token![enum]) {
+            input.parse().map(Enum)
+        } else if lookahead.peek(Token![fn]) {
+            input.parse().map(Fn)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for ItemDeclaration {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        use ItemDeclaration::*;
+        match self {
+            Struct(e) => e.to_tokens(tokens),
+            Enum(e) => e.to_tokens(tokens),
+            Fn(e) => e.to_tokens(tokens),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ItemFrpStmt {
+    Dependency(FrpStmtDependency),
+    Arrow(FrpStmtArrow),
+    Destructure(FrpStmtDestructure),
+}
+
+impl Parse for ItemFrpStmt {
+    fn parse(input: ParseStream) -> Result<Self> {
+        use ItemFrpStmt::*;
+
+        let let_token = input.parse()?;
+        if input.peek(Paren) {
+            return Ok(Destructure(FrpStmtDestructure {
+                let_token,
+                pat: input.parse()?,
+                eq_token: input.parse()?,
+                expr: input.parse()?,
+                semi_token: input.parse()?,
+            }));
+        }
+        let path: path::Path = input.parse()?;
+        if path.is_qualified() {
+            return Err(syn::Error::new_spanned(path, "expected identifier"));
+        }
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![=]) {
+            let eq_token = input.parse()?;
+            let expr = input.parse()?;
+            let semi_token = input.parse()?;
+            Ok(Dependency(FrpStmtDependency {
+                let_token,
+                path,
+                eq_token,
+                expr,
+                semi_token,
+            }))
+        } else if lookahead.peek(Token![:]) {
+            let colon_token = input.parse()?;
+            let ty = input.parse()?;
+            let left_arrow_token = input.parse()?;
+            let arrow_expr = Some(input.parse()?);
+            let rev_arrow_token = input.parse()?;
+            let expr = input.parse()?;
+            let semi_token = input.parse()?;
+            Ok(Arrow(FrpStmtArrow {
+                let_token,
+                path,
+                colon_token,
+                ty,
+                left_arrow_token,
+                arrow_expr,
+                rev_arrow_token,
+                expr,
+                semi_token,
+            }))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FrpStmtDependency {
+    pub let_token: Token![let],
+    pub path: path::Path,
+    pub eq_token: Token![=],
+    pub expr: expressions::Expr,
+    pub semi_token: Token![;],
+}
+
+impl ToTokens for FrpStmtDependency {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        use crate::lrfrp_ir::types::*;
+        match &self.path {
+            path::Path::Segment(_) | path::Path::Qualified(..) => unreachable!(),
+            path::Path::TypedSegment(_, ty) => match ty {
+                Type::Mono(TypeMono::Type(_)) => unreachable!(),
+                Type::Lifted(TypeLifted::Signal(TypeSignal::Local(ty))) => {
+                    self.let_token.to_tokens(tokens);
+                    self.path.to_tokens(tokens);
+                    if let MaybeType::Resolved(ty) = ty {
+                        tokens.extend(quote! { : #ty });
+                    }
+                }
+                _ => self.path.to_tokens(tokens),
+            },
+        }
+        self.eq_token.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi_token.to_tokens(tokens);
+    }
+}
+
+// `let (a, b) = expr;`
+#[derive(Debug)]
+pub struct FrpStmtDestructure {
+    pub let_token: Token![let],
+    pub pat: patterns::Pat,
+    pub eq_token: Token![=],
+    pub expr: expressions::Expr,
+    pub semi_token: Token![;],
+}
+
+impl FrpStmtDestructure {
+    // Splits the statement into a hidden signal holding the whole value and
+    // one dependency per bound name, so that each name is a separate node.
+    pub fn desugar(self) -> Result<Vec<FrpStmtDependency>> {
+        let mut names = vec![];
+        let mut bindings = vec![];
+        collect_bindings(&self.pat, &mut vec![], &mut names, &mut bindings)?;
+        if names.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let span = match &self.pat {
+            patterns::Pat::Tuple(p) => p.paren_token.span,
+            _ => self.let_token.span,
+        };
+        let hidden = Ident::new(&format!("__{}", names.join("_")), span);
+
+        let mut stmts = vec![FrpStmtDependency {
+            let_token: self.let_token,
+            path: path::Path::from(hidden.clone()),
+            eq_token: self.eq_token,
+            expr: self.expr,
+            semi_token: self.semi_token,
+        }];
+        for (ident, indices) in bindings {
+            let mut expr = expressions::Expr::Path(expressions::ExprPath {
+                path: path::Path::from(hidden.clone()),
+            });
+            for index in indices {
+                expr = expressions::Expr::Field(expressions::ExprField {
+                    base: Box::new(expr),
+                    dot_token: Default::default(),
+                    member: syn::Member::Unnamed(syn::Index {
+                        index,
+                        span: ident.span(),
+                    }),
+                });
+            }
+            stmts.push(FrpStmtDependency {
+                let_token: self.let_token,
+                path: path::Path::from(ident),
+                eq_token: self.eq_token,
+                expr,
+                semi_token: self.semi_token,
+            });
+        }
+        Ok(stmts)
+    }
+}
+
+fn collect_bindings(
+    pat: &patterns::Pat,
+    indices: &mut Vec<u32>,
+    names: &mut Vec<String>,
+    bindings: &mut Vec<(Ident, Vec<u32>)>,
+) -> Result<()> {
+    use patterns::Pat;
+    match pat {
+        Pat::Wild(_) => Ok(()),
+        Pat::Ident(p) => {
+            names.push(p.ident.to_string());
+            bindings.push((p.ident.clone(), indices.clone()));
+            match &p.subpat {
+                Some((_, pat)) => collect_bindings(pat, indices, names, bindings),
+                None => Ok(()),
+            }
+        }
+        // the elements after `..` are unknown before type checking
+        Pat::Tuple(p) if !p.back.is_empty() => Err(syn::Error::new_spanned(
+            &p.back,
+            "elements after `..` are not allowed in signal definitions",
+        )),
+        Pat::Tuple(p) => {
+            for (index, pat) in p.front.iter().enumerate() {
+                indices.push(index as u32);
+                collect_bindings(pat, indices, names, bindings)?;
+                indices.pop();
+            }
+            Ok(())
+        }
+        pat => Err(syn::Error::new_spanned(
+            pat,
+            "only tuple patterns are allowed in signal definitions",
+        )),
+    }
+}
+
+#[derive(Debug)]
+pub struct FrpStmtArrow {
+    pub let_token: Token![let],
+    pub path: path::Path,
+    pub colon_token: Token![:],
+    pub ty: types::Type,
+    pub left_arrow_token: Token![<-],
+    // `None` for the cells synthesized from `pre`, which start from `Default::default()`
+    pub arrow_expr: Option<expressions::ArrowExpr>,
+    pub rev_arrow_token: custom_punctuations::RevArrow,
+    pub expr: expressions::Expr,
+    pub semi_token: Token![;],
+}
+
+impl FrpStmtArrow {
+    pub fn count(&self) -> usize {
+        self.arrow_expr
+            .as_ref()
+            .map_or(1, |arrow_expr| arrow_expr.count())
+    }
+
+    // the hidden fields of the ring buffer holding the values of `delay N`
+    // which are not visible yet
+    pub fn ring(&self) -> Option<(Ident, Ident)> {
+        if self.count() > 1 {
+            let ident: &Ident = self.path.borrow();
+            Some((
+                Ident::new(&format!("__{}_ring", ident), ident.span()),
+                Ident::new(&format!("__{}_pos", ident), ident.span()),
+            ))
+        } else {
+            None
+        }
+    }
+
+    // the cell itself holds the current value
+    fn ring_len(&self) -> proc_macro2::Literal {
+        proc_macro2::Literal::usize_unsuffixed(self.count() - 1)
+    }
+}
+
+impl Parse for Ast {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut items = vec![];
+        while !input.is_empty() {
+            let item = input.parse()?;
+            items.push(item);
+        }
+
+        Ok(Ast { items })
+    }
+}
+
+#[derive(Debug)]
+pub struct FrpStmtArrows(Vec<FrpStmtArrow>);
+
+impl FrpStmtArrows {
+    pub fn new() -> Self {
+        FrpStmtArrows(vec![])
+    }
+
+    pub fn push(&mut self, arrow: FrpStmtArrow) {
+        self.0.push(arrow);
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&FrpStmtArrow) -> bool) {
+        self.0.retain(f);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FrpStmtArrow> {
+        self.0.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut FrpStmtArrow> {
+        self.0.iter_mut()
+    }
+
+    // the states of sub-program instances are embedded as cells as well
+    pub fn cell_definition(&self, instances: &[&expressions::ExprInstance]) -> TokenStream {
+        let mut fields = TokenStream::new();
+        let mut defaults = TokenStream::new();
+        let mut members = vec![];
+        for instance in instances {
+            let cell = &instance.cell;
+            let module = &instance.module;
+            members.push((cell.clone(), quote! { super::#module::FRP }));
+            fields.extend(instance.cell_field());
+            defaults.extend(quote! {
+                #cell: core::default::Default::default(),
+            });
+        }
+        for arrow in &self.0 {
+            let ident: &Ident = arrow.path.borrow();
+            let colon = &arrow.colon_token;
+            let ty = &arrow.ty;
+            members.push((ident.clone(), quote! { #ty }));
+            fields.extend(quote! {
+                #ident #colon #ty,
+            });
+            defaults.extend(quote! {
+                #ident: core::default::Default::default(),
+            });
+            if let Some((ring, pos)) = arrow.ring() {
+                let len = arrow.ring_len();
+                if cfg!(feature = "serde") {
+                    let deserialize = format!("__serde_ring::{}", ring);
+                    fields.extend(quote! {
+                        #[serde(
+                            serialize_with = "__serde_ring::serialize",
+                            deserialize_with = #deserialize
+                        )]
+                    });
+                }
+                fields.extend(quote! {
+                    #ring: [#ty; #len],
+                    #pos: usize,
+                });
+                members.push((ring.clone(), quote! { [#ty; #len] }));
+                members.push((pos.clone(), quote! { usize }));
+                defaults.extend(quote! {
+                    #ring: [core::default::Default::default(); #len],
+                    #pos: 0,
+                });
+            }
+        }
+
+        // `Default` is implemented by hand since arrays longer than 32 do not derive it.
+        // `Cell` is public for `State`, but its fields are not.
+        let derive_serde = derive::serde();
+        let cell = quote! { Cell };
+        let state_key = derive::state_key(&cell, &Shape::of_fields(&cell, members.into_iter()));
+        quote! {
+            #[derive(Clone)]
+            #derive_serde
+            pub struct Cell {
+                #fields
+            }
+
+            #state_key
+
+            impl core::default::Default for Cell {
+                fn default() -> Self {
+                    Cell {
+                        #defaults
+                    }
+                }
+            }
+        }
+    }
+
+    // `serde` implements its traits only for arrays of up to 32 elements, so the rings
+    // are (de)serialized as tuples of any length here. Every ring has its own
+    // `deserialize_with` since arrays cannot be created generically over their length.
+    pub fn serde_ring(&self) -> Option<TokenStream> {
+        if !cfg!(feature = "serde") {
+            return None;
+        }
+        let mut deserializers = TokenStream::new();
+        for arrow in &self.0 {
+            if let Some((ring, _)) = arrow.ring() {
+                let ty = &arrow.ty;
+                let len = arrow.ring_len();
+                deserializers.extend(quote! {
+                    pub fn #ring<'de, D>(deserializer: D) -> Result<[#ty; #len], D::Error>
+                    where
+                        D: Deserializer<'de>,
+                    {
+                        let mut ring = [core::default::Default::default(); #len];
+                        deserializer.deserialize_tuple(#len, RingVisitor(&mut ring))?;
+                        Ok(ring)
+                    }
+                });
+            }
+        }
+        if deserializers.is_empty() {
+            return None;
+        }
+
+        Some(quote! {
+            mod __serde_ring {
+                #[allow(unused_imports)]
+                use super::*;
+                use core::fmt;
+                use serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+                use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+                pub fn serialize<S, T>(ring: &[T], serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                    T: Serialize,
+                {
+                    let mut tuple = serializer.serialize_tuple(ring.len())?;
+                    for value in ring.iter() {
+                        tuple.serialize_element(value)?;
+                    }
+                    tuple.end()
+                }
+
+                struct RingVisitor<'a, T>(&'a mut [T]);
+
+                impl<'de, 'a, T: Deserialize<'de>> Visitor<'de> for RingVisitor<'a, T> {
+                    type Value = ();
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        write!(f, "an array of length {}", self.0.len())
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> Result<(), A::Error>
+                    where
+                        A: SeqAccess<'de>,
+                    {
+                        for i in 0..self.0.len() {
+                            match seq.next_element()? {
+                                Some(value) => self.0[i] = value,
+                                None => return Err(A::Error::invalid_length(i, &self)),
+                            }
+                        }
+                        Ok(())
+                    }
+                }
+
+                #deserializers
+            }
+        })
+    }
+
+    pub fn cell_updates(&self) -> TokenStream {
+        let mut cell_updates = TokenStream::new();
+        for arrow in &self.0 {
+            let path = &arrow.path;
+            let expr = &arrow.expr;
+            match arrow.ring() {
+                // the oldest buffered value becomes visible. The position wraps without `%`,
+                // which clippy denies for the ring of `delay 2` of length 1.
+                Some((ring, pos)) => {
+                    let len = arrow.ring_len();
+                    cell_updates.extend(quote! {
+                        {
+                            let __value = #expr;
+                            let __pos = self.cell.#pos;
+                            #path = core::mem::replace(&mut self.cell.#ring[__pos], __value);
+                            self.cell.#pos = if __pos + 1 == #len { 0 } else { __pos + 1 };
+                        }
+                    });
+                }
+                None => cell_updates.extend(quote! {
+                    #path = #expr;
+                }),
+            }
+        }
+
+        cell_updates
+    }
+
+    pub fn cell_initializations(&self, instances: &[&expressions::ExprInstance]) -> TokenStream {
+        let mut cell_initializations = TokenStream::new();
+        for instance in instances {
+            cell_initializations.extend(instance.cell_initialization());
+        }
+        for arrow in &self.0 {
+            let path = &arrow.path;
+            let mut expr = match &arrow.arrow_expr {
+                Some(arrow_expr) => &*arrow_expr.expr,
+                None => continue,
+            };
+            // `delay 3 (-5)` needs parentheses which are unused here
+            while let expressions::Expr::Paren(paren) = expr {
+                expr = &paren.expr;
+            }
+            cell_initializations.extend(quote! {
+                #path = #expr;
+            });
+            if let Some((ring, _)) = arrow.ring() {
+                let len = arrow.ring_len();
+                cell_initializations.extend(quote! {
+                    self.cell.#ring = [#path; #len];
+                });
+            }
+        }
+        cell_initializations
+    }
+}
@@ -3,5 +3,10 @@ use syn::custom_keyword;
 custom_keyword!(In);
 custom_keyword!(Out);
 custom_keyword!(Args);
+custom_keyword!(Probe);
+custom_keyword!(assert);
+custom_keyword!(property);
 custom_keyword!(then);
 custom_keyword!(delay);
+custom_keyword!(pre);
+custom_keyword!(with);
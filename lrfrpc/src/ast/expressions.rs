@@ -1,4 +1,5 @@
-use super::custom_keywords::{delay, then};
+use super::custom_keywords::{delay, pre, then, with};
+use super::custom_punctuations::RevArrow;
 use super::literals::Lit;
 use super::path::Path;
 use super::patterns::Pat;
@@ -10,7 +11,7 @@ use std::ops::Deref;
 
 use crate::lrfrp_ir::types;
 
-use quote::{quote, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
@@ -22,23 +23,71 @@ use syn::token::{Brace, Bracket, Colon, Dot2, FatArrow, If, Match, Paren};
 use syn::Result;
 use syn::Token;
 use syn::{braced, bracketed, parenthesized};
-use syn::{Ident, Member};
+use syn::{Ident, LitInt, Member};
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 
+mod builtins;
 mod precedence;
+pub use builtins::Builtin;
 use precedence::Precedence;
 
 #[derive(Debug)]
 pub struct ArrowExpr {
     pub delay_token: delay,
+    // `delay N init` delays by `N` instants
+    pub count: Option<LitInt>,
     pub expr: Box<Expr>,
 }
 
+impl ArrowExpr {
+    pub fn count(&self) -> usize {
+        match &self.count {
+            // validated while parsing
+            Some(count) => count.base10_parse().unwrap_or_else(|_| unreachable!()),
+            None => 1,
+        }
+    }
+}
+
 impl Parse for ArrowExpr {
     fn parse(input: ParseStream) -> Result<Self> {
+        let delay_token = input.parse()?;
+        // an integer followed by another expression is the number of instants,
+        // so that `delay 2 -< x` and `delay 2 + 1 -< x` keep their meanings
+        let count = if input.peek(LitInt) {
+            let fork = input.fork();
+            fork.parse::<LitInt>()?;
+            // `delay 3 -5` could delay by 3 instants as well as start with `3 - 5`
+            if fork.peek(Token![-]) && !fork.peek(RevArrow) {
+                return Err(syn::Error::new(
+                    fork.span(),
+                    "ambiguous `-` after an integer in `delay`, write `delay N (-init)` for a \
+                     negative initial value or `delay (a - b)` for a subtraction",
+                ));
+            }
+            if fork.peek(RevArrow)
+                || fork.fork().parse::<BinOp>().is_ok()
+                || fork.peek(Token![as])
+                || fork.peek(Token![:])
+            {
+                None
+            } else {
+                let count: LitInt = input.parse()?;
+                if count.base10_parse::<usize>()? == 0 {
+                    return Err(syn::Error::new(
+                        count.span(),
+                        "the number of delayed instants must be at least 1",
+                    ));
+                }
+                Some(count)
+            }
+        } else {
+            None
+        };
         Ok(ArrowExpr {
-            delay_token: input.parse()?,
+            delay_token,
+            count,
             expr: Box::new(input.parse()?),
         })
     }
@@ -63,14 +112,17 @@ pub enum Expr {
     Cast(ExprCast),
     If(ExprIf),
     Index(ExprIndex),
+    Init(ExprInit),
+    Instance(ExprInstance),
     Lit(ExprLit),
-    #[allow(dead_code)]
     Match(ExprMatch),
     Paren(ExprParen),
+    Pre(ExprPre),
     Struct(ExprStruct),
     Tuple(ExprTuple),
     Path(ExprPath),
     List(ExprList),
+    Repeat(ExprRepeat),
     Type(ExprType),
 
     // types for type checker
@@ -84,6 +136,37 @@ impl Parse for Expr {
     }
 }
 
+impl Expr {
+    // `Spanned` cannot be used here because typed paths expand to `self.cell.x` etc.
+    // whose leading tokens carry the call-site span.
+    pub fn span(&self) -> Span {
+        use Expr::*;
+        match self {
+            Unary(e) => e.op.span(),
+            Binary(e) => e.lhs.span(),
+            Block(e) => e.braced_token.span,
+            Call(e) => Borrow::<Ident>::borrow(&e.func).span(),
+            Field(e) => e.base.span(),
+            Cast(e) => e.expr.span(),
+            If(e) => e.if_token.span,
+            Index(e) => e.expr.span(),
+            Init(e) => e.lhs.span(),
+            Instance(e) => e.module.span(),
+            Lit(e) => e.lit.span(),
+            Match(e) => e.match_token.span,
+            Paren(e) => e.paren_token.span,
+            Pre(e) => e.pre_token.span,
+            Struct(e) => Borrow::<Ident>::borrow(&e.path).span(),
+            Tuple(e) => e.paren_token.span,
+            Path(e) => Borrow::<Ident>::borrow(e).span(),
+            List(e) => e.bracket_token.span,
+            Repeat(e) => e.bracket_token.span,
+            Type(e) => e.expr.span(),
+            TypedExpr(e, _) => e.span(),
+        }
+    }
+}
+
 impl ToTokens for Expr {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         use Expr::*;
@@ -92,23 +175,26 @@ impl ToTokens for Expr {
             Binary(e) => e.to_tokens(tokens),
             // Block(ref e),
             Call(e) => e.to_tokens(tokens),
-            // Field(ref e),
-            // Cast(ref e),
+            Field(e) => e.to_tokens(tokens),
+            Cast(e) => e.to_tokens(tokens),
             If(e) => e.to_tokens(tokens),
-            // Index(ref e),
+            Index(e) => e.to_tokens(tokens),
+            Instance(e) => e.to_tokens(tokens),
             Lit(e) => e.to_tokens(tokens),
-            // Match(ref e),
+            Match(e) => e.to_tokens(tokens),
             Paren(e) => e.to_tokens(tokens),
-            // Struct(ref e),
-            // Tuple(ref e),
+            Struct(e) => e.to_tokens(tokens),
+            Tuple(e) => e.to_tokens(tokens),
             Path(e) => e.to_tokens(tokens),
-            // List(ref e),
-            // Type(ref e),
+            List(e) => e.to_tokens(tokens),
+            Repeat(e) => e.to_tokens(tokens),
+            Type(e) => e.to_tokens(tokens),
             TypedExpr(e, _) => e.to_tokens(tokens),
 
             Block(e) => e.to_tokens(tokens),
 
-            _ => unimplemented!("totokens for expr"),
+            // lifted into cells in `LrfrpIR::from_ast`
+            Init(_) | Pre(_) => unreachable!(),
         }
     }
 }
@@ -120,7 +206,16 @@ fn ambiguous_expr(input: ParseStream, allow_struct: AllowStruct) -> Result<Expr>
 
 fn unary_expr(input: ParseStream, allow_struct: AllowStruct) -> Result<Expr> {
     if input.peek(Token![!]) || input.peek(Token![-]) {
-        Ok(input.parse().map(Expr::Unary)?)
+        // unary operators bind tighter than binary ones as in Rust
+        Ok(Expr::Unary(ExprUnary {
+            op: input.parse()?,
+            expr: Box::new(unary_expr(input, allow_struct)?),
+        }))
+    } else if input.peek(pre) {
+        Ok(Expr::Pre(ExprPre {
+            pre_token: input.parse()?,
+            expr: Box::new(unary_expr(input, allow_struct)?),
+        }))
     } else {
         trailer_expr(input, allow_struct)
     }
@@ -144,11 +239,11 @@ fn atom_expr(input: ParseStream, allow_struct: AllowStruct) -> Result<Expr> {
     } else if input.peek(Paren) {
         paren_or_tuple(input)
     } else if input.peek(Bracket) {
-        input.parse().map(Expr::List)
+        list_or_repeat(input)
     } else if input.peek(Token![if]) {
         input.parse().map(Expr::If)
     } else if input.peek(Match) {
-        unimplemented!("Match")
+        input.parse().map(Expr::Match)
     } else if input.peek(Brace) {
         input.parse().map(Expr::Block)
     } else {
@@ -192,11 +287,26 @@ fn path_or_struct_or_fn(input: ParseStream, allow_struct: AllowStruct) -> Result
         }))
     } else if input.peek(Paren) {
         let content;
-        Ok(Expr::Call(ExprCall {
+        let call = ExprCall {
             func: path,
             paren_token: parenthesized!(content in input),
             args: content.parse_terminated(Expr::parse)?,
-        }))
+            builtin: None,
+        };
+        if input.peek(with) {
+            if call.func.path.is_qualified() {
+                return Err(syn::Error::new_spanned(&call.func, "expected module name"));
+            }
+            let content;
+            let instance_args = InstanceArgs {
+                with_token: input.parse()?,
+                brace_token: braced!(content in input),
+                fields: content.parse_terminated(FieldValue::parse)?,
+            };
+            Ok(Expr::Instance(ExprInstance::new(call, Some(instance_args))))
+        } else {
+            Ok(Expr::Call(call))
+        }
     } else {
         Ok(Expr::Path(path))
     }
@@ -234,9 +344,49 @@ fn paren_or_tuple(input: ParseStream) -> Result<Expr> {
     Ok(Expr::Tuple(ExprTuple { paren_token, elems }))
 }
 
+fn list_or_repeat(input: ParseStream) -> Result<Expr> {
+    let content;
+    let bracket_token = bracketed!(content in input);
+    if content.is_empty() {
+        return Ok(Expr::List(ExprList {
+            bracket_token,
+            elems: Punctuated::new(),
+        }));
+    }
+
+    let first: Expr = content.parse()?;
+    if content.peek(Token![;]) {
+        let semi_token = content.parse()?;
+        let len: LitInt = content.parse()?;
+        len.base10_parse::<usize>()?;
+        return Ok(Expr::Repeat(ExprRepeat {
+            bracket_token,
+            expr: Box::new(first),
+            semi_token,
+            len,
+        }));
+    }
+
+    let mut elems = Punctuated::new();
+    elems.push_value(first);
+    while !content.is_empty() {
+        let punct = content.parse()?;
+        elems.push_punct(punct);
+        if content.is_empty() {
+            break;
+        }
+        let value = content.parse()?;
+        elems.push_value(value);
+    }
+    Ok(Expr::List(ExprList {
+        bracket_token,
+        elems,
+    }))
+}
+
 fn trailer_helper(input: ParseStream, mut e: Expr) -> Result<Expr> {
     loop {
-        if input.peek(Token![.]) && input.peek(Token![..]) {
+        if input.peek(Token![.]) && !input.peek(Token![..]) {
             let dot_token = input.parse()?;
             let member = input.parse()?;
             e = Expr::Field(ExprField {
@@ -287,6 +437,16 @@ fn parse_expr(
                 op,
                 rhs: Box::new(rhs),
             });
+        } else if Precedence::Init >= base && input.peek(Token![->]) {
+            let arrow_token: Token![->] = input.parse()?;
+            let rhs = unary_expr(input, allow_struct)?;
+            // right associative
+            let rhs = parse_expr(input, rhs, allow_struct, Precedence::Init)?;
+            lhs = Expr::Init(ExprInit {
+                lhs: Box::new(lhs),
+                arrow_token,
+                rhs: Box::new(rhs),
+            });
         } else if Precedence::Cast >= base && input.peek(Token![as]) {
             let as_token: Token![as] = input.parse()?;
             let ty = input.parse()?;
@@ -324,7 +484,7 @@ impl Borrow<Ident> for ExprPath {
 impl Parse for ExprPath {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(ExprPath {
-            path: input.parse()?,
+            path: Path::parse_without_arguments(input)?,
         })
     }
 }
@@ -337,8 +497,19 @@ impl ToTokens for ExprPath {
 
 #[derive(Debug)]
 pub struct ExprTuple {
-    paren_token: Paren,
-    elems: Punctuated<Expr, Token![,]>,
+    pub paren_token: Paren,
+    pub elems: Punctuated<Expr, Token![,]>,
+}
+
+impl ToTokens for ExprTuple {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.paren_token.surround(tokens, |tokens| {
+            self.elems.to_tokens(tokens);
+            if self.elems.len() == 1 && !self.elems.trailing_punct() {
+                tokens.extend(quote! { , });
+            }
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -363,6 +534,21 @@ pub struct ExprStruct {
     pub rest: Option<Box<Expr>>,
 }
 
+impl ToTokens for ExprStruct {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        self.brace_token.surround(tokens, |tokens| {
+            self.fields.to_tokens(tokens);
+            if let Some(rest) = &self.rest {
+                if !self.fields.empty_or_trailing() {
+                    tokens.extend(quote! { , });
+                }
+                tokens.extend(quote! { .. #rest });
+            }
+        });
+    }
+}
+
 #[derive(Debug)]
 pub struct FieldValue {
     pub member: Member,
@@ -379,6 +565,11 @@ impl Parse for FieldValue {
                 let value: Expr = input.parse()?;
                 (Some(colon_token), value)
             }
+            Member::Named(_) if input.peek(Token![:]) => {
+                let colon_token: Token![:] = input.parse()?;
+                let value: Expr = input.parse()?;
+                (Some(colon_token), value)
+            }
             Member::Named(ref ident) => {
                 let value = Expr::Path(ExprPath {
                     path: Path::from(ident.clone()),
@@ -395,6 +586,15 @@ impl Parse for FieldValue {
     }
 }
 
+impl ToTokens for FieldValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        // shorthands are expanded because the value may be a lifted signal
+        let member = &self.member;
+        let expr = &self.expr;
+        tokens.extend(quote! { #member: #expr });
+    }
+}
+
 #[derive(Debug)]
 pub struct ExprMatch {
     pub match_token: Match,
@@ -403,6 +603,43 @@ pub struct ExprMatch {
     pub arms: Vec<Arm>,
 }
 
+impl Parse for ExprMatch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let match_token = input.parse()?;
+        let expr = Box::new(ambiguous_expr(input, AllowStruct(false))?);
+        let brace_token = braced!(content in input);
+        let mut arms = vec![];
+        while !content.is_empty() {
+            let arm: Arm = content.parse()?;
+            let is_block = matches!(*arm.body, Expr::Block(_));
+            if arm.comma.is_none() && !is_block && !content.is_empty() {
+                return Err(content.error("expected `,`"));
+            }
+            arms.push(arm);
+        }
+
+        Ok(ExprMatch {
+            match_token,
+            expr,
+            brace_token,
+            arms,
+        })
+    }
+}
+
+impl ToTokens for ExprMatch {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.match_token.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.brace_token.surround(tokens, |tokens| {
+            for arm in self.arms.iter() {
+                arm.to_tokens(tokens);
+            }
+        });
+    }
+}
+
 #[derive(Debug)]
 pub struct Arm {
     pub pat: Pat,
@@ -412,6 +649,35 @@ pub struct Arm {
     pub comma: Option<Comma>,
 }
 
+impl Parse for Arm {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Arm {
+            pat: Pat::parse_multi(input)?,
+            guard: if input.peek(Token![if]) {
+                Some((input.parse()?, Box::new(input.parse()?)))
+            } else {
+                None
+            },
+            fat_arrow_token: input.parse()?,
+            body: Box::new(input.parse()?),
+            comma: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Arm {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.pat.to_tokens(tokens);
+        if let Some((if_token, guard)) = &self.guard {
+            if_token.to_tokens(tokens);
+            guard.to_tokens(tokens);
+        }
+        self.fat_arrow_token.to_tokens(tokens);
+        self.body.to_tokens(tokens);
+        tokens.extend(quote! { , });
+    }
+}
+
 #[derive(Debug)]
 pub struct ExprLit {
     pub lit: Lit,
@@ -438,6 +704,31 @@ pub struct ExprIndex {
     pub index: Box<Expr>,
 }
 
+impl ExprIndex {
+    // the index if it is known at compile time
+    pub fn constant(&self) -> Option<u64> {
+        let mut index = &*self.index;
+        while let Expr::Paren(e) = index {
+            index = &e.expr;
+        }
+        match index {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) => lit.base10_parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl ToTokens for ExprIndex {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.expr.to_tokens(tokens);
+        self.bracket_token.surround(tokens, |tokens| {
+            self.index.to_tokens(tokens);
+        });
+    }
+}
+
 #[derive(Debug)]
 pub struct ExprIf {
     pub if_token: Token![if],
@@ -476,18 +767,52 @@ impl ToTokens for ExprIf {
     }
 }
 
+// `a -> b`: `a` at the first instant, `b` afterwards
+#[derive(Debug)]
+pub struct ExprInit {
+    pub lhs: Box<Expr>,
+    pub arrow_token: Token![->],
+    pub rhs: Box<Expr>,
+}
+
+// `pre e`: the value of `e` at the previous instant
+#[derive(Debug)]
+pub struct ExprPre {
+    pub pre_token: pre,
+    pub expr: Box<Expr>,
+}
+
 #[derive(Debug)]
 pub struct ExprCast {
-    expr: Box<Expr>,
-    as_token: Token![as],
-    ty: Box<Type>,
+    pub expr: Box<Expr>,
+    pub as_token: Token![as],
+    pub ty: Box<Type>,
+}
+
+impl ToTokens for ExprCast {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.expr.to_tokens(tokens);
+        self.as_token.to_tokens(tokens);
+        self.ty.to_tokens(tokens);
+    }
 }
 
 #[derive(Debug)]
 pub struct ExprType {
-    expr: Box<Expr>,
-    colon_token: Token![:],
-    ty: Box<Type>,
+    pub expr: Box<Expr>,
+    pub colon_token: Token![:],
+    pub ty: Box<Type>,
+}
+
+impl ToTokens for ExprType {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        // type ascription is not stable in Rust
+        let expr = &self.expr;
+        let ty = &self.ty;
+        tokens.extend(quote! {
+            core::convert::identity::<#ty>(#expr)
+        });
+    }
 }
 
 #[derive(Debug)]
@@ -546,21 +871,20 @@ pub struct ExprField {
     pub member: Member,
 }
 
+impl ToTokens for ExprField {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.base.to_tokens(tokens);
+        self.dot_token.to_tokens(tokens);
+        self.member.to_tokens(tokens);
+    }
+}
+
 #[derive(Debug)]
 pub struct ExprUnary {
     pub op: UnOp,
     pub expr: Box<Expr>,
 }
 
-impl Parse for ExprUnary {
-    fn parse(input: ParseStream) -> Result<Self> {
-        Ok(ExprUnary {
-            op: input.parse()?,
-            expr: Box::new(input.parse()?),
-        })
-    }
-}
-
 impl ToTokens for ExprUnary {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         self.op.to_tokens(tokens);
@@ -589,6 +913,15 @@ pub enum UnOp {
     Neg(Sub),
 }
 
+impl UnOp {
+    pub fn span(&self) -> Span {
+        match self {
+            UnOp::Not(op) => op.spans[0],
+            UnOp::Neg(op) => op.spans[0],
+        }
+    }
+}
+
 impl Parse for UnOp {
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
@@ -618,13 +951,37 @@ pub struct ExprList {
     pub elems: Punctuated<Expr, Token![,]>,
 }
 
-impl Parse for ExprList {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let content;
-        Ok(ExprList {
-            bracket_token: bracketed!(content in input),
-            elems: Punctuated::<Expr, Token![,]>::parse_terminated(&content)?,
-        })
+impl ToTokens for ExprList {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.bracket_token.surround(tokens, |tokens| {
+            self.elems.to_tokens(tokens);
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct ExprRepeat {
+    pub bracket_token: Bracket,
+    pub expr: Box<Expr>,
+    pub semi_token: Token![;],
+    pub len: LitInt,
+}
+
+impl ExprRepeat {
+    pub fn length(&self) -> usize {
+        // validated while parsing
+        self.len.base10_parse().unwrap_or_else(|_| unreachable!())
+    }
+}
+
+impl ToTokens for ExprRepeat {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let expr = &self.expr;
+        let semi_token = &self.semi_token;
+        let len = &self.len;
+        tokens.extend(quote! {
+            [#expr #semi_token #len]
+        });
     }
 }
 
@@ -633,10 +990,16 @@ pub struct ExprCall {
     pub func: ExprPath,
     pub paren_token: Paren,
     pub args: Punctuated<Expr, Comma>,
+    // resolved by the dependency checker
+    pub builtin: Option<Builtin>,
 }
 
 impl ToTokens for ExprCall {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let Some(builtin) = self.builtin {
+            tokens.extend(builtin.expand(&self.args));
+            return;
+        }
         self.func.to_tokens(tokens);
         self.paren_token.surround(tokens, |tokens| {
             self.args.to_tokens(tokens);
@@ -644,6 +1007,96 @@ impl ToTokens for ExprCall {
     }
 }
 
+// `Module(inputs) with { args }`, an instance of another `frp!` program whose inputs and
+// outputs are passed by position in the order of its `In` and `Out` fields
+#[derive(Debug)]
+pub struct ExprInstance {
+    pub module: Ident,
+    pub paren_token: Paren,
+    pub args: Punctuated<Expr, Comma>,
+    pub instance_args: Option<InstanceArgs>,
+    // the cell field holding the state of the instance, named in `LrfrpIR::from_ast`
+    pub cell: Ident,
+    // `None` if the only output is bound as is
+    pub outputs: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct InstanceArgs {
+    pub with_token: with,
+    pub brace_token: Brace,
+    pub fields: Punctuated<FieldValue, Comma>,
+}
+
+impl ExprInstance {
+    pub fn new(call: ExprCall, instance_args: Option<InstanceArgs>) -> Self {
+        let module: &Ident = call.func.borrow();
+        ExprInstance {
+            module: module.clone(),
+            paren_token: call.paren_token,
+            args: call.args,
+            instance_args,
+            cell: module.clone(),
+            outputs: None,
+        }
+    }
+
+    pub fn cell_field(&self) -> TokenStream {
+        let cell = &self.cell;
+        let module = &self.module;
+        quote! {
+            #cell: super::#module::FRP,
+        }
+    }
+
+    pub fn cell_initialization(&self) -> TokenStream {
+        let cell = &self.cell;
+        let module = &self.module;
+        // rustc checks the arguments against `Args` of the module, at `with { .. }`
+        let args = self.instance_args.as_ref().map(|instance_args| {
+            let fields = &instance_args.fields;
+            let mut args = quote_spanned! { instance_args.with_token.span =>
+                super::#module::Args
+            };
+            instance_args
+                .brace_token
+                .surround(&mut args, |tokens| fields.to_tokens(tokens));
+            args
+        });
+        quote! {
+            self.cell.#cell = super::#module::FRP::new(#args);
+        }
+    }
+}
+
+// the instance runs where its outputs are needed in the calculation order
+impl ToTokens for ExprInstance {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let cell = &self.cell;
+        let module = &self.module;
+        let args = &self.args;
+        // a single output is not a tuple unless it is bound as `(x,)`
+        let values = match self.outputs {
+            Some(1) => quote! { (__output.values(),) },
+            _ => quote! { __output.values() },
+        };
+        // mismatches against the `In` of the module are reported at the instance
+        let input = quote_spanned! {module.span()=>
+            super::#module::In::new(#args)
+        };
+        tokens.extend(quote! {
+            {
+                let __input = #input;
+                self.cell.#cell.run(&__input);
+                match self.cell.#cell.sample() {
+                    core::option::Option::Some(__output) => #values,
+                    core::option::Option::None => unreachable!(),
+                }
+            }
+        });
+    }
+}
+
 #[derive(Debug)]
 pub enum BinOp {
     Add(Add),
@@ -686,8 +1139,8 @@ impl Parse for BinOp {
             input.parse().map(BinOp::Ge)
         } else if input.peek(Token![+]) {
             input.parse().map(BinOp::Add)
-        // prevent from matching `-<` token
-        } else if input.peek(Token![-]) && !input.peek2(Token![<]) {
+        // prevent from matching `-<` and `->` tokens
+        } else if input.peek(Token![-]) && !input.peek2(Token![<]) && !input.peek(Token![->]) {
             input.parse().map(BinOp::Sub)
         } else if input.peek(Token![*]) {
             input.parse().map(BinOp::Mul)
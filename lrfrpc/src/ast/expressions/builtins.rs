@@ -0,0 +1,124 @@
+use super::Expr;
+use syn::punctuated::Punctuated;
+use syn::{Ident, Token};
+
+use quote::quote;
+
+use proc_macro2::TokenStream;
+
+// combinators over fixed-size arrays and events, expanded in place so that no runtime
+// support is needed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Builtin {
+    // map(f, xs) or map(f, ev)
+    Map,
+    // fold(f, init, xs)
+    Fold,
+    // zip(xs, ys)
+    Zip,
+    // when(cond, value)
+    When,
+    // hold(init, ev), whose memory is lifted into a cell in `LrfrpIR::from_ast`
+    Hold,
+    // merge(a, b), preferring `a` when both occur
+    Merge,
+    // filter(pred, ev)
+    Filter,
+}
+
+impl Builtin {
+    pub fn from_ident(ident: &Ident) -> Option<Self> {
+        if ident == "map" {
+            Some(Builtin::Map)
+        } else if ident == "fold" {
+            Some(Builtin::Fold)
+        } else if ident == "zip" {
+            Some(Builtin::Zip)
+        } else if ident == "when" {
+            Some(Builtin::When)
+        } else if ident == "hold" {
+            Some(Builtin::Hold)
+        } else if ident == "merge" {
+            Some(Builtin::Merge)
+        } else if ident == "filter" {
+            Some(Builtin::Filter)
+        } else {
+            None
+        }
+    }
+
+    pub fn arity(self) -> usize {
+        use Builtin::*;
+        match self {
+            Map | Zip | When | Hold | Merge | Filter => 2,
+            Fold => 3,
+        }
+    }
+
+    // the arguments are assumed to be checked by the type checker
+    pub fn expand(self, args: &Punctuated<Expr, Token![,]>) -> TokenStream {
+        use Builtin::*;
+        let args: Vec<_> = args.iter().collect();
+        match self {
+            // both arrays and `Option` have `map`
+            Map => {
+                let (f, xs) = (args[0], args[1]);
+                quote! {
+                    (#xs).map(#f)
+                }
+            }
+            Fold => {
+                let (f, init, xs) = (args[0], args[1], args[2]);
+                quote! {
+                    core::iter::IntoIterator::into_iter(#xs).fold(#init, #f)
+                }
+            }
+            // `[T; N]::zip` is not stable, so the elements of `ys` are paired in order
+            Zip => {
+                let (xs, ys) = (args[0], args[1]);
+                quote! {
+                    {
+                        let __ys = #ys;
+                        let mut __i = 0;
+                        (#xs).map(|__x| {
+                            let __y = __ys[__i];
+                            __i += 1;
+                            (__x, __y)
+                        })
+                    }
+                }
+            }
+            When => {
+                let (cond, value) = (args[0], args[1]);
+                quote! {
+                    if #cond {
+                        core::option::Option::Some(#value)
+                    } else {
+                        core::option::Option::None
+                    }
+                }
+            }
+            Hold => {
+                let (init, ev) = (args[0], args[1]);
+                quote! {
+                    match #ev {
+                        core::option::Option::Some(__x) => __x,
+                        core::option::Option::None => #init,
+                    }
+                }
+            }
+            Merge => {
+                let (a, b) = (args[0], args[1]);
+                quote! {
+                    (#a).or(#b)
+                }
+            }
+            Filter => {
+                let (pred, ev) = (args[0], args[1]);
+                quote! {
+                    (#ev).filter(|__x| #pred(*__x))
+                }
+            }
+        }
+    }
+}
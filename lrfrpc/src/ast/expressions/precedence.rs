@@ -5,6 +5,7 @@ use syn::Token;
 #[derive(Copy, Clone, PartialEq, PartialOrd)]
 pub enum Precedence {
     Any,
+    Init,
     Or,
     And,
     Compare,
@@ -42,6 +43,8 @@ impl Precedence {
             Precedence::of(&op)
         } else if input.peek(Token![as]) || input.peek(Token![:]) && !input.peek(Token![::]) {
             Precedence::Cast
+        } else if input.peek(Token![->]) {
+            Precedence::Init
         } else {
             Precedence::Any
         }
@@ -5,7 +5,7 @@ use syn::{LitBool, LitFloat, LitInt};
 
 use quote::ToTokens;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 
 #[derive(Debug)]
 pub enum Lit {
@@ -15,6 +15,15 @@ pub enum Lit {
 }
 
 impl Lit {
+    pub fn span(&self) -> Span {
+        use Lit::*;
+        match self {
+            Int(e) => e.span(),
+            Float(e) => e.span(),
+            Bool(e) => e.span,
+        }
+    }
+
     pub fn peeked(input: &ParseStream) -> bool {
         input.peek(LitInt) || input.peek(LitFloat) || {
             let cursor = input.cursor();
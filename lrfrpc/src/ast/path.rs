@@ -20,6 +20,8 @@ use std::ptr;
 pub enum Path {
     Segment(PathSegment),
     TypedSegment(PathSegment, types::Type),
+    // enum variant such as `Mode::Idle`
+    Qualified(PathSegment, Token![::], PathSegment),
 }
 
 impl Path {
@@ -32,18 +34,35 @@ impl Path {
                 match ptr::read(self) {
                     Segment(s) => TypedSegment(s, ty),
                     TypedSegment(s, _) => TypedSegment(s, ty),
+                    // enum variants are constants
+                    Qualified(q, c, s) => Qualified(q, c, s),
                 },
             );
         }
     }
 }
 
+impl Path {
+    pub fn is_qualified(&self) -> bool {
+        matches!(self, Path::Qualified(..))
+    }
+
+    pub fn is_local(&self) -> bool {
+        use types::{Type, TypeLifted, TypeSignal};
+        matches!(
+            self,
+            Path::TypedSegment(_, Type::Lifted(TypeLifted::Signal(TypeSignal::Local(_))))
+        )
+    }
+}
+
 impl Borrow<Ident> for Path {
     fn borrow(&self) -> &Ident {
         use Path::*;
         match self {
             Segment(ref segment) => segment.borrow(),
             TypedSegment(segment, _) => segment.borrow(),
+            Qualified(_, _, segment) => segment.borrow(),
         }
     }
 }
@@ -57,9 +76,28 @@ where
     }
 }
 
+impl Path {
+    // paths in expressions never take generic arguments, so that `a < b` is a comparison
+    pub fn parse_without_arguments(input: ParseStream) -> Result<Self> {
+        let segment = PathSegment::from(input.parse::<Ident>()?);
+        if input.peek(Token![::]) {
+            let colon2_token = input.parse()?;
+            let variant = PathSegment::from(input.parse::<Ident>()?);
+            Ok(Path::Qualified(segment, colon2_token, variant))
+        } else {
+            Ok(Path::Segment(segment))
+        }
+    }
+}
+
 impl Parse for Path {
     fn parse(input: ParseStream) -> Result<Self> {
-        Ok(Path::Segment(input.parse()?))
+        let segment = input.parse()?;
+        if input.peek(Token![::]) {
+            Ok(Path::Qualified(segment, input.parse()?, input.parse()?))
+        } else {
+            Ok(Path::Segment(segment))
+        }
     }
 }
 
@@ -68,6 +106,11 @@ impl ToTokens for Path {
         use Path::*;
         match self {
             Segment(segment) => segment.to_tokens(tokens),
+            Qualified(qualifier, colon2_token, segment) => {
+                qualifier.to_tokens(tokens);
+                colon2_token.to_tokens(tokens);
+                segment.to_tokens(tokens);
+            }
             TypedSegment(segment, ty) => {
                 let ident: &Ident = segment.borrow();
                 match ty {
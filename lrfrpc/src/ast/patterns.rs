@@ -0,0 +1,397 @@
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::token::{Brace, Bracket, Colon, Comma, Dot2, Paren, Underscore};
+use syn::Ident;
+use syn::Member;
+use syn::Result;
+use syn::Token;
+use syn::{braced, parenthesized};
+
+use super::literals::Lit;
+use super::path::Path;
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use std::borrow::Borrow;
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Pat {
+    Wild(PatWild),
+    Ident(PatIdent),
+    Struct(PatStruct),
+    TupleStruct(PatTupleStruct),
+    Path(PatPath),
+    Tuple(PatTuple),
+    Lit(PatLit),
+    List(PatList),
+    Or(PatOr),
+}
+
+impl Pat {
+    // top-level pattern of match arms, which may have alternatives
+    pub fn parse_multi(input: ParseStream) -> Result<Self> {
+        let pat: Pat = input.parse()?;
+        if !input.peek(Token![|]) || input.peek(Token![||]) {
+            return Ok(pat);
+        }
+
+        let mut cases = Punctuated::new();
+        cases.push_value(pat);
+        while input.peek(Token![|]) && !input.peek(Token![||]) {
+            cases.push_punct(input.parse()?);
+            cases.push_value(input.parse()?);
+        }
+        Ok(Pat::Or(PatOr { cases }))
+    }
+}
+
+impl Parse for Pat {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![_]) {
+            Ok(input.parse().map(Pat::Wild)?)
+        } else if Lit::peeked(&input) {
+            Ok(input.parse().map(Pat::Lit)?)
+        } else if input.peek(Ident) && input.peek2(Token![::]) {
+            let path = input.parse()?;
+            if input.peek(Paren) {
+                Ok(Pat::TupleStruct(PatTupleStruct {
+                    path,
+                    pat: input.parse()?,
+                }))
+            } else {
+                Ok(Pat::Path(PatPath { path }))
+            }
+        } else if input.peek(Ident) && input.peek2(Brace) {
+            Ok(input.parse().map(Pat::Struct)?)
+        } else if input.peek(Ident) && input.peek2(Paren) {
+            // `Some(p)` of events
+            Ok(Pat::TupleStruct(PatTupleStruct {
+                path: Path::parse_without_arguments(input)?,
+                pat: input.parse()?,
+            }))
+        } else if input.peek(Paren) {
+            let pat: PatTuple = input.parse()?;
+            // `(p)` is just a parenthesized pattern
+            if pat.front.len() == 1 && !pat.front.trailing_punct() && pat.dot2_token.is_none() {
+                Ok(pat
+                    .front
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| unreachable!()))
+            } else {
+                Ok(Pat::Tuple(pat))
+            }
+        } else if input.peek(Ident) {
+            let pat: PatIdent = input.parse()?;
+            // `None` of events is a variant rather than a binding
+            if pat.ident == "None" && pat.subpat.is_none() {
+                Ok(Pat::Path(PatPath {
+                    path: Path::from(pat.ident),
+                }))
+            } else {
+                Ok(Pat::Ident(pat))
+            }
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl ToTokens for Pat {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        use Pat::*;
+        match self {
+            Wild(p) => p.to_tokens(tokens),
+            Ident(p) => p.to_tokens(tokens),
+            Struct(p) => p.to_tokens(tokens),
+            Path(p) => p.path.to_tokens(tokens),
+            TupleStruct(p) => {
+                p.path.to_tokens(tokens);
+                p.pat.to_tokens(tokens);
+            }
+            Lit(p) => p.lit.to_tokens(tokens),
+            Tuple(p) => p.to_tokens(tokens),
+            List(p) => p.to_tokens(tokens),
+            Or(p) => p.cases.to_tokens(tokens),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PatWild {
+    pub underscore_token: Underscore,
+}
+
+impl Parse for PatWild {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let underscore_token = input.parse()?;
+        Ok(PatWild { underscore_token })
+    }
+}
+
+impl ToTokens for PatWild {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.underscore_token.to_tokens(tokens);
+    }
+}
+
+#[derive(Debug)]
+pub struct PatIdent {
+    pub ident: Ident,
+    pub subpat: Option<(Token![@], Box<Pat>)>,
+}
+
+impl Parse for PatIdent {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(PatIdent {
+            ident: input.parse()?,
+            subpat: {
+                if input.peek(Token![@]) {
+                    let at_token = input.parse()?;
+                    let pat = input.parse()?;
+                    Some((at_token, Box::new(pat)))
+                } else {
+                    None
+                }
+            },
+        })
+    }
+}
+
+impl ToTokens for PatIdent {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.ident.to_tokens(tokens);
+        if let Some((at_token, pat)) = &self.subpat {
+            at_token.to_tokens(tokens);
+            pat.to_tokens(tokens);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PatStruct {
+    pub path: Path,
+    pub brace_token: Brace,
+    pub fields: Punctuated<FieldPat, Comma>,
+    pub dot2_token: Option<Dot2>,
+}
+
+impl Parse for PatStruct {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let path = Path::parse_without_arguments(input)?;
+        let brace_token = braced!(content in input);
+        let mut fields = Punctuated::new();
+        let mut dot2_token = None;
+        while !content.is_empty() {
+            if content.peek(Token![..]) {
+                dot2_token = Some(content.parse()?);
+                break;
+            }
+            fields.push_value(content.parse()?);
+            if content.is_empty() {
+                break;
+            }
+            fields.push_punct(content.parse()?);
+        }
+        Ok(PatStruct {
+            path,
+            brace_token,
+            fields,
+            dot2_token,
+        })
+    }
+}
+
+impl ToTokens for PatStruct {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        self.brace_token.surround(tokens, |tokens| {
+            self.fields.to_tokens(tokens);
+            if let Some(dot2_token) = &self.dot2_token {
+                if !self.fields.empty_or_trailing() {
+                    tokens.extend(quote! { , });
+                }
+                dot2_token.to_tokens(tokens);
+            }
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct FieldPat {
+    pub member: Member,
+    pub colon_token: Option<Colon>,
+    pub pat: Box<Pat>,
+}
+
+// `field: pat` or the shorthand `field` binding the field to its name
+impl Parse for FieldPat {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        if input.peek(Token![:]) {
+            Ok(FieldPat {
+                member: Member::Named(ident),
+                colon_token: Some(input.parse()?),
+                pat: Box::new(input.parse()?),
+            })
+        } else {
+            Ok(FieldPat {
+                member: Member::Named(ident.clone()),
+                colon_token: None,
+                pat: Box::new(Pat::Ident(PatIdent {
+                    ident,
+                    subpat: None,
+                })),
+            })
+        }
+    }
+}
+
+impl ToTokens for FieldPat {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match &self.colon_token {
+            Some(colon_token) => {
+                self.member.to_tokens(tokens);
+                colon_token.to_tokens(tokens);
+                self.pat.to_tokens(tokens);
+            }
+            None => self.pat.to_tokens(tokens),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PatTupleStruct {
+    pub path: Path,
+    pub pat: PatTuple,
+}
+
+#[derive(Debug)]
+pub struct PatTuple {
+    pub paren_token: Paren,
+    pub front: Punctuated<Pat, Comma>,
+    pub dot2_token: Option<Dot2>,
+    pub comma_token: Option<Comma>,
+    pub back: Punctuated<Pat, Comma>,
+}
+
+// the elements before and after `..`, if any
+impl Parse for PatTuple {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let paren_token = parenthesized!(content in input);
+        let mut front = Punctuated::new();
+        let mut dot2_token = None;
+        let mut comma_token = None;
+        let mut back = Punctuated::new();
+        while !content.is_empty() {
+            if content.peek(Token![..]) {
+                dot2_token = Some(content.parse()?);
+                if !content.is_empty() {
+                    comma_token = Some(content.parse()?);
+                    back = content.parse_terminated(Pat::parse)?;
+                }
+                break;
+            }
+            front.push_value(content.parse()?);
+            if content.is_empty() {
+                break;
+            }
+            front.push_punct(content.parse()?);
+        }
+        Ok(PatTuple {
+            paren_token,
+            front,
+            dot2_token,
+            comma_token,
+            back,
+        })
+    }
+}
+
+impl ToTokens for PatTuple {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.paren_token.surround(tokens, |tokens| {
+            self.front.to_tokens(tokens);
+            match &self.dot2_token {
+                Some(dot2_token) => {
+                    dot2_token.to_tokens(tokens);
+                    self.comma_token.to_tokens(tokens);
+                    self.back.to_tokens(tokens);
+                }
+                None if self.front.len() == 1 && !self.front.trailing_punct() => {
+                    tokens.extend(quote! { , });
+                }
+                None => {}
+            }
+        });
+    }
+}
+
+impl PatTuple {
+    // the patterns of the elements which are not matched by `..`
+    pub fn elems(&self) -> impl Iterator<Item = &Pat> {
+        self.front.iter().chain(self.back.iter())
+    }
+
+    pub fn elems_mut(&mut self) -> impl Iterator<Item = &mut Pat> {
+        self.front.iter_mut().chain(self.back.iter_mut())
+    }
+}
+
+#[derive(Debug)]
+pub struct PatPath {
+    pub path: Path,
+}
+
+impl Borrow<Ident> for PatPath {
+    fn borrow(&self) -> &Ident {
+        self.path.borrow()
+    }
+}
+
+#[derive(Debug)]
+pub struct PatLit {
+    pub lit: Lit,
+}
+
+impl Parse for PatLit {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(PatLit {
+            lit: input.parse()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct PatOr {
+    pub cases: Punctuated<Pat, Token![|]>,
+}
+
+#[derive(Debug)]
+pub struct PatList {
+    pub bracket_token: Bracket,
+    pub front: Punctuated<Pat, Comma>,
+    pub middle: Option<Box<Pat>>,
+    pub dot2_token: Option<Dot2>,
+    pub comma_token: Option<Comma>,
+    pub back: Punctuated<Pat, Comma>,
+}
+
+impl ToTokens for PatList {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.bracket_token.surround(tokens, |tokens| {
+            self.front.to_tokens(tokens);
+            if let Some(middle) = &self.middle {
+                middle.to_tokens(tokens);
+            }
+            self.dot2_token.to_tokens(tokens);
+            self.comma_token.to_tokens(tokens);
+            self.back.to_tokens(tokens);
+        });
+    }
+}
@@ -7,7 +7,7 @@ use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::token::{Bracket, Paren, Underscore};
 use syn::{bracketed, parenthesized};
-use syn::{Ident, Result, Token};
+use syn::{Ident, LitInt, Result, Token};
 
 use quote::{quote, ToTokens};
 
@@ -42,9 +42,17 @@ impl Parse for Type {
             Ok(input.parse().map(Type::Path)?)
         } else if lookahead.peek(Bracket) {
             let content;
+            let bracket_token = bracketed!(content in input);
+            let ty = content.parse()?;
+            let semi_token = content.parse()?;
+            let len: LitInt = content.parse()?;
+            // only fixed-size arrays have a statically known layout
+            len.base10_parse::<usize>()?;
             Ok(Type::List(TypeList {
-                bracket_token: bracketed!(content in input),
-                ty: content.parse()?,
+                bracket_token,
+                ty,
+                semi_token,
+                len,
             }))
         } else if lookahead.peek(Paren) {
             let content;
@@ -98,8 +106,8 @@ impl ToTokens for Type {
 
 #[derive(Clone, Debug)]
 pub struct TypeParen {
-    paren_token: Paren,
-    ty: Box<Type>,
+    pub paren_token: Paren,
+    pub ty: Box<Type>,
 }
 
 impl fmt::Display for TypeParen {
@@ -120,21 +128,31 @@ impl ToTokens for TypeParen {
 
 #[derive(Clone, Debug)]
 pub struct TypeList {
-    bracket_token: Bracket,
-    ty: Box<Type>,
+    pub bracket_token: Bracket,
+    pub ty: Box<Type>,
+    pub semi_token: Token![;],
+    pub len: LitInt,
+}
+
+impl TypeList {
+    pub fn length(&self) -> usize {
+        // validated while parsing
+        self.len.base10_parse().unwrap_or_else(|_| unreachable!())
+    }
 }
 
 impl fmt::Display for TypeList {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "[{}]", self.ty)
+        write!(f, "[{}; {}]", self.ty, self.length())
     }
 }
 
 impl ToTokens for TypeList {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ty = &self.ty;
+        let len = &self.len;
         let type_list = quote! {
-            [#ty]
+            [#ty; #len]
         };
         tokens.extend(type_list);
     }
@@ -142,13 +160,23 @@ impl ToTokens for TypeList {
 
 #[derive(Clone, Debug)]
 pub struct TypeTuple {
-    paren_token: Paren,
-    elems: Punctuated<Type, Token![,]>,
+    pub paren_token: Paren,
+    pub elems: Punctuated<Type, Token![,]>,
 }
 
 impl fmt::Display for TypeTuple {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({:?})", self.elems)
+        write!(f, "(")?;
+        for (i, ty) in self.elems.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", ty)?;
+        }
+        if self.elems.len() == 1 {
+            write!(f, ",")?;
+        }
+        write!(f, ")")
     }
 }
 
@@ -164,7 +192,7 @@ impl ToTokens for TypeTuple {
 
 #[derive(Clone, Debug)]
 pub struct TypeInfer {
-    underscore_token: Underscore,
+    pub underscore_token: Underscore,
 }
 
 impl fmt::Display for TypeInfer {
@@ -185,13 +213,28 @@ impl ToTokens for TypeInfer {
 
 #[derive(Clone, Debug)]
 pub struct TypePath {
-    path: path::Path,
+    pub path: path::Path,
 }
 
 impl fmt::Display for TypePath {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let ident: &Ident = self.path.borrow();
-        write!(f, "{}", ident.to_string())
+        write!(f, "{}", ident)?;
+        if let path::Path::Segment(path::PathSegment {
+            arguments: path::PathArguments::AngleBracketed(ref args),
+            ..
+        }) = self.path
+        {
+            write!(f, "<")?;
+            for (i, ty) in args.args.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", ty)?;
+            }
+            write!(f, ">")?;
+        }
+        Ok(())
     }
 }
 
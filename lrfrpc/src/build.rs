@@ -0,0 +1,43 @@
+//! Compiling `.lrfrp` files from build scripts.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     lrfrpc::build::compile("src/counter.lrfrp");
+//! }
+//!
+//! // src/main.rs
+//! include!(concat!(env!("OUT_DIR"), "/counter.rs"));
+//! ```
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Compiles the `.lrfrp` file at `path` into `$OUT_DIR/<file stem>.rs` and returns the
+/// path of the generated file.
+///
+/// Cargo is told to rerun the build script when the file changes. The diagnostics are
+/// printed and the build script panics if the program has errors.
+pub fn compile<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let code = match super::compile_file(path) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            panic!("failed to compile `{}`", path.display());
+        }
+    };
+
+    let out_dir = env::var_os("OUT_DIR").expect("`OUT_DIR` is set only for build scripts");
+    let stem = path
+        .file_stem()
+        .expect("expected a path to a `.lrfrp` file");
+    let mut output = PathBuf::from(out_dir).join(stem);
+    output.set_extension("rs");
+    fs::write(&output, code)
+        .unwrap_or_else(|e| panic!("failed to write `{}`: {}", output.display(), e));
+    output
+}
@@ -0,0 +1,914 @@
+use super::ast::{self, FrpStmtArrows, ItemDeclaration, ItemProbe};
+use super::lrfrp_ir::{LrfrpIR, Property};
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use std::borrow::Borrow;
+
+pub mod derive;
+
+pub fn codegen(lrfrp_ir: LrfrpIR) -> TokenStream {
+    let records_violations = lrfrp_ir
+        .properties
+        .iter()
+        .any(|property| property.is_assert)
+        && matches!(lrfrp_ir.module.assert_policy, ast::AssertPolicy::Record);
+    let schema_version = schema_version(&lrfrp_ir, records_violations);
+    let LrfrpIR {
+        module,
+        input,
+        output,
+        args,
+        probe,
+        properties,
+        declarations,
+        body,
+        warnings,
+    } = lrfrp_ir;
+
+    let module_name = &module.name;
+
+    let args_field = args.as_ref().map(|_| {
+        quote! {
+            args: Args,
+        }
+    });
+
+    let args_initialization = args.as_ref().map(|_| {
+        quote! {
+            args,
+        }
+    });
+
+    let repr_c = if module.is_ffi() {
+        Some(quote! { #[repr(C)] })
+    } else {
+        None
+    };
+    let args_definition = args.as_ref().map(|args| {
+        quote! {
+            #repr_c
+            #args
+        }
+    });
+    let probe_definition = probe.as_ref().map(|probe| {
+        quote! {
+            #repr_c
+            #probe
+        }
+    });
+
+    let probe_field = probe.as_ref().map(|_| {
+        quote! {
+            probe: Probe,
+        }
+    });
+    let probe_initialization = probe.as_ref().map(|_| {
+        quote! {
+            probe: Probe::default(),
+        }
+    });
+    let probe_reset = probe.as_ref().map(|_| {
+        quote! {
+            self.probe = Probe::default();
+        }
+    });
+    let probe_accessor = probe.as_ref().map(|_| {
+        quote! {
+            /// Returns the probed signals and cells of the last instant, or `None`
+            /// before the first `run`.
+            #[inline]
+            pub fn probe(&self) -> core::option::Option<&Probe> {
+                if self.running {
+                    Some(&self.probe)
+                } else {
+                    None
+                }
+            }
+        }
+    });
+    let probe_updates = probe_updates(&probe, &body.arrows);
+
+    let (violations_definition, assert_checks) = assert_checks(&module, &properties);
+    let (violations_field, violations_initialization, violations_reset, violations_accessor) =
+        if records_violations {
+            (
+                Some(quote! { violations: Violations, }),
+                Some(quote! { violations: Violations::default(), }),
+                Some(quote! { self.violations = Violations::default(); }),
+                Some(quote! {
+                    /// Returns the `assert` items failed since the program was created or
+                    /// reset.
+                    #[allow(dead_code)]
+                    #[inline]
+                    pub fn violations(&self) -> Violations {
+                        self.violations
+                    }
+
+                    /// Forgets the failed `assert` items.
+                    #[allow(dead_code)]
+                    #[inline]
+                    pub fn clear_violations(&mut self) {
+                        self.violations = Violations::default();
+                    }
+                }),
+            )
+        } else {
+            (None, None, None, None)
+        };
+
+    // programs without `Args` are reset with `()` through `FrpProgram`
+    let (reset_with, trait_reset_with) = match args {
+        Some(_) => (
+            Some(quote! {
+                #[inline]
+                pub fn reset_with(&mut self, args: Args) {
+                    self.args = args;
+                    self.reset();
+                }
+            }),
+            quote! { FRP::reset_with(self, args) },
+        ),
+        None => (None, quote! { FRP::reset(self) }),
+    };
+
+    // programs without `Args` take `()` through `FrpProgram`
+    let (args_ty, args_pat) = match args {
+        Some(_) => (quote! { Args }, quote! { args }),
+        None => (quote! { () }, quote! { _ }),
+    };
+
+    // positional access for the programs instantiating this one
+    let input_constructor = {
+        let params = input.fields.iter();
+        let idents = input.fields.iter().map(|field| &field.ident);
+        quote! {
+            #[allow(dead_code)]
+            impl In {
+                #[inline]
+                pub fn new(#(#params),*) -> Self {
+                    In { #(#idents),* }
+                }
+            }
+        }
+    };
+    let output_values = {
+        let tys: Vec<_> = output.fields.iter().map(|field| &field.ty).collect();
+        let idents: Vec<_> = output.fields.iter().map(|field| &field.ident).collect();
+        let (ty, values) = if output.fields.len() == 1 {
+            (quote! { #(#tys)* }, quote! { #(self.#idents)* })
+        } else {
+            (quote! { (#(#tys),*) }, quote! { (#(self.#idents),*) })
+        };
+        quote! {
+            #[allow(dead_code)]
+            impl Out {
+                #[inline]
+                pub fn values(&self) -> #ty {
+                    #values
+                }
+            }
+        }
+    };
+
+    let state_args_field = args.as_ref().map(|_| {
+        quote! {
+            pub args: Args,
+        }
+    });
+    let state_probe_field = probe.as_ref().map(|_| {
+        quote! {
+            pub probe: Probe,
+        }
+    });
+    let state_violations_field = if records_violations {
+        Some(quote! {
+            pub violations: Violations,
+        })
+    } else {
+        None
+    };
+    let (snapshot_violations, restore_violations) = if records_violations {
+        (
+            quote! { violations: self.violations, },
+            quote! { self.violations = state.violations; },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+    let (snapshot_probe, restore_probe) = match probe {
+        Some(_) => (
+            quote! { probe: self.probe.clone(), },
+            quote! { self.probe = state.probe; },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+    let (snapshot_args, restore_args) = match args {
+        Some(_) => (
+            quote! { args: self.args.clone(), },
+            quote! { self.args = state.args; },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
+    let instances = body.instances();
+    let declarations = declarations.iter().map(|declaration| match declaration {
+        ItemDeclaration::Fn(_) => quote! { #declaration },
+        _ => quote! { #repr_c #declaration },
+    });
+    let ffi_functions = if module.is_ffi() {
+        Some(ffi_functions(
+            &module.name.to_string(),
+            args.is_some(),
+            probe.is_some(),
+            records_violations,
+        ))
+    } else {
+        None
+    };
+
+    let derive_serde = derive::serde();
+    let import_serde = if cfg!(feature = "serde") {
+        Some(quote! {
+            use serde::{Deserialize, Serialize};
+        })
+    } else {
+        None
+    };
+    let serde_ring = body.arrows.serde_ring();
+    let cell_definition = body.arrows.cell_definition(&instances);
+
+    let step = step(
+        &module.name.to_string(),
+        &properties,
+        &body.dependencies,
+        &probe_updates,
+        &body.arrows,
+    );
+    // `step` takes the tracer of `run_traced` only with the `trace` feature
+    let no_tracer = if cfg!(feature = "trace") {
+        Some(quote! { , None })
+    } else {
+        None
+    };
+
+    // the `assert` items are checked after `step`, which is all of `run` without them
+    let run_step = |tracer: Option<TokenStream>| {
+        if assert_checks.is_empty() {
+            quote! {
+                self.step(input #tracer);
+            }
+        } else {
+            quote! {
+                let violated = self.step(input #tracer);
+                #assert_checks
+            }
+        }
+    };
+    let run = run_step(no_tracer.clone());
+
+    let run_traced = if cfg!(feature = "trace") {
+        let run_traced = run_step(Some(quote! { , Some(tracer) }));
+        Some(quote! {
+            /// Runs as `run` does, passing the inputs, local signals, outputs and cells of
+            /// the instant to `tracer`.
+            #[allow(dead_code)]
+            pub fn run_traced(&mut self, input: &In, tracer: &mut dyn ::lrfrp::trace::Tracer) {
+                #run_traced
+            }
+        })
+    } else {
+        None
+    };
+
+    let (run_checked, impl_check) = if cfg!(feature = "check") {
+        let (run_checked, impl_check) = run_checked(&properties, &no_tracer);
+        (Some(run_checked), Some(impl_check))
+    } else {
+        (None, None)
+    };
+
+    let cell_initializations = body.arrows.cell_initializations(&instances);
+
+    let token_stream = quote! {
+        #[allow(non_snake_case)]
+        mod #module_name {
+            #[allow(dead_code)]
+            pub type Event<T> = core::option::Option<T>;
+            #import_serde
+
+            #repr_c
+            #input
+            #input_constructor
+            #repr_c
+            #output
+            #output_values
+            #args_definition
+            #probe_definition
+            #violations_definition
+            #cell_definition
+
+            #(#declarations)*
+            #(#warnings)*
+
+            #serde_ring
+
+            /// Identifies the layout of `State`, which `restore` checks.
+            pub const SCHEMA_VERSION: u64 = #schema_version;
+
+            /// The whole state of `FRP`, as returned by `snapshot`.
+            #[derive(Clone)]
+            #derive_serde
+            pub struct State {
+                pub version: u64,
+                pub running: bool,
+                pub output: Out,
+                #state_args_field
+                #state_probe_field
+                #state_violations_field
+                pub cell: Cell,
+            }
+
+            #repr_c
+            #[derive(Clone, Default)]
+            #derive_serde
+            pub struct FRP {
+                running: bool,
+                output: Out,
+                #args_field
+                #probe_field
+                #violations_field
+                cell: Cell,
+            }
+
+            impl FRP {
+                #[inline]
+                pub fn new(#args_field) -> Self {
+                    let mut frp = FRP {
+                        running: false,
+                        output: Out::default(),
+                        #args_initialization
+                        #probe_initialization
+                        #violations_initialization
+                        cell: Cell::default(),
+                    };
+                    frp.cell_initializations();
+                    frp
+                }
+
+                #[inline]
+                fn cell_initializations(&mut self) {
+                    #cell_initializations
+                }
+
+                #[inline]
+                pub fn sample(&self) -> core::option::Option<&Out> {
+                    if self.running {
+                        Some(&self.output)
+                    } else {
+                        None
+                    }
+                }
+
+                #probe_accessor
+                #violations_accessor
+
+                pub fn run(&mut self, input: &In) {
+                    #run
+                }
+
+                #step
+
+                #run_traced
+
+                #run_checked
+
+                // in place, so that programs embedded in others are not moved. The
+                // cells go back to their defaults first, which resets the ring positions.
+                #[inline]
+                pub fn reset(&mut self) {
+                    self.running = false;
+                    self.output = Out::default();
+                    #probe_reset
+                    #violations_reset
+                    self.cell = Cell::default();
+                    self.cell_initializations();
+                }
+
+                #reset_with
+
+                pub fn snapshot(&self) -> State {
+                    State {
+                        version: SCHEMA_VERSION,
+                        running: self.running,
+                        output: self.output.clone(),
+                        #snapshot_args
+                        #snapshot_probe
+                        #snapshot_violations
+                        cell: self.cell.clone(),
+                    }
+                }
+
+                pub fn restore(&mut self, state: State) -> Result<(), ::lrfrp::VersionMismatch> {
+                    if state.version != SCHEMA_VERSION {
+                        return Err(::lrfrp::VersionMismatch {
+                            expected: SCHEMA_VERSION,
+                            found: state.version,
+                        });
+                    }
+                    self.running = state.running;
+                    self.output = state.output;
+                    #restore_args
+                    #restore_probe
+                    #restore_violations
+                    self.cell = state.cell;
+                    Ok(())
+                }
+            }
+
+            impl ::lrfrp::FrpProgram for FRP {
+                type In = In;
+                type Out = Out;
+                type Args = #args_ty;
+                type State = State;
+
+                #[inline]
+                fn new(#args_pat: Self::Args) -> Self {
+                    FRP::new(#args_initialization)
+                }
+
+                #[inline]
+                fn run(&mut self, input: &In) {
+                    FRP::run(self, input)
+                }
+
+                #[inline]
+                fn sample(&self) -> core::option::Option<&Out> {
+                    FRP::sample(self)
+                }
+
+                #[inline]
+                fn reset(&mut self) {
+                    FRP::reset(self)
+                }
+
+                #[inline]
+                fn reset_with(&mut self, #args_pat: Self::Args) {
+                    #trait_reset_with
+                }
+
+                #[inline]
+                fn snapshot(&self) -> State {
+                    FRP::snapshot(self)
+                }
+
+                #[inline]
+                fn restore(&mut self, state: State) -> Result<(), ::lrfrp::VersionMismatch> {
+                    FRP::restore(self, state)
+                }
+            }
+
+            #impl_check
+
+            #ffi_functions
+        }
+    };
+
+    #[cfg(feature = "print-codegen")]
+    print_codegen(&token_stream);
+
+    token_stream
+}
+
+// FNV-1a of the types making up `State`, combined with the versions of the instantiated
+// programs. It only needs to be stable across builds of the same program.
+fn schema_version(lrfrp_ir: &LrfrpIR, records_violations: bool) -> TokenStream {
+    let LrfrpIR {
+        input,
+        output,
+        args,
+        probe,
+        declarations,
+        body,
+        ..
+    } = lrfrp_ir;
+    let instances = body.instances();
+
+    let mut schema = TokenStream::new();
+    let input_fields = input.fields.iter();
+    let output_fields = output.fields.iter();
+    let args_fields = args.iter().flat_map(|args| args.fields.iter());
+    let probe_fields = probe.iter().flat_map(|probe| probe.fields.iter());
+    schema.extend(quote! {
+        In { #(#input_fields),* }
+        Out { #(#output_fields),* }
+        Args { #(#args_fields),* }
+        Probe { #(#probe_fields),* }
+    });
+    if records_violations {
+        schema.extend(quote! { Violations });
+    }
+    for declaration in declarations.iter() {
+        match declaration {
+            ItemDeclaration::Struct(e) => {
+                let ident = &e.ident;
+                let fields = e.fields.iter();
+                schema.extend(quote! { struct #ident { #(#fields),* } });
+            }
+            ItemDeclaration::Enum(e) => {
+                let ident = &e.ident;
+                let variants = &e.variants;
+                schema.extend(quote! { enum #ident { #variants } });
+            }
+            ItemDeclaration::Fn(_) => {}
+        }
+    }
+    for arrow in body.arrows.iter() {
+        let ident: &Ident = arrow.path.borrow();
+        let ty = &arrow.ty;
+        let count = arrow.count();
+        schema.extend(quote! { #ident: #ty; #count });
+    }
+    for instance in instances.iter() {
+        let cell = &instance.cell;
+        let module = &instance.module;
+        schema.extend(quote! { #cell: #module });
+    }
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in schema.to_string().bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    let hash = Literal::u64_suffixed(hash);
+    // the same module may be instantiated more than once
+    let rotations = (1..=instances.len() as u32).map(Literal::u32_unsuffixed);
+    let modules = instances.iter().map(|instance| &instance.module);
+    quote! {
+        #hash #(^ super::#modules::SCHEMA_VERSION.rotate_left(#rotations))*
+    }
+}
+
+// The instant shared by `run`, `run_traced` and `run_checked`, returning the violated
+// `assert` and `property` items as bits by their positions in `properties`. With the
+// `trace` feature, the signals are passed to the tracer before the cells are updated,
+// leaving out the hidden signals and cells synthesized by the compiler.
+fn step(
+    name: &str,
+    properties: &[Property],
+    dependencies: &[ast::FrpStmtDependency],
+    probe_updates: &TokenStream,
+    arrows: &FrpStmtArrows,
+) -> TokenStream {
+    let calculations = dependencies.iter();
+    let cell_updates = arrows.cell_updates();
+
+    let (tracer_param, trace) = if cfg!(feature = "trace") {
+        let locals = dependencies.iter().filter_map(|dependency| {
+            let ident: &Ident = dependency.path.borrow();
+            if dependency.path.is_local() && !ident.to_string().starts_with("__") {
+                Some(ident)
+            } else {
+                None
+            }
+        });
+        let local_names = locals.clone().map(|ident| ident.to_string());
+        let cells: Vec<&Ident> = arrows
+            .iter()
+            .map(|arrow| arrow.path.borrow())
+            .filter(|ident: &&Ident| !ident.to_string().starts_with("__"))
+            .collect();
+        let cell_names = cells.iter().map(|ident| ident.to_string());
+        (
+            Some(quote! {
+                , tracer: core::option::Option<&mut dyn ::lrfrp::trace::Tracer>
+            }),
+            Some(quote! {
+                if let Some(tracer) = tracer {
+                    use ::lrfrp::trace::Trace;
+
+                    tracer.begin();
+                    tracer.enter(#name);
+                    input.trace("In", tracer);
+                    #(#locals.trace(#local_names, tracer);)*
+                    self.output.trace("Out", tracer);
+                    tracer.enter("Cell");
+                    #(self.cell.#cells.trace(#cell_names, tracer);)*
+                    tracer.exit();
+                    tracer.exit();
+                    tracer.end();
+                }
+            }),
+        )
+    } else {
+        (None, None)
+    };
+
+    let violated = if properties.is_empty() {
+        quote! { 0 }
+    } else {
+        let signals = properties.iter().map(|property| &property.signal);
+        let bits = (0..properties.len()).map(|i| Literal::u64_suffixed(1 << i));
+        quote! {
+            let mut violated = 0;
+            #(
+                if !#signals {
+                    violated |= #bits;
+                }
+            )*
+            violated
+        }
+    };
+
+    quote! {
+        #[inline]
+        fn step(&mut self, input: &In #tracer_param) -> u64 {
+            self.running |= true;
+            #(#calculations)*
+            #trace
+            let violated = {
+                #violated
+            };
+            #probe_updates
+            #cell_updates
+            violated
+        }
+    }
+}
+
+// `run` returning the violated properties as bits, with the implementation of
+// `lrfrp::check::Check`
+fn run_checked(
+    properties: &[Property],
+    no_tracer: &Option<TokenStream>,
+) -> (TokenStream, TokenStream) {
+    let names = properties.iter().map(|property| &property.name);
+    let run_checked = quote! {
+        /// Runs as `run` does, except for failing `assert` items, returning the properties
+        /// violated at the instant as bits by their positions in
+        /// `lrfrp::check::Check::PROPERTIES`.
+        #[allow(dead_code)]
+        pub fn run_checked(&mut self, input: &In) -> u64 {
+            self.step(input #no_tracer)
+        }
+    };
+    let impl_check = quote! {
+        impl ::lrfrp::check::StateKey for FRP {
+            #[inline]
+            fn write_key(&self, key: &mut ::lrfrp::check::Vec<u8>) {
+                ::lrfrp::check::StateKey::write_key(&self.cell, key);
+            }
+        }
+
+        impl ::lrfrp::check::Check for FRP {
+            const PROPERTIES: &'static [&'static str] = &[#(#names),*];
+
+            #[inline]
+            fn run_checked(&mut self, input: &In) -> u64 {
+                FRP::run_checked(self, input)
+            }
+        }
+    };
+    (run_checked, impl_check)
+}
+
+// `Violations` of the `assert` items, and the checks of the bits returned by `step`
+// applying the policy of the module to them
+fn assert_checks(
+    module: &ast::ItemMod,
+    properties: &[Property],
+) -> (Option<TokenStream>, TokenStream) {
+    let asserts: Vec<_> = properties
+        .iter()
+        .filter(|property| property.is_assert)
+        .collect();
+    if asserts.is_empty() {
+        return (None, TokenStream::new());
+    }
+
+    let names = asserts.iter().map(|property| &property.name);
+    let derive_debug = if cfg!(feature = "impl-debug") {
+        Some(quote! { #[derive(Debug)] })
+    } else {
+        None
+    };
+    let derive_serde = derive::serde();
+    let definition = quote! {
+        /// The failed `assert` items, as bits by their positions in `Violations::NAMES`.
+        #derive_debug
+        #[derive(Clone, Copy, Default, PartialEq, Eq)]
+        #derive_serde
+        pub struct Violations(pub u64);
+
+        #[allow(dead_code)]
+        impl Violations {
+            /// The conditions of the `assert` items in the order of their declarations.
+            pub const NAMES: &'static [&'static str] = &[#(#names),*];
+
+            #[inline]
+            pub fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// The conditions of the failed `assert` items.
+            pub fn names(self) -> impl Iterator<Item = &'static str> {
+                Self::NAMES
+                    .iter()
+                    .enumerate()
+                    .filter(move |(i, _)| self.0 & (1 << i) != 0)
+                    .map(|(_, name)| *name)
+            }
+        }
+    };
+
+    // the bits of `step` are by the positions among every property
+    let positions = properties
+        .iter()
+        .enumerate()
+        .filter(|(_, property)| property.is_assert)
+        .map(|(i, _)| Literal::u64_suffixed(1 << i));
+    let bits = (0..asserts.len()).map(|i| Literal::u64_suffixed(1 << i));
+    let policy = match &module.assert_policy {
+        ast::AssertPolicy::Panic => {
+            let module_name = module.name.to_string();
+            quote! {
+                if let Some(name) = violations.names().next() {
+                    panic!("assertion `{}` failed in `{}`", name, #module_name);
+                }
+            }
+        }
+        ast::AssertPolicy::Record => quote! {
+            self.violations.0 |= violations.0;
+        },
+        ast::AssertPolicy::Hook(path) => {
+            // relative paths are written from the module containing the program
+            let relative = path.leading_colon.is_none()
+                && !["crate", "self", "super"]
+                    .iter()
+                    .any(|keyword| path.segments[0].ident == keyword);
+            let prefix = if relative {
+                Some(quote! { super:: })
+            } else {
+                None
+            };
+            quote! {
+                if !violations.is_empty() {
+                    #prefix #path(violations);
+                }
+            }
+        }
+    };
+    let checks = if asserts.len() == properties.len() {
+        quote! {
+            let violations = Violations(violated);
+            #policy
+        }
+    } else {
+        quote! {
+            let mut violations = Violations(0);
+            #(
+                if violated & #positions != 0 {
+                    violations.0 |= #bits;
+                }
+            )*
+            #policy
+        }
+    };
+    (Some(definition), checks)
+}
+
+// the probed values are copied before the cells are updated, so that the cells hold
+// the values visible at the instant
+fn probe_updates(probe: &Option<ItemProbe>, arrows: &FrpStmtArrows) -> TokenStream {
+    let cells: Vec<&Ident> = arrows.iter().map(|arrow| arrow.path.borrow()).collect();
+    let updates = probe
+        .iter()
+        .flat_map(|probe| probe.fields.iter())
+        .map(|field| {
+            let ident = &field.ident;
+            if cells.contains(&ident) {
+                quote! { self.probe.#ident = self.cell.#ident; }
+            } else {
+                quote! { self.probe.#ident = #ident; }
+            }
+        });
+    quote! { #(#updates)* }
+}
+
+// `FRP` is opaque to C, which allocates `<name>_size()` bytes for it
+fn ffi_functions(
+    name: &str,
+    has_args: bool,
+    has_probe: bool,
+    records_violations: bool,
+) -> TokenStream {
+    let new = format_ident!("{}_new", name);
+    let run = format_ident!("{}_run", name);
+    let sample = format_ident!("{}_sample", name);
+    let size = format_ident!("{}_size", name);
+    let probe = if has_probe {
+        let probe = format_ident!("{}_probe", name);
+        Some(quote! {
+            /// Returns the probed values of the last instant, or null before the first run.
+            ///
+            /// # Safety
+            ///
+            /// `frp` must have been initialized by `new`.
+            #[no_mangle]
+            pub unsafe extern "C" fn #probe(frp: *const FRP) -> *const Probe {
+                match (*frp).probe() {
+                    Some(probe) => probe,
+                    None => core::ptr::null(),
+                }
+            }
+        })
+    } else {
+        None
+    };
+    let violations = if records_violations {
+        let violations = format_ident!("{}_violations", name);
+        Some(quote! {
+            /// Returns the `assert` items failed since the program was initialized or
+            /// reset, as bits.
+            ///
+            /// # Safety
+            ///
+            /// `frp` must have been initialized by `new`.
+            #[no_mangle]
+            pub unsafe extern "C" fn #violations(frp: *const FRP) -> u64 {
+                (*frp).violations().0
+            }
+        })
+    } else {
+        None
+    };
+    let (args_param, args) = if has_args {
+        (quote! { , args: *const Args }, quote! { (*args).clone() })
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    quote! {
+        /// Initializes the program at `frp`.
+        ///
+        /// # Safety
+        ///
+        /// `frp` must point to `size` writable bytes aligned for any type, and `args`
+        /// must point to valid arguments.
+        #[no_mangle]
+        pub unsafe extern "C" fn #new(frp: *mut FRP #args_param) {
+            core::ptr::write(frp, FRP::new(#args));
+        }
+
+        /// Calculates the outputs of the next instant from `input`.
+        ///
+        /// # Safety
+        ///
+        /// `frp` must have been initialized by `new`, and `input` must point to valid
+        /// inputs.
+        #[no_mangle]
+        pub unsafe extern "C" fn #run(frp: *mut FRP, input: *const In) {
+            (*frp).run(&*input);
+        }
+
+        /// Returns the outputs of the last instant, or null before the first run.
+        ///
+        /// # Safety
+        ///
+        /// `frp` must have been initialized by `new`.
+        #[no_mangle]
+        pub unsafe extern "C" fn #sample(frp: *const FRP) -> *const Out {
+            match (*frp).sample() {
+                Some(output) => output,
+                None => core::ptr::null(),
+            }
+        }
+
+        #probe
+
+        #violations
+
+        /// Returns the size in bytes of `FRP`.
+        #[no_mangle]
+        pub extern "C" fn #size() -> usize {
+            core::mem::size_of::<FRP>()
+        }
+    }
+}
+
+#[cfg(feature = "print-codegen")]
+fn print_codegen(token_stream: &TokenStream) {
+    use ::rustfmt::config::Config;
+    use ::rustfmt::Input;
+    use std::io;
+
+    let input = Input::Text(token_stream.to_string());
+    let mut output = io::stderr();
+    let res = rustfmt::format_input(input, &Config::default(), Some(&mut output));
+    if let Ok((_, file_map, _)) = res {
+        eprintln!(r#"{}"#, file_map[0].1);
+    } else {
+        eprintln!("formatting generated codes failed");
+    }
+}
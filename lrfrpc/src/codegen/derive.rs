@@ -0,0 +1,533 @@
+// The impls of the traits of `lrfrp` features for the declared structs and enums,
+// `In`, `Out`, `Args`, `Probe` and `Cell`, one function per feature. A struct is
+// walked like an enum of a single variant.
+
+use crate::ast::{Field, ItemEnum};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::Ident;
+
+// the derives of the `serde` feature, imported by `codegen` from `serde` in the user's
+// crate since `rustfmt` of `print-codegen` fails on paths in derives
+pub fn serde() -> Option<TokenStream> {
+    if cfg!(feature = "serde") {
+        Some(quote! {
+            #[derive(Serialize, Deserialize)]
+        })
+    } else {
+        None
+    }
+}
+
+enum Style {
+    Named,
+    Unnamed,
+    Unit,
+}
+
+// The fields of a struct or of an enum variant, bound to their names if they are named
+// and to `field_N` otherwise. Columns and traces are named after them.
+pub struct Fields {
+    path: TokenStream,
+    name: String,
+    style: Style,
+    bindings: Vec<Ident>,
+    names: Vec<String>,
+    tys: Vec<TokenStream>,
+}
+
+impl Fields {
+    fn named(path: TokenStream, fields: impl Iterator<Item = (Ident, TokenStream)>) -> Self {
+        let (bindings, tys): (Vec<_>, Vec<_>) = fields.unzip();
+        Fields {
+            name: path.to_string(),
+            path,
+            style: Style::Named,
+            names: bindings.iter().map(|ident| ident.to_string()).collect(),
+            bindings,
+            tys,
+        }
+    }
+
+    // `path { a, b }`, `path(field_0, field_1)` or `path`, as a pattern or a value
+    fn pat(&self) -> TokenStream {
+        let path = &self.path;
+        let bindings = &self.bindings;
+        match self.style {
+            Style::Named => quote! { #path { #(#bindings),* } },
+            _ => self.build(bindings.iter()),
+        }
+    }
+
+    // `path { .. }`, `path(..)` or `path`
+    fn wild(&self) -> TokenStream {
+        let path = &self.path;
+        match self.style {
+            Style::Named => quote! { #path { .. } },
+            Style::Unnamed => quote! { #path(..) },
+            Style::Unit => quote! { #path },
+        }
+    }
+
+    // the value of the fields from `values` in order
+    fn build<T: ToTokens>(&self, values: impl Iterator<Item = T>) -> TokenStream {
+        let path = &self.path;
+        let bindings = &self.bindings;
+        match self.style {
+            Style::Named => quote! { #path { #(#bindings: #values),* } },
+            Style::Unnamed => quote! { #path(#(#values),*) },
+            Style::Unit => quote! { #path },
+        }
+    }
+
+    // `value` for each field
+    fn all(&self, value: TokenStream) -> impl Iterator<Item = TokenStream> + '_ {
+        self.tys.iter().map(move |_| value.clone())
+    }
+}
+
+// the declared type whose traits are implemented
+pub enum Shape {
+    Struct(Fields),
+    Enum(Vec<Fields>),
+}
+
+impl Shape {
+    pub fn of_struct<'a>(ident: &impl ToTokens, fields: impl Iterator<Item = &'a Field>) -> Self {
+        let fields = fields.map(|field| (field.ident.clone(), field.ty.to_token_stream()));
+        Shape::of_fields(ident, fields)
+    }
+
+    // `Cell`, whose fields are not declared in the language
+    pub fn of_fields(
+        ident: &impl ToTokens,
+        fields: impl Iterator<Item = (Ident, TokenStream)>,
+    ) -> Self {
+        Shape::Struct(Fields::named(ident.to_token_stream(), fields))
+    }
+
+    pub fn of_enum(item: &ItemEnum) -> Self {
+        let ident = &item.ident;
+        let variants = item.variants.iter().map(|variant| {
+            let ident_variant = &variant.ident;
+            Fields {
+                path: quote! { #ident::#ident_variant },
+                name: ident_variant.to_string(),
+                style: match variant.paren_token {
+                    Some(_) => Style::Unnamed,
+                    None => Style::Unit,
+                },
+                bindings: (0..variant.fields.len())
+                    .map(|i| format_ident!("field_{}", i))
+                    .collect(),
+                names: (0..variant.fields.len()).map(|i| i.to_string()).collect(),
+                tys: variant
+                    .fields
+                    .iter()
+                    .map(|ty| ty.to_token_stream())
+                    .collect(),
+            }
+        });
+        Shape::Enum(variants.collect())
+    }
+
+    fn variants(&self) -> &[Fields] {
+        match self {
+            Shape::Struct(fields) => std::slice::from_ref(fields),
+            Shape::Enum(variants) => variants,
+        }
+    }
+}
+
+// `Trace` of the `trace` feature. Structs are scopes of their fields, while only the
+// variant of enums is traced, by its position and name.
+pub fn trace(ident: &impl ToTokens, shape: &Shape) -> Option<TokenStream> {
+    if !cfg!(feature = "trace") {
+        return None;
+    }
+    let (trace, trace_absent) = match shape {
+        Shape::Struct(fields) => {
+            let bindings = &fields.bindings;
+            let names = &fields.names;
+            let tys = &fields.tys;
+            (
+                quote! {
+                    tracer.enter(name);
+                    #(::lrfrp::trace::Trace::trace(&self.#bindings, #names, tracer);)*
+                    tracer.exit();
+                },
+                quote! {
+                    tracer.enter(name);
+                    #(<#tys as ::lrfrp::trace::Trace>::trace_absent(#names, tracer);)*
+                    tracer.exit();
+                },
+            )
+        }
+        Shape::Enum(variants) => {
+            let arms = variants.iter().enumerate().map(|(index, variant)| {
+                let index = index as u32;
+                let name = &variant.name;
+                let pat = variant.wild();
+                quote! {
+                    #pat => (#index, #name),
+                }
+            });
+            let first = &variants[0].name;
+            (
+                quote! {
+                    let (index, variant) = match self {
+                        #(#arms)*
+                    };
+                    tracer.value(name, ::lrfrp::trace::Value::Enum { index, name: variant });
+                },
+                quote! {
+                    tracer.absent(name, ::lrfrp::trace::Value::Enum { index: 0, name: #first });
+                },
+            )
+        }
+    };
+    Some(quote! {
+        impl ::lrfrp::trace::Trace for #ident {
+            fn trace(&self, name: &str, tracer: &mut dyn ::lrfrp::trace::Tracer) {
+                #trace
+            }
+
+            fn trace_absent(name: &str, tracer: &mut dyn ::lrfrp::trace::Tracer) {
+                #trace_absent
+            }
+        }
+    })
+}
+
+// `Columns` of the `replay` feature. The columns of structs are named after their
+// fields. Enums are written by the names of their variants, whose fields are spread
+// over the columns `name.Variant.N`, empty for the other variants. `In`, `Out`, `Args`
+// and `Probe` are the records of logs.
+pub fn columns(ident: &impl ToTokens, shape: &Shape, is_record: bool) -> Option<TokenStream> {
+    if !cfg!(feature = "replay") {
+        return None;
+    }
+    let (names, read, write) = match shape {
+        Shape::Struct(fields) => {
+            let bindings = &fields.bindings;
+            let names = &fields.names;
+            let tys = &fields.tys;
+            let values = tys.iter().zip(names.iter()).map(|(ty, name)| {
+                quote! {
+                    <#ty as ::lrfrp::replay::Columns>::read(
+                        &::lrfrp::replay::column_name(name, #name),
+                        row,
+                    )?
+                }
+            });
+            let value = fields.build(values);
+            (
+                quote! {
+                    #(<#tys as ::lrfrp::replay::Columns>::names(
+                        &::lrfrp::replay::column_name(name, #names),
+                        names,
+                    );)*
+                },
+                quote! {
+                    Ok(#value)
+                },
+                quote! {
+                    #(::lrfrp::replay::Columns::write(&self.#bindings, fields);)*
+                },
+            )
+        }
+        Shape::Enum(variants) => {
+            let data_variants: Vec<_> = variants
+                .iter()
+                .filter(|variant| !variant.tys.is_empty())
+                .collect();
+            let columns = data_variants.iter().map(|variant| {
+                let variant_name = &variant.name;
+                let tys = &variant.tys;
+                let indices = &variant.names;
+                quote! {
+                    let variant = ::lrfrp::replay::column_name(name, #variant_name);
+                    #(<#tys as ::lrfrp::replay::Columns>::names(
+                        &::lrfrp::replay::column_name(&variant, #indices),
+                        names,
+                    );)*
+                }
+            });
+            let reads = variants.iter().map(|variant| {
+                let variant_name = &variant.name;
+                if variant.tys.is_empty() {
+                    let value = variant.pat();
+                    return quote! { #variant_name => Ok(#value), };
+                }
+                let values = variant
+                    .tys
+                    .iter()
+                    .zip(variant.names.iter())
+                    .map(|(ty, index)| {
+                        quote! {
+                            <#ty as ::lrfrp::replay::Columns>::read(
+                                &::lrfrp::replay::column_name(&variant, #index),
+                                row,
+                            )?
+                        }
+                    });
+                let value = variant.build(values);
+                quote! {
+                    #variant_name => {
+                        let variant = ::lrfrp::replay::column_name(name, #variant_name);
+                        Ok(#value)
+                    }
+                }
+            });
+            let names = variants.iter().map(|variant| {
+                let variant_name = &variant.name;
+                let pat = variant.wild();
+                quote! { #pat => #variant_name, }
+            });
+            let writes = data_variants.iter().map(|variant| {
+                let pat = variant.pat();
+                let bindings = &variant.bindings;
+                let tys = &variant.tys;
+                let others = if variants.len() > 1 {
+                    Some(quote! {
+                        _ => {
+                            #(::lrfrp::replay::write_absent::<#tys>(fields);)*
+                        }
+                    })
+                } else {
+                    None
+                };
+                quote! {
+                    match self {
+                        #pat => {
+                            #(::lrfrp::replay::Columns::write(#bindings, fields);)*
+                        }
+                        #others
+                    }
+                }
+            });
+            (
+                quote! {
+                    names.push(name.into());
+                    #(#columns)*
+                },
+                quote! {
+                    match row.get(name)? {
+                        #(#reads)*
+                        _ => Err(row.invalid(name)),
+                    }
+                },
+                quote! {
+                    let name = match self {
+                        #(#names)*
+                    };
+                    fields.push(name.into());
+                    #(#writes)*
+                },
+            )
+        }
+    };
+    let record = if is_record {
+        Some(quote! {
+            impl ::lrfrp::replay::Record for #ident {}
+        })
+    } else {
+        None
+    };
+    Some(quote! {
+        impl ::lrfrp::replay::Columns for #ident {
+            fn names(name: &str, names: &mut ::lrfrp::replay::Vec<::lrfrp::replay::String>) {
+                #names
+            }
+
+            fn read(
+                name: &str,
+                row: &::lrfrp::replay::Row,
+            ) -> Result<Self, ::lrfrp::replay::ReplayError> {
+                #read
+            }
+
+            fn write(&self, fields: &mut ::lrfrp::replay::Vec<::lrfrp::replay::String>) {
+                #write
+            }
+        }
+        #record
+    })
+}
+
+// `Arbitrary` of the `testing` feature. Structs are shrunk a field at a time, where the
+// declared structs are `Copy`, `In`, `Out`, `Args` and `Probe` only `Clone`. The
+// variants of enums are shrunk to the ones declared before them, with default fields.
+pub fn arbitrary(ident: &impl ToTokens, shape: &Shape, is_copy: bool) -> Option<TokenStream> {
+    if !cfg!(feature = "testing") {
+        return None;
+    }
+    let (arbitrary, shrink) = match shape {
+        Shape::Struct(fields) => {
+            let value = fields.build(fields.all(quote! {
+                ::lrfrp::testing::Arbitrary::arbitrary(rng)
+            }));
+            let bindings = &fields.bindings;
+            let copy = if is_copy {
+                quote! { *self }
+            } else {
+                quote! { self.clone() }
+            };
+            (
+                value,
+                quote! {
+                    #(
+                        for value in ::lrfrp::testing::Arbitrary::shrink(&self.#bindings) {
+                            let mut value_shrunk = #copy;
+                            value_shrunk.#bindings = value;
+                            shrunk.push(value_shrunk);
+                        }
+                    )*
+                },
+            )
+        }
+        Shape::Enum(variants) => {
+            let constructors: Vec<_> = variants
+                .iter()
+                .map(|variant| {
+                    variant.build(variant.all(quote! {
+                        ::lrfrp::testing::Arbitrary::arbitrary(rng)
+                    }))
+                })
+                .collect();
+            let defaults = variants.iter().map(|variant| {
+                variant.build(variant.all(quote! { core::default::Default::default() }))
+            });
+            let patterns = variants.iter().map(|variant| variant.wild());
+            let len = variants.len();
+            let indices: Vec<_> = (0..len).map(|i| i as u64).collect();
+            let last = &constructors[len - 1];
+            let arbitrary_constructors = &constructors[..len - 1];
+            let arbitrary_indices = &indices[..len - 1];
+            let len = len as u64;
+            (
+                quote! {
+                    match rng.below(#len) {
+                        #(#arbitrary_indices => #arbitrary_constructors,)*
+                        _ => #last,
+                    }
+                },
+                quote! {
+                    let index: u64 = match self {
+                        #(#patterns => #indices,)*
+                    };
+                    #(
+                        if #indices < index {
+                            shrunk.push(#defaults);
+                        }
+                    )*
+                },
+            )
+        }
+    };
+    Some(quote! {
+        impl ::lrfrp::testing::Arbitrary for #ident {
+            fn arbitrary(rng: &mut ::lrfrp::testing::Rng) -> Self {
+                #arbitrary
+            }
+
+            fn shrink(&self) -> ::lrfrp::testing::Vec<Self> {
+                let mut shrunk = ::lrfrp::testing::Vec::new();
+                #shrink
+                shrunk
+            }
+        }
+    })
+}
+
+// `Finite` of the `check` feature, the values of the variants in the order of their
+// declarations
+pub fn finite(ident: &impl ToTokens, shape: &Shape) -> Option<TokenStream> {
+    if !cfg!(feature = "check") {
+        return None;
+    }
+    let variants = shape.variants().iter().map(|variant| {
+        let value = variant.pat();
+        if variant.tys.is_empty() {
+            return quote! { core::iter::once(#value) };
+        }
+        // the nested pairs `(a, (b, ()))` of fields, which `Finite` enumerates
+        let (ty, pat) = variant.tys.iter().zip(variant.bindings.iter()).rev().fold(
+            (quote! { () }, quote! { () }),
+            |(ty, pat), (field_ty, binding)| {
+                (quote! { (#field_ty, #ty) }, quote! { (#binding, #pat) })
+            },
+        );
+        quote! {
+            <#ty as ::lrfrp::check::Finite>::values()?
+                .into_iter()
+                .map(|#pat| #value)
+        }
+    });
+    Some(quote! {
+        impl ::lrfrp::check::Finite for #ident {
+            fn values() -> Option<::lrfrp::check::Vec<Self>> {
+                let values: ::lrfrp::check::Vec<Self> = core::iter::empty()
+                    #(.chain(#variants))*
+                    .collect();
+                if values.len() > ::lrfrp::check::MAX_VALUES {
+                    return None;
+                }
+                Some(values)
+            }
+        }
+    })
+}
+
+// `StateKey` of the `check` feature, the keys of the fields one after another,
+// preceded by the position of the variant for enums
+pub fn state_key(ident: &impl ToTokens, shape: &Shape) -> Option<TokenStream> {
+    if !cfg!(feature = "check") {
+        return None;
+    }
+    let (key, write_key) = match shape {
+        Shape::Struct(fields) => {
+            let bindings = &fields.bindings;
+            let key = if bindings.is_empty() {
+                quote! { _ }
+            } else {
+                quote! { key }
+            };
+            (
+                key,
+                quote! {
+                    #(::lrfrp::check::StateKey::write_key(&self.#bindings, key);)*
+                },
+            )
+        }
+        Shape::Enum(variants) => {
+            let keys = variants.iter().enumerate().map(|(index, variant)| {
+                let index = index as u32;
+                let pat = variant.pat();
+                let bindings = &variant.bindings;
+                quote! {
+                    #pat => {
+                        ::lrfrp::check::StateKey::write_key(&#index, key);
+                        #(::lrfrp::check::StateKey::write_key(#bindings, key);)*
+                    }
+                }
+            });
+            (
+                quote! { key },
+                quote! {
+                    match self {
+                        #(#keys)*
+                    }
+                },
+            )
+        }
+    };
+    Some(quote! {
+        impl ::lrfrp::check::StateKey for #ident {
+            fn write_key(&self, #key: &mut ::lrfrp::check::Vec<u8>) {
+                #write_key
+            }
+        }
+    })
+}
@@ -0,0 +1,1130 @@
+use super::ast::expressions::{Arm, BinOp, Expr, ExprBinary, ExprMatch, UnOp};
+use super::ast::literals::Lit;
+use super::ast::path::Path;
+use super::ast::patterns::Pat;
+use super::ast::statements::Stmt;
+use super::ast::types::Type;
+use super::ast::{AssertPolicy, Field, FrpStmtArrow, FrpStmtDependency, ItemDeclaration, ItemFn};
+use super::lrfrp_ir::types::{Type as VarType, TypeLifted, TypeMono, TypeSignal};
+use super::lrfrp_ir::{Errors, LrfrpIR};
+
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::Span;
+use syn::{Ident, Member, Result};
+
+/// The C header and source of a program.
+#[derive(Debug, Clone)]
+pub struct CSource {
+    pub header: String,
+    pub source: String,
+}
+
+// identifiers which are emitted as is must not collide with C or the generated code
+const RESERVED: &[&str] = &[
+    "auto", "bool", "break", "case", "char", "const", "continue", "default", "do", "double",
+    "else", "enum", "extern", "false", "float", "for", "goto", "if", "inline", "input", "int",
+    "long", "register", "restrict", "return", "self", "short", "signed", "sizeof", "static",
+    "struct", "switch", "true", "typedef", "union", "unsigned", "void", "volatile", "while",
+];
+
+fn primitive(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "bool" => "bool",
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "isize" => "intptr_t",
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "usize" => "size_t",
+        "f32" => "float",
+        "f64" => "double",
+        _ => return None,
+    })
+}
+
+// C promotes these to `int`, so their arithmetic is truncated back as Rust does
+fn is_narrow(name: &str) -> bool {
+    matches!(name, "i8" | "i16" | "u8" | "u16")
+}
+
+fn is_float(name: &str) -> bool {
+    matches!(name, "f32" | "f64")
+}
+
+fn unsupported(span: Span, what: &str) -> syn::Error {
+    syn::Error::new(span, format!("{} not supported by the C backend", what))
+}
+
+// Emits the same semantics as `FRP::run` in `codegen` for programs over scalars,
+// structs and enums without fields. Values are plain C values and every expression is
+// pure, so `if` and `match` become conditional expressions.
+pub fn codegen_c(lrfrp_ir: &LrfrpIR, header_name: &str) -> Result<CSource> {
+    let LrfrpIR {
+        module,
+        args,
+        probe,
+        properties,
+        declarations,
+        body,
+        ..
+    } = lrfrp_ir;
+
+    let mut cgen = CGen::new(&module.name.to_string(), declarations);
+    let mut errors = Errors::new();
+    for instance in body.instances() {
+        errors.push(unsupported(instance.module.span(), "instances are"));
+    }
+
+    let prefix = cgen.prefix.clone();
+    let mut types = cgen.types(lrfrp_ir, &mut errors);
+    if let Some(s) = errors.check(cgen.cell_struct(body.arrows.iter())) {
+        types += &s;
+    }
+
+    let (args_field, args_param, args_init, reset_args) = match args {
+        Some(_) => (
+            format!("    {}_Args args;\n", prefix),
+            format!(", const {}_Args *args", prefix),
+            "    self->args = *args;\n",
+            format!("    {}_Args args = self->args;\n", prefix),
+        ),
+        None => (String::new(), String::new(), "", String::new()),
+    };
+    let reset_call = if args.is_some() { ", &args" } else { "" };
+    let (probe_field, probe_prototype, probe_definition) = match probe {
+        Some(_) => (
+            format!("    {}_Probe probe;\n", prefix),
+            format!(
+                "/* Returns the probed values of the last instant, or NULL before the first step. */\n\
+                 const {p}_Probe *{p}_probe(const {p}_FRP *self);\n",
+                p = prefix
+            ),
+            format!(
+                "const {p}_Probe *{p}_probe(const {p}_FRP *self) {{\n\
+                 \x20   return self->running ? &self->probe : NULL;\n\
+                 }}\n\n",
+                p = prefix
+            ),
+        ),
+        None => (String::new(), String::new(), String::new()),
+    };
+
+    let asserts: Vec<_> = properties
+        .iter()
+        .filter(|property| property.is_assert)
+        .collect();
+    let mut violations_comment = String::new();
+    if !asserts.is_empty() {
+        violations_comment += "/* The failed `assert` items, as bits by their positions:\n";
+        for (i, property) in asserts.iter().enumerate() {
+            violations_comment += &format!(" * {:>4}: `{}`\n", i, property.name);
+        }
+        violations_comment += " */\n\n";
+    }
+    let (violations_field, violations_prototype, violations_definition, policy, stdlib) =
+        match &module.assert_policy {
+            _ if asserts.is_empty() => (
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                "",
+            ),
+            AssertPolicy::Panic => (
+                String::new(),
+                String::new(),
+                String::new(),
+                "    if (violations != 0) {\n        abort();\n    }\n".to_owned(),
+                "#include <stdlib.h>\n",
+            ),
+            AssertPolicy::Record => (
+                "    uint64_t violations;\n".to_owned(),
+                format!(
+                    "/* Returns the `assert` items failed since init or reset. */\n\
+                     uint64_t {p}_violations(const {p}_FRP *self);\n",
+                    p = prefix
+                ),
+                format!(
+                    "uint64_t {p}_violations(const {p}_FRP *self) {{\n\
+                     \x20   return self->violations;\n\
+                     }}\n\n",
+                    p = prefix
+                ),
+                "    self->violations |= violations;\n".to_owned(),
+                "",
+            ),
+            AssertPolicy::Hook(path) => {
+                let hook = &path.segments[path.segments.len() - 1].ident;
+                (
+                    String::new(),
+                    format!(
+                        "/* Called with the failed `assert` items, defined by the user. */\n\
+                         void {}(uint64_t violations);\n",
+                        hook
+                    ),
+                    String::new(),
+                    format!(
+                        "    if (violations != 0) {{\n        {}(violations);\n    }}\n",
+                        hook
+                    ),
+                    "",
+                )
+            }
+        };
+
+    let header = format!(
+        "/* Generated by lrfrpc. Do not edit. */\n\
+         #ifndef LRFRP_{guard}_H\n\
+         #define LRFRP_{guard}_H\n\n\
+         #include <stdbool.h>\n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\n\
+         {types}\
+         {violations_comment}\
+         typedef struct {{\n    bool running;\n    {p}_Out output;\n{args_field}{probe_field}{violations_field}    {p}_Cell cell;\n}} {p}_FRP;\n\n\
+         /* Initializes a program whose cells hold their initial values. */\n\
+         void {p}_init({p}_FRP *self{args_param});\n\
+         /* Calculates the outputs of the next instant from `input`. */\n\
+         void {p}_step({p}_FRP *self, const {p}_In *input);\n\
+         /* Returns the outputs of the last instant, or NULL before the first step. */\n\
+         const {p}_Out *{p}_sample(const {p}_FRP *self);\n\
+         {probe_prototype}\
+         {violations_prototype}\
+         /* Brings the program back to the state right after init. */\n\
+         void {p}_reset({p}_FRP *self);\n\n\
+         #endif\n",
+        guard = prefix.to_uppercase(),
+        p = prefix,
+        types = types,
+        args_field = args_field,
+        probe_field = probe_field,
+        probe_prototype = probe_prototype,
+        violations_comment = violations_comment,
+        violations_field = violations_field,
+        violations_prototype = violations_prototype,
+        args_param = args_param,
+    );
+
+    let mut functions = String::new();
+    let mut prototypes = String::new();
+    for declaration in declarations.iter() {
+        if let ItemDeclaration::Fn(item_fn) = declaration {
+            if let Some((prototype, definition)) = errors.check(cgen.function(item_fn)) {
+                prototypes += &format!("{};\n", prototype);
+                functions += &format!("{} {{\n{}}}\n\n", prototype, definition);
+            }
+        }
+    }
+    if !prototypes.is_empty() {
+        prototypes += "\n";
+    }
+
+    let mut initializations = String::new();
+    for arrow in body.arrows.iter() {
+        if let Some(s) = errors.check(cgen.cell_initialization(arrow)) {
+            initializations += &s;
+        }
+    }
+    let mut calculations = String::new();
+    for dependency in body.dependencies.iter() {
+        if let Some(s) = errors.check(cgen.calculation(dependency)) {
+            calculations += &s;
+        }
+    }
+    for field in probe.iter().flat_map(|probe| probe.fields.iter()) {
+        let value = body
+            .arrows
+            .iter()
+            .find(|arrow| Borrow::<Ident>::borrow(&arrow.path) == &field.ident)
+            .map(|_| format!("self->cell.{}", field.ident))
+            .unwrap_or_else(|| field.ident.to_string());
+        calculations += &format!("    self->probe.{} = {};\n", field.ident, value);
+    }
+    // the `property` items are only checked on the Rust side
+    for property in properties.iter().filter(|property| !property.is_assert) {
+        calculations += &format!("    (void){};\n", property.signal);
+    }
+    if !asserts.is_empty() {
+        calculations += "    uint64_t violations = 0;\n";
+        for (i, property) in asserts.iter().enumerate() {
+            calculations += &format!(
+                "    if (!{}) {{\n        violations |= UINT64_C(1) << {};\n    }}\n",
+                property.signal, i
+            );
+        }
+    }
+    let mut updates = String::new();
+    for arrow in body.arrows.iter() {
+        if let Some(s) = errors.check(cgen.cell_update(arrow)) {
+            updates += &s;
+        }
+    }
+    errors.finish()?;
+
+    let source = format!(
+        "/* Generated by lrfrpc. Do not edit. */\n\
+         #include \"{header_name}\"\n\n\
+         #include <math.h>\n\
+         {stdlib}\
+         #include <string.h>\n\n\
+         {prototypes}{functions}\
+         void {p}_init({p}_FRP *self{args_param}) {{\n\
+         \x20   memset(self, 0, sizeof(*self));\n\
+         {args_init}{initializations}}}\n\n\
+         void {p}_step({p}_FRP *self, const {p}_In *input) {{\n\
+         \x20   self->running = true;\n\
+         {calculations}{updates}{policy}}}\n\n\
+         const {p}_Out *{p}_sample(const {p}_FRP *self) {{\n\
+         \x20   return self->running ? &self->output : NULL;\n\
+         }}\n\n\
+         {probe_definition}\
+         {violations_definition}\
+         void {p}_reset({p}_FRP *self) {{\n\
+         {reset_args}    {p}_init(self{reset_call});\n\
+         }}\n",
+        header_name = header_name,
+        p = prefix,
+        prototypes = prototypes,
+        functions = functions,
+        args_param = args_param,
+        args_init = args_init,
+        initializations = initializations,
+        calculations = calculations,
+        updates = updates,
+        policy = policy,
+        stdlib = stdlib,
+        probe_definition = probe_definition,
+        violations_definition = violations_definition,
+        reset_args = reset_args,
+        reset_call = reset_call,
+    );
+
+    Ok(CSource { header, source })
+}
+
+// The Rust backend lays out the types of `#[ffi]` modules with `#[repr(C)]`, so they are
+// restricted to the ones the header can declare.
+pub fn check_ffi_types(lrfrp_ir: &LrfrpIR) -> Result<()> {
+    let mut cgen = CGen::new(&lrfrp_ir.module.name.to_string(), &lrfrp_ir.declarations);
+    let mut errors = Errors::new();
+    cgen.types(lrfrp_ir, &mut errors);
+    errors.finish()
+}
+
+// Declares the functions exported by the Rust code of an `#[ffi]` module. Only the
+// types crossing the boundary are spelled out, `FRP` is left opaque.
+pub fn ffi_header(lrfrp_ir: &LrfrpIR) -> Result<String> {
+    let module = &lrfrp_ir.module;
+    if !module.is_ffi() {
+        return Err(syn::Error::new_spanned(
+            &module.name,
+            "C headers are generated only for `#[ffi]` modules",
+        ));
+    }
+
+    let mut cgen = CGen::new(&module.name.to_string(), &lrfrp_ir.declarations);
+    let mut errors = Errors::new();
+    let types = cgen.types(lrfrp_ir, &mut errors);
+    errors.finish()?;
+
+    let args_param = match lrfrp_ir.args {
+        Some(_) => format!(", const {}_Args *args", cgen.prefix),
+        None => String::new(),
+    };
+    let probe_prototype = match lrfrp_ir.probe {
+        Some(_) => format!(
+            "/* Returns the probed values of the last instant, or NULL before the first run. */\n\
+             const {p}_Probe *{p}_probe(const {p}_FRP *frp);\n",
+            p = cgen.prefix
+        ),
+        None => String::new(),
+    };
+    let records_violations = lrfrp_ir
+        .properties
+        .iter()
+        .any(|property| property.is_assert)
+        && matches!(module.assert_policy, AssertPolicy::Record);
+    let violations_prototype = if records_violations {
+        format!(
+            "/* Returns the `assert` items failed since the program was initialized, as bits. */\n\
+             uint64_t {p}_violations(const {p}_FRP *frp);\n",
+            p = cgen.prefix
+        )
+    } else {
+        String::new()
+    };
+    Ok(format!(
+        "/* Generated by lrfrpc. Do not edit. */\n\
+         #ifndef LRFRP_{guard}_H\n\
+         #define LRFRP_{guard}_H\n\n\
+         #include <stdbool.h>\n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\n\
+         #ifdef __cplusplus\n\
+         extern \"C\" {{\n\
+         #endif\n\n\
+         {types}\
+         typedef struct {p}_FRP {p}_FRP;\n\n\
+         /* The size in bytes of a program, to be allocated aligned for any type. */\n\
+         size_t {p}_size(void);\n\
+         /* Initializes a program in `frp`. */\n\
+         void {p}_new({p}_FRP *frp{args_param});\n\
+         /* Calculates the outputs of the next instant from `input`. */\n\
+         void {p}_run({p}_FRP *frp, const {p}_In *input);\n\
+         /* Returns the outputs of the last instant, or NULL before the first run. */\n\
+         const {p}_Out *{p}_sample(const {p}_FRP *frp);\n\
+         {probe_prototype}\
+         {violations_prototype}\n\
+         #ifdef __cplusplus\n\
+         }}\n\
+         #endif\n\n\
+         #endif\n",
+        guard = cgen.prefix.to_uppercase(),
+        p = cgen.prefix,
+        types = types,
+        args_param = args_param,
+        probe_prototype = probe_prototype,
+        violations_prototype = violations_prototype,
+    ))
+}
+
+struct CGen<'a> {
+    prefix: String,
+    // field types of the declared structs
+    structs: HashMap<String, HashMap<String, String>>,
+    enums: HashSet<String>,
+    functions: HashMap<String, &'a ItemFn>,
+    // the Rust types of the signals, cells and arguments
+    signals: HashMap<String, String>,
+    // local bindings with the C expressions they stand for and their types if known
+    scopes: Vec<HashMap<String, (String, Option<String>)>>,
+}
+
+impl<'a> CGen<'a> {
+    fn new(prefix: &str, declarations: &'a [ItemDeclaration]) -> Self {
+        let mut cgen = CGen {
+            prefix: prefix.to_owned(),
+            structs: HashMap::new(),
+            enums: HashSet::new(),
+            functions: HashMap::new(),
+            signals: HashMap::new(),
+            scopes: vec![],
+        };
+        for declaration in declarations.iter() {
+            match declaration {
+                ItemDeclaration::Struct(e) => {
+                    let fields = e
+                        .fields
+                        .iter()
+                        .map(|field| (field.ident.to_string(), field.ty.to_string()))
+                        .collect();
+                    cgen.structs.insert(e.ident.to_string(), fields);
+                }
+                ItemDeclaration::Enum(e) => {
+                    cgen.enums.insert(e.ident.to_string());
+                }
+                ItemDeclaration::Fn(e) => {
+                    cgen.functions.insert(e.ident.to_string(), e);
+                }
+            }
+        }
+        cgen
+    }
+
+    fn ident(&self, ident: &Ident) -> Result<String> {
+        let name = ident.to_string();
+        if RESERVED.contains(&name.as_str()) {
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("`{}` cannot be used as an identifier in C", name),
+            ));
+        }
+        Ok(name)
+    }
+
+    fn c_type(&self, ty: &Type) -> Result<String> {
+        match ty {
+            Type::Paren(ty) => self.c_type(&ty.ty),
+            Type::Path(path) => {
+                let ident: &Ident = path.path.borrow();
+                let name = ident.to_string();
+                let is_plain = matches!(path.path, Path::Segment(ref segment)
+                    if matches!(segment.arguments, super::ast::path::PathArguments::None));
+                if !is_plain {
+                    Err(unsupported(ident.span(), &format!("`{}` is", ty)))
+                } else if let Some(c) = primitive(&name) {
+                    Ok(c.to_owned())
+                } else if self.structs.contains_key(&name) || self.enums.contains(&name) {
+                    Ok(format!("{}_{}", self.prefix, name))
+                } else {
+                    Err(unsupported(ident.span(), &format!("`{}` is", ty)))
+                }
+            }
+            Type::List(e) => Err(unsupported(e.bracket_token.span, "arrays are")),
+            Type::Tuple(e) => Err(unsupported(e.paren_token.span, "tuples are")),
+            Type::Infer(e) => Err(syn::Error::new(
+                e.underscore_token.spans[0],
+                "type annotations needed",
+            )),
+        }
+    }
+
+    // the declared structs and enums followed by `In`, `Out`, `Args` and `Probe`
+    fn types(&mut self, lrfrp_ir: &LrfrpIR, errors: &mut Errors) -> String {
+        let LrfrpIR {
+            input,
+            output,
+            args,
+            probe,
+            declarations,
+            ..
+        } = lrfrp_ir;
+
+        let mut types = String::new();
+        for declaration in declarations.iter() {
+            match declaration {
+                ItemDeclaration::Struct(e) => {
+                    let name = format!("{}_{}", self.prefix, e.ident);
+                    if let Some(s) = errors.check(self.c_struct(&name, e.fields.iter())) {
+                        types += &s;
+                    }
+                }
+                ItemDeclaration::Enum(e) => {
+                    let mut variants = String::new();
+                    for variant in e.variants.iter() {
+                        if variant.paren_token.is_some() {
+                            errors.push(unsupported(
+                                variant.ident.span(),
+                                "variants with fields are",
+                            ));
+                        }
+                        variants +=
+                            &format!("    {}_{}_{},\n", self.prefix, e.ident, variant.ident);
+                    }
+                    types += &format!(
+                        "typedef enum {{\n{}}} {}_{};\n\n",
+                        variants, self.prefix, e.ident
+                    );
+                }
+                ItemDeclaration::Fn(_) => {}
+            }
+        }
+        let io = [
+            ("In", Some(&input.fields)),
+            ("Out", Some(&output.fields)),
+            ("Args", args.as_ref().map(|args| &args.fields)),
+        ];
+        for (name, fields) in io.iter() {
+            if let Some(fields) = fields {
+                for field in fields.iter() {
+                    self.signals
+                        .insert(field.ident.to_string(), field.ty.to_string());
+                }
+                let name = format!("{}_{}", self.prefix, name);
+                if let Some(s) = errors.check(self.c_struct(&name, fields.iter())) {
+                    types += &s;
+                }
+            }
+        }
+        // the probed names are already known as signals or cells
+        if let Some(probe) = probe {
+            let name = format!("{}_Probe", self.prefix);
+            if let Some(s) = errors.check(self.c_struct(&name, probe.fields.iter())) {
+                types += &s;
+            }
+        }
+        types
+    }
+
+    fn c_struct<'b>(
+        &mut self,
+        name: &str,
+        fields: impl Iterator<Item = &'b Field>,
+    ) -> Result<String> {
+        let mut members = String::new();
+        let mut errors = Errors::new();
+        for field in fields {
+            let member = self
+                .c_type(&field.ty)
+                .and_then(|ty| Ok(format!("    {} {};\n", ty, self.ident(&field.ident)?)));
+            if let Some(member) = errors.check(member) {
+                members += &member;
+            }
+        }
+        errors.finish()?;
+        // empty structs are not allowed in C
+        if members.is_empty() {
+            members += "    char unused_;\n";
+        }
+        Ok(format!("typedef struct {{\n{}}} {};\n\n", members, name))
+    }
+
+    fn cell_struct(&mut self, arrows: impl Iterator<Item = &'a FrpStmtArrow>) -> Result<String> {
+        let mut members = String::new();
+        let mut errors = Errors::new();
+        for arrow in arrows {
+            let ident: &Ident = arrow.path.borrow();
+            self.signals.insert(ident.to_string(), arrow.ty.to_string());
+            let member = self.c_type(&arrow.ty).and_then(|ty| {
+                let mut member = format!("    {} {};\n", ty, self.ident(ident)?);
+                if let Some((ring, pos)) = arrow.ring() {
+                    member += &format!("    {} {}[{}];\n", ty, ring, arrow.count() - 1);
+                    member += &format!("    size_t {};\n", pos);
+                }
+                Ok(member)
+            });
+            if let Some(member) = errors.check(member) {
+                members += &member;
+            }
+        }
+        errors.finish()?;
+        if members.is_empty() {
+            members += "    char unused_;\n";
+        }
+        Ok(format!(
+            "typedef struct {{\n{}}} {}_Cell;\n\n",
+            members, self.prefix
+        ))
+    }
+
+    fn function(&mut self, item_fn: &ItemFn) -> Result<(String, String)> {
+        let output = item_fn.output.to_string();
+        let mut params = vec![];
+        let mut scope = HashMap::new();
+        for arg in item_fn.inputs.iter() {
+            let ident = match &arg.pat {
+                Pat::Ident(p) if p.subpat.is_none() => &p.ident,
+                _ => {
+                    return Err(unsupported(
+                        arg.colon_token.span,
+                        "patterns of arguments are",
+                    ))
+                }
+            };
+            let name = self.ident(ident)?;
+            params.push(format!("{} {}", self.c_type(&arg.ty)?, name));
+            scope.insert(name.clone(), (name, Some(arg.ty.to_string())));
+        }
+        if params.is_empty() {
+            params.push("void".to_owned());
+        }
+        let prototype = format!(
+            "static {} {}_{}({})",
+            self.c_type(&item_fn.output)?,
+            self.prefix,
+            item_fn.ident,
+            params.join(", ")
+        );
+
+        self.scopes.push(scope);
+        let definition = self.function_body(&item_fn.expr, &output);
+        self.scopes.pop();
+        Ok((prototype, definition?))
+    }
+
+    // a block of a function is the only place where local bindings are declared
+    fn function_body(&mut self, expr: &Expr, output: &str) -> Result<String> {
+        let block = match expr {
+            Expr::Block(block) => block,
+            expr => return Ok(format!("    return {};\n", self.expr(expr, Some(output))?)),
+        };
+        let mut definition = String::new();
+        for stmt in block.stmts.iter() {
+            match stmt {
+                Stmt::Local(local) => {
+                    let ident = match &local.pat {
+                        Pat::Ident(p) if p.subpat.is_none() => &p.ident,
+                        _ => return Err(unsupported(local.let_token.span, "destructuring is")),
+                    };
+                    let name = self.ident(ident)?;
+                    let ty = self.type_of(&local.expr).ok_or_else(|| {
+                        syn::Error::new(
+                            ident.span(),
+                            format!(
+                                "the C backend needs the type of `{}`; write `expr: T`",
+                                name
+                            ),
+                        )
+                    })?;
+                    let value = self.expr(&local.expr, Some(&ty))?;
+                    definition += &format!(
+                        "    const {} {} = {};\n",
+                        self.c_type_of(&ty, ident.span())?,
+                        name,
+                        value
+                    );
+                    if let Some(scope) = self.scopes.last_mut() {
+                        scope.insert(name.clone(), (name, Some(ty)));
+                    }
+                }
+                Stmt::Expr(expr) => {
+                    definition += &format!("    return {};\n", self.expr(expr, Some(output))?);
+                }
+            }
+        }
+        Ok(definition)
+    }
+
+    fn c_type_of(&self, name: &str, span: Span) -> Result<String> {
+        match primitive(name) {
+            Some(c) => Ok(c.to_owned()),
+            None if self.structs.contains_key(name) || self.enums.contains(name) => {
+                Ok(format!("{}_{}", self.prefix, name))
+            }
+            None => Err(unsupported(span, &format!("`{}` is", name))),
+        }
+    }
+
+    fn cell_initialization(&mut self, arrow: &FrpStmtArrow) -> Result<String> {
+        let arrow_expr = match &arrow.arrow_expr {
+            Some(arrow_expr) => arrow_expr,
+            // zeroed like `Default::default()`
+            None => return Ok(String::new()),
+        };
+        let ident: &Ident = arrow.path.borrow();
+        let ty = arrow.ty.to_string();
+        let mut initialization = format!(
+            "    self->cell.{} = {};\n",
+            ident,
+            self.expr(&arrow_expr.expr, Some(&ty))?
+        );
+        if let Some((ring, _)) = arrow.ring() {
+            initialization += &format!(
+                "    for (size_t i = 0; i < {}; i++) {{\n        self->cell.{}[i] = self->cell.{};\n    }}\n",
+                arrow.count() - 1,
+                ring,
+                ident
+            );
+        }
+        Ok(initialization)
+    }
+
+    fn calculation(&mut self, dependency: &FrpStmtDependency) -> Result<String> {
+        let ident: &Ident = dependency.path.borrow();
+        let name = self.ident(ident)?;
+        match &dependency.path {
+            Path::TypedSegment(_, VarType::Lifted(TypeLifted::Signal(TypeSignal::Output(_)))) => {
+                let ty = self.signals.get(&name).cloned();
+                Ok(format!(
+                    "    self->output.{} = {};\n",
+                    name,
+                    self.expr(&dependency.expr, ty.as_deref())?
+                ))
+            }
+            Path::TypedSegment(_, VarType::Lifted(TypeLifted::Signal(TypeSignal::Local(ty)))) => {
+                let ty = match ty {
+                    super::lrfrp_ir::types::MaybeType::Resolved(ty) => ty.to_string(),
+                    super::lrfrp_ir::types::MaybeType::Unresolved => {
+                        return Err(syn::Error::new(ident.span(), "type annotations needed"))
+                    }
+                };
+                self.signals.insert(name.clone(), ty.clone());
+                let value = self.expr(&dependency.expr, Some(&ty))?;
+                Ok(format!(
+                    "    const {} {} = {};\n",
+                    self.c_type_of(&ty, ident.span())?,
+                    name,
+                    value
+                ))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn cell_update(&mut self, arrow: &FrpStmtArrow) -> Result<String> {
+        let ident: &Ident = arrow.path.borrow();
+        let ty = arrow.ty.to_string();
+        let value = self.expr(&arrow.expr, Some(&ty))?;
+        match arrow.ring() {
+            // the oldest buffered value becomes visible
+            Some((ring, pos)) => Ok(format!(
+                "    {{\n\
+                 \x20       const {ty} value = {value};\n\
+                 \x20       const size_t pos = self->cell.{pos};\n\
+                 \x20       self->cell.{ident} = self->cell.{ring}[pos];\n\
+                 \x20       self->cell.{ring}[pos] = value;\n\
+                 \x20       self->cell.{pos} = (pos + 1) % {len};\n\
+                 \x20   }}\n",
+                ty = self.c_type(&arrow.ty)?,
+                value = value,
+                pos = pos,
+                ident = ident,
+                ring = ring,
+                len = arrow.count() - 1,
+            )),
+            None => Ok(format!("    self->cell.{} = {};\n", ident, value)),
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&(String, Option<String>)> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    // the Rust type of `expr` as far as it is needed to choose C operators and literals
+    fn type_of(&self, expr: &Expr) -> Option<String> {
+        use Expr::*;
+        match expr {
+            Lit(e) => match e.lit {
+                super::ast::literals::Lit::Bool(_) => Some("bool".to_owned()),
+                _ => None,
+            },
+            Unary(e) => self.type_of(&e.expr),
+            Binary(e) => {
+                use BinOp::*;
+                match e.op {
+                    And(_) | Or(_) | Eq(_) | Lt(_) | Le(_) | Ne(_) | Ge(_) | Gt(_) => {
+                        Some("bool".to_owned())
+                    }
+                    Shl(_) | Shr(_) => self.type_of(&e.lhs),
+                    _ => self.type_of(&e.lhs).or_else(|| self.type_of(&e.rhs)),
+                }
+            }
+            Paren(e) => self.type_of(&e.expr),
+            Cast(e) => Some(e.ty.to_string()),
+            Type(e) => Some(e.ty.to_string()),
+            If(e) => self
+                .type_of(&e.then_branch)
+                .or_else(|| self.type_of(&e.else_branch)),
+            Match(e) => e.arms.iter().find_map(|arm| self.type_of(&arm.body)),
+            Call(e) => {
+                let ident: &Ident = e.func.borrow();
+                self.functions
+                    .get(&ident.to_string())
+                    .map(|item_fn| item_fn.output.to_string())
+            }
+            Struct(e) => {
+                let ident: &Ident = e.path.borrow();
+                Some(ident.to_string())
+            }
+            Field(e) => match (&e.member, self.type_of(&e.base)) {
+                (Member::Named(member), Some(base)) => self
+                    .structs
+                    .get(&base)
+                    .and_then(|fields| fields.get(&member.to_string()))
+                    .cloned(),
+                _ => None,
+            },
+            Path(e) => match &e.path {
+                super::ast::path::Path::Qualified(qualifier, _, _) => {
+                    Some(qualifier.ident.to_string())
+                }
+                path => {
+                    let ident: &Ident = path.borrow();
+                    let name = ident.to_string();
+                    match self.lookup(&name) {
+                        Some((_, ty)) => ty.clone(),
+                        None => self.signals.get(&name).cloned(),
+                    }
+                }
+            },
+            TypedExpr(e, _) => self.type_of(e),
+            _ => None,
+        }
+    }
+
+    fn lit(&self, lit: &Lit, hint: Option<&str>) -> String {
+        match lit {
+            Lit::Bool(e) => e.value.to_string(),
+            Lit::Int(e) => {
+                let suffix = match hint {
+                    Some("u32") => "U",
+                    Some("u64") | Some("usize") => "ULL",
+                    Some("i64") | Some("isize") => "LL",
+                    _ => "",
+                };
+                format!("{}{}", e.base10_digits(), suffix)
+            }
+            Lit::Float(e) => {
+                let mut digits = e.base10_digits().to_owned();
+                if !digits.contains(['.', 'e', 'E']) {
+                    digits += ".0";
+                }
+                // `f32` arithmetic must not be widened by `double` literals
+                if hint == Some("f32") {
+                    digits += "f";
+                }
+                digits
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr, hint: Option<&str>) -> Result<String> {
+        use Expr::*;
+        match expr {
+            Lit(e) => Ok(self.lit(&e.lit, hint)),
+            Path(e) => self.path(&e.path),
+            Paren(e) => Ok(format!("({})", self.expr(&e.expr, hint)?)),
+            TypedExpr(e, _) => self.expr(e, hint),
+            Unary(e) => {
+                let ty = self.type_of(&e.expr).or_else(|| hint.map(str::to_owned));
+                let operand = self.expr(&e.expr, ty.as_deref())?;
+                let op = match (&e.op, ty.as_deref()) {
+                    (UnOp::Neg(_), _) => "-",
+                    (UnOp::Not(_), Some("bool")) => "!",
+                    (UnOp::Not(_), Some(ty)) if primitive(ty).is_some() && !is_float(ty) => "~",
+                    (UnOp::Not(op), _) => {
+                        return Err(syn::Error::new(
+                            op.spans[0],
+                            "the C backend cannot tell whether `!` is logical or bitwise here",
+                        ))
+                    }
+                };
+                Ok(self.narrow(format!("({}{})", op, operand), ty.as_deref()))
+            }
+            Binary(e) => self.binary(e, hint),
+            Cast(e) => {
+                let ty = self.c_type(&e.ty)?;
+                Ok(format!("(({})({}))", ty, self.expr(&e.expr, None)?))
+            }
+            Type(e) => {
+                let ty = e.ty.to_string();
+                let c_ty = self.c_type(&e.ty)?;
+                Ok(format!("(({})({}))", c_ty, self.expr(&e.expr, Some(&ty))?))
+            }
+            If(e) => Ok(format!(
+                "({} ? {} : {})",
+                self.expr(&e.cond, Some("bool"))?,
+                self.expr(&e.then_branch, hint)?,
+                self.expr(&e.else_branch, hint)?
+            )),
+            Match(e) => self.expr_match(e, hint),
+            Call(e) => {
+                let ident: &Ident = e.func.borrow();
+                if e.builtin.is_some() {
+                    return Err(unsupported(ident.span(), &format!("`{}` is", ident)));
+                }
+                let item_fn = match self.functions.get(&ident.to_string()) {
+                    Some(item_fn) => *item_fn,
+                    None => return Err(unsupported(ident.span(), "external functions are")),
+                };
+                let mut args = vec![];
+                for (arg, param) in e.args.iter().zip(item_fn.inputs.iter()) {
+                    let ty = param.ty.to_string();
+                    args.push(self.expr(arg, Some(&ty))?);
+                }
+                Ok(format!("{}_{}({})", self.prefix, ident, args.join(", ")))
+            }
+            Field(e) => match &e.member {
+                Member::Named(member) => Ok(format!(
+                    "{}.{}",
+                    self.expr(&e.base, None)?,
+                    self.ident(member)?
+                )),
+                Member::Unnamed(index) => Err(unsupported(index.span, "tuples are")),
+            },
+            Struct(e) => {
+                let ident: &Ident = e.path.borrow();
+                if let Some(rest) = &e.rest {
+                    return Err(unsupported(rest.span(), "struct update syntax is"));
+                }
+                let field_types = self.structs.get(&ident.to_string()).cloned();
+                let mut fields = vec![];
+                for field in e.fields.iter() {
+                    let member = match &field.member {
+                        Member::Named(member) => member,
+                        Member::Unnamed(index) => {
+                            return Err(unsupported(index.span, "tuples are"))
+                        }
+                    };
+                    let ty = field_types
+                        .as_ref()
+                        .and_then(|types| types.get(&member.to_string()).cloned());
+                    let value = self.expr(&field.expr, ty.as_deref())?;
+                    fields.push(format!(".{} = {}", self.ident(member)?, value));
+                }
+                Ok(format!(
+                    "(({}_{}){{ {} }})",
+                    self.prefix,
+                    ident,
+                    fields.join(", ")
+                ))
+            }
+            Block(e) => Err(unsupported(
+                e.braced_token.span,
+                "blocks outside of functions are",
+            )),
+            Index(e) => Err(unsupported(e.bracket_token.span, "arrays are")),
+            List(e) => Err(unsupported(e.bracket_token.span, "arrays are")),
+            Repeat(e) => Err(unsupported(e.bracket_token.span, "arrays are")),
+            Tuple(e) => Err(unsupported(e.paren_token.span, "tuples are")),
+            Instance(e) => Err(unsupported(e.module.span(), "instances are")),
+            // lifted into cells in `LrfrpIR::from_ast`
+            Init(_) | Pre(_) => unreachable!(),
+        }
+    }
+
+    // wraps arithmetic on types narrower than `int` so that it wraps around as in Rust
+    fn narrow(&self, code: String, ty: Option<&str>) -> String {
+        match ty {
+            Some(ty) if is_narrow(ty) => format!("(({})({}))", primitive(ty).unwrap_or(ty), code),
+            _ => code,
+        }
+    }
+
+    fn path(&self, path: &Path) -> Result<String> {
+        let ident: &Ident = path.borrow();
+        match path {
+            Path::Qualified(qualifier, _, variant) => {
+                let name = qualifier.ident.to_string();
+                if !self.enums.contains(&name) {
+                    return Err(unsupported(qualifier.ident.span(), "external enums are"));
+                }
+                Ok(format!("{}_{}_{}", self.prefix, name, variant.ident))
+            }
+            _ if self.lookup(&ident.to_string()).is_some() => Ok(self
+                .lookup(&ident.to_string())
+                .map(|(code, _)| code.clone())
+                .unwrap_or_default()),
+            Path::Segment(_) => self.ident(ident),
+            Path::TypedSegment(_, ty) => {
+                let name = self.ident(ident)?;
+                Ok(match ty {
+                    VarType::Lifted(TypeLifted::Cell(_)) => format!("self->cell.{}", name),
+                    VarType::Mono(TypeMono::Args(_)) => format!("self->args.{}", name),
+                    VarType::Mono(TypeMono::Type(_)) => name,
+                    VarType::Lifted(TypeLifted::Signal(ty)) => match ty {
+                        TypeSignal::Local(_) => name,
+                        TypeSignal::Input(_) => format!("input->{}", name),
+                        TypeSignal::Output(_) => format!("self->output.{}", name),
+                    },
+                })
+            }
+        }
+    }
+
+    fn binary(&mut self, e: &ExprBinary, hint: Option<&str>) -> Result<String> {
+        use BinOp::*;
+        let (lhs_ty, rhs_ty) = (self.type_of(&e.lhs), self.type_of(&e.rhs));
+        let (op, comparison) = match e.op {
+            Add(_) => ("+", false),
+            Sub(_) => ("-", false),
+            Mul(_) => ("*", false),
+            Div(_) => ("/", false),
+            Rem(_) => ("%", false),
+            And(_) => ("&&", false),
+            Or(_) => ("||", false),
+            BitXor(_) => ("^", false),
+            BitAnd(_) => ("&", false),
+            BitOr(_) => ("|", false),
+            Shl(_) => ("<<", false),
+            Shr(_) => (">>", false),
+            Eq(_) => ("==", true),
+            Lt(_) => ("<", true),
+            Le(_) => ("<=", true),
+            Ne(_) => ("!=", true),
+            Ge(_) => (">=", true),
+            Gt(_) => (">", true),
+        };
+
+        // the operands share their type except for shifts and logical operators
+        let operand_ty = match e.op {
+            And(_) | Or(_) => Some("bool".to_owned()),
+            Shl(_) | Shr(_) => lhs_ty.clone().or_else(|| hint.map(str::to_owned)),
+            _ if comparison => lhs_ty.clone().or_else(|| rhs_ty.clone()),
+            _ => lhs_ty
+                .clone()
+                .or_else(|| rhs_ty.clone())
+                .or_else(|| hint.map(str::to_owned)),
+        };
+        let operand_ty = operand_ty.as_deref();
+        if let (true, Some(ty)) = (comparison, operand_ty) {
+            if self.structs.contains_key(ty) {
+                return Err(unsupported(e.lhs.span(), "comparing structs is"));
+            }
+        }
+
+        let lhs = self.expr(&e.lhs, operand_ty)?;
+        let rhs = match e.op {
+            Shl(_) | Shr(_) => self.expr(&e.rhs, rhs_ty.as_deref())?,
+            _ => self.expr(&e.rhs, operand_ty)?,
+        };
+        let code = match (&e.op, operand_ty) {
+            (Rem(_), Some("f32")) => format!("fmodf({}, {})", lhs, rhs),
+            (Rem(_), Some("f64")) => format!("fmod({}, {})", lhs, rhs),
+            _ => format!("({} {} {})", lhs, op, rhs),
+        };
+        if comparison {
+            Ok(code)
+        } else {
+            Ok(self.narrow(code, operand_ty))
+        }
+    }
+
+    // the last arm is taken whenever the others do not match, which is exhaustive
+    // since the type checker has checked the patterns
+    fn expr_match(&mut self, e: &ExprMatch, hint: Option<&str>) -> Result<String> {
+        let scrutinee = format!("({})", self.expr(&e.expr, None)?);
+        let ty = self.type_of(&e.expr);
+        let mut code = None;
+        for arm in e.arms.iter().rev() {
+            let mut scope = HashMap::new();
+            let cond = self.pattern(&arm.pat, &scrutinee, ty.as_deref(), &mut scope)?;
+            self.scopes.push(scope);
+            let arm_code = self.arm(arm, hint);
+            self.scopes.pop();
+            let (guard, body) = arm_code?;
+            code = Some(match code {
+                None => body,
+                Some(rest) => {
+                    let cond = match guard {
+                        Some(guard) => format!("({} && {})", cond, guard),
+                        None => cond,
+                    };
+                    format!("({} ? {} : {})", cond, body, rest)
+                }
+            });
+        }
+        code.ok_or_else(|| unsupported(e.match_token.span, "matches without arms are"))
+    }
+
+    fn arm(&mut self, arm: &Arm, hint: Option<&str>) -> Result<(Option<String>, String)> {
+        let guard = match &arm.guard {
+            Some((_, guard)) => Some(self.expr(guard, Some("bool"))?),
+            None => None,
+        };
+        Ok((guard, self.expr(&arm.body, hint)?))
+    }
+
+    fn pattern(
+        &self,
+        pat: &Pat,
+        scrutinee: &str,
+        ty: Option<&str>,
+        scope: &mut HashMap<String, (String, Option<String>)>,
+    ) -> Result<String> {
+        match pat {
+            Pat::Wild(_) => Ok("true".to_owned()),
+            Pat::Lit(p) => Ok(format!("({} == {})", scrutinee, self.lit(&p.lit, ty))),
+            Pat::Path(p) => Ok(format!("({} == {})", scrutinee, self.path(&p.path)?)),
+            // bindings stand for the scrutinee itself
+            Pat::Ident(p) => {
+                let name = self.ident(&p.ident)?;
+                scope.insert(name, (scrutinee.to_owned(), ty.map(str::to_owned)));
+                match &p.subpat {
+                    Some((_, subpat)) => self.pattern(subpat, scrutinee, ty, scope),
+                    None => Ok("true".to_owned()),
+                }
+            }
+            Pat::Or(p) => {
+                let mut cases = vec![];
+                for case in p.cases.iter() {
+                    cases.push(self.pattern(case, scrutinee, ty, scope)?);
+                }
+                Ok(format!("({})", cases.join(" || ")))
+            }
+            Pat::TupleStruct(p) => {
+                let ident: &Ident = p.path.borrow();
+                Err(unsupported(ident.span(), "variants with fields are"))
+            }
+            Pat::Struct(p) => Err(unsupported(p.brace_token.span, "struct patterns are")),
+            Pat::Tuple(p) => Err(unsupported(p.paren_token.span, "tuples are")),
+            Pat::List(p) => Err(unsupported(p.bracket_token.span, "arrays are")),
+        }
+    }
+}
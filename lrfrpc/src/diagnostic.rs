@@ -0,0 +1,113 @@
+use proc_macro2::{LexError, Span};
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// An error found in the source of a program.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    /// The 1-based line and column where the erroneous tokens start, or `None` if the
+    /// error concerns the whole program.
+    pub location: Option<(usize, usize)>,
+    // the number of columns to be underlined
+    width: usize,
+}
+
+impl Diagnostic {
+    fn new<T: fmt::Display>(message: T, span: Span) -> Self {
+        let (start, end) = (span.start(), span.end());
+        // the call site of a parsed string has no location
+        let location = if start.line == 0 || span.source_text().is_none() {
+            None
+        } else {
+            Some((start.line, start.column + 1))
+        };
+        let width = if start.line == end.line && end.column > start.column {
+            end.column - start.column
+        } else {
+            1
+        };
+        Diagnostic {
+            message: message.to_string(),
+            location,
+            width,
+        }
+    }
+
+    pub(crate) fn from_error(error: syn::Error) -> Vec<Self> {
+        error
+            .into_iter()
+            .map(|error| Diagnostic::new(&error, error.span()))
+            .collect()
+    }
+
+    pub(crate) fn from_lex_error(error: &LexError) -> Self {
+        Diagnostic::new(error, error.span())
+    }
+
+    /// Renders the diagnostic like rustc, quoting the line of `source` it points to.
+    pub fn render(&self, path: &str, source: &str) -> String {
+        let mut rendered = format!("error: {}\n", self.message);
+        let (line, column) = match self.location {
+            Some(location) => location,
+            None => {
+                rendered += &format!(" --> {}\n", path);
+                return rendered;
+            }
+        };
+
+        let number = line.to_string();
+        let pad = " ".repeat(number.len());
+        let text = source.lines().nth(line - 1).unwrap_or("");
+        rendered += &format!("{}--> {}:{}:{}\n", pad, path, line, column);
+        rendered += &format!("{} |\n", pad);
+        rendered += &format!("{} | {}\n", number, text);
+        rendered += &format!(
+            "{} | {}{}\n",
+            pad,
+            " ".repeat(column - 1),
+            "^".repeat(self.width)
+        );
+        rendered
+    }
+}
+
+/// An error of compiling a `.lrfrp` file.
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    Compile {
+        path: PathBuf,
+        source: String,
+        diagnostics: Vec<Diagnostic>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(path, e) => writeln!(f, "error: cannot read `{}`: {}", path.display(), e),
+            Error::Compile {
+                path,
+                source,
+                diagnostics,
+            } => {
+                let path = path.display().to_string();
+                for diagnostic in diagnostics.iter() {
+                    writeln!(f, "{}", diagnostic.render(&path, source))?;
+                }
+                write!(
+                    f,
+                    "error: could not compile `{}` due to {} previous error{}",
+                    path,
+                    diagnostics.len(),
+                    if diagnostics.len() == 1 { "" } else { "s" }
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,132 @@
+//! The compiler of lrfrp, shared by the `frp!` macro, the `lrfrpc` binary and build
+//! scripts compiling `.lrfrp` files.
+
+mod ast;
+pub mod build;
+mod codegen;
+mod codegen_c;
+mod diagnostic;
+mod lrfrp_ir;
+
+pub use codegen_c::CSource;
+pub use diagnostic::{Diagnostic, Error};
+
+use lrfrp_ir::LrfrpIR;
+
+use proc_macro2::TokenStream;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Compiles the body of an `frp!` invocation into the items it expands to.
+pub fn compile(input: TokenStream) -> syn::Result<TokenStream> {
+    let ast = syn::parse2::<ast::Ast>(input)?;
+    let lrfrp_ir = LrfrpIR::from_ast(ast)?;
+    if lrfrp_ir.module.is_ffi() {
+        codegen_c::check_ffi_types(&lrfrp_ir)?;
+    }
+    Ok(codegen::codegen(lrfrp_ir))
+}
+
+/// Compiles the source of a `.lrfrp` file into Rust source, formatted by `rustfmt` if
+/// it is available.
+pub fn compile_str(source: &str) -> Result<String, Vec<Diagnostic>> {
+    let lrfrp_ir = lower(source)?;
+    if lrfrp_ir.module.is_ffi() {
+        codegen_c::check_ffi_types(&lrfrp_ir).map_err(Diagnostic::from_error)?;
+    }
+    Ok(format(&codegen::codegen(lrfrp_ir).to_string()))
+}
+
+/// Checks the source of a `.lrfrp` file without generating code, returning the warnings
+/// about unused signals, inputs and arguments.
+pub fn check_str(source: &str) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
+    let lrfrp_ir = lower(source)?;
+    if lrfrp_ir.module.is_ffi() {
+        codegen_c::check_ffi_types(&lrfrp_ir).map_err(Diagnostic::from_error)?;
+    }
+    Ok(lrfrp_ir
+        .warnings
+        .into_iter()
+        .flat_map(|warning| Diagnostic::from_error(warning.into()))
+        .collect())
+}
+
+/// Compiles the `.lrfrp` file at `path` into Rust source.
+pub fn compile_file<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    let path = path.as_ref();
+    let code = read(path, compile_str)?;
+    let name = path.file_name().unwrap_or(path.as_os_str());
+    Ok(format!(
+        "// Generated by lrfrpc from `{}`. Do not edit.\n\n{}",
+        name.to_string_lossy(),
+        code
+    ))
+}
+
+/// Compiles the source of a `.lrfrp` file into a C header and source, where the source
+/// includes the header as `header_name`.
+pub fn compile_c_str(source: &str, header_name: &str) -> Result<CSource, Vec<Diagnostic>> {
+    let lrfrp_ir = lower(source)?;
+    codegen_c::codegen_c(&lrfrp_ir, header_name).map_err(Diagnostic::from_error)
+}
+
+/// Compiles the `.lrfrp` file at `path` into a C header and source.
+pub fn compile_c_file<P: AsRef<Path>>(path: P, header_name: &str) -> Result<CSource, Error> {
+    read(path.as_ref(), |source| compile_c_str(source, header_name))
+}
+
+/// Generates the C header declaring the functions exported by an `#[ffi]` module.
+pub fn compile_header_str(source: &str) -> Result<String, Vec<Diagnostic>> {
+    let lrfrp_ir = lower(source)?;
+    codegen_c::ffi_header(&lrfrp_ir).map_err(Diagnostic::from_error)
+}
+
+/// Generates the C header of the `#[ffi]` module in the `.lrfrp` file at `path`.
+pub fn compile_header_file<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    read(path.as_ref(), compile_header_str)
+}
+
+fn lower(source: &str) -> Result<LrfrpIR, Vec<Diagnostic>> {
+    let input = source
+        .parse::<TokenStream>()
+        .map_err(|e| vec![Diagnostic::from_lex_error(&e)])?;
+    let ast = syn::parse2::<ast::Ast>(input).map_err(Diagnostic::from_error)?;
+    LrfrpIR::from_ast(ast).map_err(Diagnostic::from_error)
+}
+
+fn read<T, F>(path: &Path, f: F) -> Result<T, Error>
+where
+    F: FnOnce(&str) -> Result<T, Vec<Diagnostic>>,
+{
+    let source = fs::read_to_string(path).map_err(|e| Error::Io(path.to_owned(), e))?;
+    f(&source).map_err(|diagnostics| Error::Compile {
+        path: path.to_owned(),
+        source,
+        diagnostics,
+    })
+}
+
+// the code is left as is if `rustfmt` fails
+fn format(code: &str) -> String {
+    let rustfmt = std::env::var("RUSTFMT").unwrap_or_else(|_| "rustfmt".to_owned());
+    let child = Command::new(rustfmt)
+        .args(["--edition", "2018", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let formatted = child.ok().and_then(|mut child| {
+        // rustfmt reads the whole input before writing anything
+        child.stdin.take()?.write_all(code.as_bytes()).ok()?;
+        let output = child.wait_with_output().ok()?;
+        if output.status.success() {
+            String::from_utf8(output.stdout).ok()
+        } else {
+            None
+        }
+    });
+    formatted.unwrap_or_else(|| code.to_owned())
+}
@@ -0,0 +1,181 @@
+use super::ast::{self, Item};
+pub use error::Errors;
+use proc_macro2::Span;
+use std::borrow::Borrow;
+use syn::{Error, Ident, Result};
+
+mod deps_check;
+mod deps_trailer;
+mod error;
+mod instance;
+mod temporal;
+mod tsort;
+mod typeck;
+pub mod types;
+
+macro_rules! try_write {
+    ($value:expr => $target:ident, $errors:ident) => {{
+        use syn::Error;
+        if $target.is_some() {
+            $errors.push(Error::new_spanned($value, "Duplicated items"));
+        } else {
+            $target = Some($value);
+        }
+    }};
+}
+
+macro_rules! item_check {
+    ($value:ident, $item_name:expr, $errors:ident) => {
+        if $value.is_none() {
+            $errors.push(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(r#"Item `{}` not found"#, $item_name),
+            ));
+        }
+    };
+}
+
+// the `check` feature reports the violated properties of an instant as bits of a `u64`
+const MAX_PROPERTIES: usize = 64;
+
+// an `assert` or `property` item, whose condition is calculated as the hidden signal
+// `signal`. Only `assert` items are checked by `run`.
+#[derive(Debug)]
+pub struct Property {
+    pub name: String,
+    pub signal: Ident,
+    pub is_assert: bool,
+}
+
+#[derive(Debug)]
+pub struct LrfrpIR {
+    pub module: ast::ItemMod,
+    pub input: ast::ItemIn,
+    pub output: ast::ItemOut,
+    pub args: Option<ast::ItemArgs>,
+    pub probe: Option<ast::ItemProbe>,
+    pub properties: Vec<Property>,
+    pub declarations: Vec<ast::ItemDeclaration>,
+    pub body: deps_check::OrderedStmts,
+    pub warnings: Vec<error::UnusedVariableWarning>,
+}
+
+impl LrfrpIR {
+    pub fn from_ast(ast: ast::Ast) -> Result<Self> {
+        let mut module = None;
+        let mut input = None;
+        let mut output = None;
+        let mut args = None;
+        let mut probe = None;
+
+        let mut declarations = vec![];
+        let mut frp_stmts = vec![];
+        let mut properties: Vec<Property> = vec![];
+        let mut errors = Errors::new();
+
+        for item in ast.items.into_iter() {
+            use Item::*;
+            match item {
+                Mod(e) => try_write!(e => module, errors),
+                In(e) => try_write!(e => input, errors),
+                Out(e) => try_write!(e => output, errors),
+                Args(e) => try_write!(e => args, errors),
+                Probe(e) => try_write!(e => probe, errors),
+                Assert(e) => {
+                    let index = properties.len();
+                    let (name, dependency) = e.desugar(index);
+                    properties.push(self::Property {
+                        name,
+                        signal: Borrow::<Ident>::borrow(&dependency.path).clone(),
+                        is_assert: true,
+                    });
+                    frp_stmts.push(ast::ItemFrpStmt::Dependency(dependency));
+                }
+                Property(e) => {
+                    let ident = e.ident.clone();
+                    let (name, dependency) = e.desugar();
+                    if properties.iter().any(|property| property.name == name) {
+                        errors.push(Error::new_spanned(ident, "Duplicated properties"));
+                        continue;
+                    }
+                    properties.push(self::Property {
+                        name,
+                        signal: Borrow::<Ident>::borrow(&dependency.path).clone(),
+                        is_assert: false,
+                    });
+                    frp_stmts.push(ast::ItemFrpStmt::Dependency(dependency));
+                }
+                FrpStmt(e) => frp_stmts.push(e),
+                Declaration(e) => declarations.push(e),
+            }
+        }
+
+        item_check!(module, "mod", errors);
+        item_check!(input, "In", errors);
+        item_check!(output, "Out", errors);
+        if properties.len() > MAX_PROPERTIES {
+            errors.push(Error::new(
+                Span::call_site(),
+                format!("at most {} properties are supported", MAX_PROPERTIES),
+            ));
+        }
+        errors.finish()?;
+        let (module, input, output) = match (module, input, output) {
+            (Some(module), Some(input), Some(output)) => (module, input, output),
+            // checked above
+            _ => unreachable!(),
+        };
+
+        // the syntactic transformations are independent of each other's errors
+        let mut errors = Errors::new();
+        instance::resolve(&module, &declarations, &mut frp_stmts, &mut errors);
+        let mut desugared = vec![];
+        for frp_stmt in frp_stmts.into_iter() {
+            match frp_stmt {
+                ast::ItemFrpStmt::Destructure(e) => {
+                    if let Some(stmts) = errors.check(e.desugar()) {
+                        desugared.extend(stmts.into_iter().map(ast::ItemFrpStmt::Dependency));
+                    }
+                }
+                frp_stmt => desugared.push(frp_stmt),
+            }
+        }
+        let mut frp_stmts = desugared;
+        errors.check(temporal::lift(&mut declarations, &mut frp_stmts));
+        errors.finish()?;
+
+        // the statements are type checked even if their dependencies are not
+        let (mut body, warnings, mut errors) = deps_check::deps_check(
+            &input,
+            &output,
+            &args,
+            &probe,
+            &properties,
+            &mut declarations,
+            frp_stmts,
+        );
+        errors.check(typeck::typeck(
+            &input,
+            &output,
+            &args,
+            &probe,
+            &properties,
+            &mut declarations,
+            &mut body,
+        ));
+        errors.finish()?;
+        body.eliminate_dead();
+
+        Ok(LrfrpIR {
+            module,
+            input,
+            output,
+            args,
+            probe,
+            properties,
+            declarations,
+            body,
+            warnings,
+        })
+    }
+}
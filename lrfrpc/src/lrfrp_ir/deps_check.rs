@@ -0,0 +1,430 @@
+use super::deps_trailer::DepExtractor;
+use super::error::{
+    CellAsOutputError, Errors, MultipleDefinitionError, NotCalculatedError, NotProbeableError,
+    UnusedVariableWarning,
+};
+use super::tsort;
+use super::types::{Type, TypeLifted, TypeSignal, Var, VarEnv};
+use super::Property;
+
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::expressions::{Expr, ExprInstance};
+use crate::ast::{
+    Field, FrpStmtArrow, FrpStmtArrows, FrpStmtDependency, ItemArgs, ItemDeclaration, ItemFrpStmt,
+    ItemIn, ItemOut, ItemProbe,
+};
+use syn::Ident;
+
+use std::collections::hash_map::Entry;
+
+#[derive(Debug)]
+pub struct OrderedStmts {
+    pub dependencies: Vec<FrpStmtDependency>,
+    pub arrows: FrpStmtArrows,
+    // the variables needed to calculate the outputs, probes and properties
+    live: HashSet<String>,
+}
+
+impl OrderedStmts {
+    // dead statements are type-checked before they are eliminated
+    pub fn eliminate_dead(&mut self) {
+        let live = &self.live;
+        self.dependencies.retain(|dependency| {
+            let ident: &Ident = dependency.path.borrow();
+            live.contains(&ident.to_string())
+        });
+        self.arrows.retain(|arrow| {
+            let ident: &Ident = arrow.path.borrow();
+            live.contains(&ident.to_string())
+        });
+    }
+
+    pub fn instances(&self) -> Vec<&ExprInstance> {
+        self.dependencies
+            .iter()
+            .filter_map(|dependency| match &dependency.expr {
+                Expr::Instance(instance) => Some(instance),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+struct VarDependency<'a> {
+    pub dependencies: HashMap<Var<'a>, HashSet<Var<'a>>>,
+    pub arrows: HashMap<Var<'a>, HashSet<Var<'a>>>,
+    // initial values of cells and arguments of instances
+    pub initials: HashMap<Var<'a>, HashSet<Var<'a>>>,
+}
+
+impl VarDependency<'_> {
+    fn new() -> Self {
+        VarDependency {
+            dependencies: HashMap::new(),
+            arrows: HashMap::new(),
+            initials: HashMap::new(),
+        }
+    }
+}
+
+// The statements are returned even with errors, so that they are type checked as well.
+pub fn deps_check(
+    input: &ItemIn,
+    output: &ItemOut,
+    args: &Option<ItemArgs>,
+    probe: &Option<ItemProbe>,
+    properties: &[Property],
+    declarations: &mut [ItemDeclaration],
+    mut frp_stmts: Vec<ItemFrpStmt>,
+) -> (OrderedStmts, Vec<UnusedVariableWarning>, Errors) {
+    let mut errors = Errors::new();
+    // collect identifiers of global let-bindings
+    let (calculation_order, live, warnings) = {
+        let errors = &mut errors;
+        let global = collect_global_idents(
+            &input,
+            &output,
+            &args,
+            probe,
+            declarations,
+            &frp_stmts,
+            errors,
+        );
+
+        let definition_order = definition_order(&frp_stmts);
+        let deps = extract_deps(&global, declarations, &mut frp_stmts, errors);
+        let sorted_dependencies = errors
+            .check(tsort::tsort(&deps.dependencies).map_err(Into::<syn::Error>::into))
+            .map(|order| order.map(|ident| ident.to_string()).rev().collect());
+        let sorted_arrows = errors
+            .check(
+                tsort::tsort(&deps.arrows)
+                    .map_err(|error| Into::<syn::Error>::into(error.between_cells())),
+            )
+            .map(|order| order.map(|ident| ident.to_string()).collect());
+        let calculation_order = match (sorted_dependencies, sorted_arrows) {
+            (Some(dependencies), Some(arrows)) if errors.is_empty() => (dependencies, arrows),
+            // the statements left out of the graph are type checked all the same
+            _ => definition_order,
+        };
+
+        // the graph misses the statements failing to be extracted
+        let live = live_vars(output, probe, properties, &deps);
+        let mut warnings = if errors.is_empty() {
+            unused_vars(input, args, &deps, &live)
+        } else {
+            vec![]
+        };
+        if cfg!(feature = "deny-unused") {
+            warnings.drain(..).for_each(|warning| errors.push(warning));
+        }
+
+        let live = live.into_iter().map(|ident| ident.to_string()).collect();
+        (calculation_order, live, warnings)
+    };
+
+    (
+        generate_ordered_stmts(frp_stmts, calculation_order, live),
+        warnings,
+        errors,
+    )
+}
+
+fn definition_order(frp_stmts: &[ItemFrpStmt]) -> (Vec<String>, Vec<String>) {
+    let mut dependencies = vec![];
+    let mut arrows = vec![];
+    for frp_stmt in frp_stmts.iter() {
+        match frp_stmt {
+            ItemFrpStmt::Dependency(dependency) => {
+                let ident: &Ident = dependency.path.borrow();
+                dependencies.push(ident.to_string());
+            }
+            ItemFrpStmt::Arrow(arrow) => {
+                let ident: &Ident = arrow.path.borrow();
+                arrows.push(ident.to_string());
+            }
+            ItemFrpStmt::Destructure(_) => unreachable!(),
+        }
+    }
+    (dependencies, arrows)
+}
+
+// walks the dependencies backwards from the outputs, probes and properties
+fn live_vars<'a>(
+    output: &'a ItemOut,
+    probe: &'a Option<ItemProbe>,
+    properties: &'a [Property],
+    deps: &VarDependency<'a>,
+) -> HashSet<Var<'a>> {
+    let mut live = HashSet::new();
+    let mut stack: Vec<Var> = output
+        .fields
+        .iter()
+        .chain(probe.iter().flat_map(|probe| probe.fields.iter()))
+        .map(|field| &field.ident)
+        .chain(properties.iter().map(|property| &property.signal))
+        .collect();
+    while let Some(var) = stack.pop() {
+        if !live.insert(var) {
+            continue;
+        }
+        for uses in [&deps.dependencies, &deps.arrows, &deps.initials].iter() {
+            if let Some(uses) = uses.get(var) {
+                stack.extend(uses.iter().filter(|var| !live.contains(*var)));
+            }
+        }
+    }
+    live
+}
+
+fn unused_vars(
+    input: &ItemIn,
+    args: &Option<ItemArgs>,
+    deps: &VarDependency,
+    live: &HashSet<Var>,
+) -> Vec<UnusedVariableWarning> {
+    let mut signals: Vec<_> = deps
+        .dependencies
+        .keys()
+        .map(|ident| (*ident, "signal"))
+        .chain(deps.arrows.keys().map(|ident| (*ident, "cell")))
+        // hidden variables are reported through the user-defined ones
+        .filter(|(ident, _)| !ident.to_string().starts_with("__"))
+        .collect();
+    signals.sort();
+
+    let fields = input
+        .fields
+        .iter()
+        .map(|field| (&field.ident, "input"))
+        .chain(
+            args.iter()
+                .flat_map(|args| args.fields.iter())
+                .map(|field| (&field.ident, "argument")),
+        );
+
+    signals
+        .into_iter()
+        .chain(fields)
+        .filter(|(ident, _)| !live.contains(ident))
+        .map(|(ident, kind)| UnusedVariableWarning::new(ident, kind))
+        .collect()
+}
+
+fn generate_ordered_stmts(
+    frp_stmts: Vec<ItemFrpStmt>,
+    calculation_order: (Vec<String>, Vec<String>),
+    live: HashSet<String>,
+) -> OrderedStmts {
+    let mut deps_map = HashMap::new();
+    let mut arrows_map = HashMap::new();
+    let mut dependencies = vec![];
+    let mut arrows = FrpStmtArrows::new();
+
+    frp_stmts.into_iter().for_each(|frp_stmt| match frp_stmt {
+        ItemFrpStmt::Dependency(dep) => {
+            let ident: &Ident = dep.path.borrow();
+            deps_map.insert(ident.to_string(), dep);
+        }
+        ItemFrpStmt::Arrow(arrow) => {
+            let ident: &Ident = arrow.path.borrow();
+            arrows_map.insert(ident.to_string(), arrow);
+        }
+        // desugared in `LrfrpIR::from_ast`
+        ItemFrpStmt::Destructure(_) => unreachable!(),
+    });
+
+    calculation_order.0.iter().for_each(|s| {
+        if let Some(frp_stmt) = deps_map.remove(s) {
+            dependencies.push(frp_stmt);
+        }
+    });
+
+    calculation_order.1.iter().for_each(|s| {
+        if let Some(frp_stmt) = arrows_map.remove(s) {
+            arrows.push(frp_stmt);
+        }
+    });
+
+    OrderedStmts {
+        dependencies,
+        arrows,
+        live,
+    }
+}
+
+fn extract_deps<'a, 'b>(
+    global: &'a VarEnv,
+    declarations: &'b mut [ItemDeclaration],
+    frp_stmts: &'b mut Vec<ItemFrpStmt>,
+    errors: &mut Errors,
+) -> VarDependency<'b> {
+    declarations.iter_mut().for_each(|declaration| {
+        use ItemDeclaration::*;
+        match declaration {
+            Struct(_) => {}
+            Enum(_) => {}
+            Fn(e) => {
+                let extractor = DepExtractor::new(global);
+                errors.check(extractor.extract(e, true));
+            }
+        }
+    });
+
+    // a statement failing to be extracted is left out of the dependency graph
+    frp_stmts
+        .iter_mut()
+        .fold(VarDependency::new(), |mut acc, frp_stmt| match frp_stmt {
+            ItemFrpStmt::Dependency(FrpStmtDependency { path, expr, .. }) => {
+                let ty = global.get(Borrow::<Ident>::borrow(path));
+                if let Some(ty) = ty {
+                    path.typing(ty);
+                }
+                let ident = Borrow::<Ident>::borrow(path);
+                let extractor = DepExtractor::new(global);
+                let dep = match expr {
+                    Expr::Instance(ExprInstance {
+                        args,
+                        instance_args,
+                        ..
+                    }) => {
+                        // the arguments of instances are given only once like initial values
+                        if let Some(instance_args) = instance_args {
+                            let mut initial = HashSet::new();
+                            for field in instance_args.fields.iter_mut() {
+                                let extractor = DepExtractor::new(global);
+                                if let Some(deps) =
+                                    errors.check(extractor.extract(&mut field.expr, true))
+                                {
+                                    initial.extend(deps);
+                                }
+                            }
+                            acc.initials.insert(ident, initial);
+                        }
+                        errors.check(extractor.extract(args, false))
+                    }
+                    expr => errors.check(extractor.extract(expr, false)),
+                };
+                if let Some(dep) = dep {
+                    acc.dependencies.insert(ident, dep);
+                }
+                acc
+            }
+            ItemFrpStmt::Arrow(FrpStmtArrow {
+                path,
+                ty,
+                arrow_expr,
+                expr,
+                ..
+            }) => {
+                path.typing(&Type::from_cell(ty));
+                let ident: &Ident = Borrow::<Ident>::borrow(path);
+                let extractor = DepExtractor::new(global);
+                if let Some(arrow_expr) = arrow_expr {
+                    if let Some(initial) = errors.check(extractor.extract(arrow_expr, true)) {
+                        acc.initials.insert(ident, initial);
+                    }
+                }
+
+                let extractor = DepExtractor::new(global);
+                if let Some(dep) = errors.check(extractor.extract(expr, false)) {
+                    acc.arrows.insert(ident, dep);
+                }
+                acc
+            }
+            ItemFrpStmt::Destructure(_) => unreachable!(),
+        })
+}
+
+fn collect_global_idents(
+    input: &ItemIn,
+    output: &ItemOut,
+    args: &Option<ItemArgs>,
+    probe: &Option<ItemProbe>,
+    declarations: &[ItemDeclaration],
+    frp_stmts: &[ItemFrpStmt],
+    errors: &mut Errors,
+) -> VarEnv {
+    let mut global = VarEnv::new();
+    frp_stmts.iter().for_each(|frp_stmt| match frp_stmt {
+        ItemFrpStmt::Arrow(ref arrow) => define(
+            &mut global,
+            errors,
+            arrow.path.borrow(),
+            Type::from_cell(&arrow.ty),
+        ),
+        ItemFrpStmt::Dependency(ref dependency) => define(
+            &mut global,
+            errors,
+            dependency.path.borrow(),
+            Type::from_local(),
+        ),
+        ItemFrpStmt::Destructure(_) => unreachable!(),
+    });
+
+    // register declarations
+    declarations.iter().for_each(|declaration| {
+        use ItemDeclaration::*;
+        match declaration {
+            // types live in their own namespace
+            Struct(_) | Enum(_) => {}
+            Fn(e) => define(&mut global, errors, &e.ident, Type::from_type(&e.output)),
+        }
+    });
+
+    // ensures all the outputs will be calculated
+    output.fields.iter().for_each(|Field { ident, ty, .. }| {
+        match global.entry(ident.clone()) {
+            Entry::Occupied(ref mut e) => {
+                let untyped = e.get_mut();
+                // prevent from using the output variables as Cell
+                if let Type::Lifted(TypeLifted::Cell(_)) = untyped {
+                    errors.push(CellAsOutputError::new(e.key()));
+                } else {
+                    *untyped = Type::from_output(ty);
+                }
+            }
+            Entry::Vacant(_) => errors.push(NotCalculatedError::new(ident)),
+        }
+    });
+
+    // only the values hidden inside `run` can be probed
+    for field in probe.iter().flat_map(|probe| probe.fields.iter()) {
+        match global.get(&field.ident) {
+            Some(Type::Lifted(TypeLifted::Signal(TypeSignal::Local(_))))
+            | Some(Type::Lifted(TypeLifted::Cell(_))) => {}
+            _ => errors.push(NotProbeableError::new(&field.ident)),
+        }
+    }
+
+    // prevent from multiple definition
+    input.fields.iter().for_each(|Field { ident, ty, .. }| {
+        define(&mut global, errors, ident, Type::from_input(ty))
+    });
+
+    // register args if given
+    if let Some(ref args) = args {
+        for field in args.fields.iter() {
+            define(
+                &mut global,
+                errors,
+                &field.ident,
+                Type::from_args(&field.ty),
+            );
+        }
+    }
+
+    global
+}
+
+// later definitions of the same name are reported and ignored
+fn define(global: &mut VarEnv, errors: &mut Errors, ident: &Ident, ty: Type) {
+    match global.entry(ident.clone()) {
+        Entry::Vacant(e) => {
+            e.insert(ty);
+        }
+        Entry::Occupied(_) => errors.push(MultipleDefinitionError::new(ident)),
+    }
+}
@@ -1,11 +1,14 @@
 use super::types::{Dependency, TyCtx, TyCtxRef, VarEnv};
-use crate::ast::expressions::{ArrowExpr, Expr, ExprBlock, ExprCall, ExprPath};
+use crate::ast::expressions::{
+    ArrowExpr, Builtin, Expr, ExprBlock, ExprCall, ExprMatch, ExprPath, ExprStruct,
+};
 use crate::ast::statements::Stmt;
 use crate::ast::ItemFn;
+use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::ops::DerefMut;
 use syn::punctuated::Punctuated;
-use syn::Result;
+use syn::{Ident, Result};
 
 // TODO: simpler implementation
 
@@ -81,18 +84,56 @@ impl<'a> DepsTrailer<'a> for Expr {
 
             Call(e) => e.deps_trailer(context),
 
-            e => unimplemented!("deps_trailer impl: {:?}", e),
+            Struct(e) => e.deps_trailer(context),
+            Match(e) => e.deps_trailer(context),
+            Tuple(e) => e.elems.deps_trailer(context),
+            Field(e) => e.base.deps_trailer(context),
+
+            Cast(e) => e.expr.deps_trailer(context),
+            Type(e) => e.expr.deps_trailer(context),
+
+            Index(e) => {
+                e.expr.deps_trailer(context);
+                e.index.deps_trailer(context);
+            }
+            List(e) => e.elems.deps_trailer(context),
+            Repeat(e) => e.expr.deps_trailer(context),
+
+            TypedExpr(e, _) => e.deps_trailer(context),
+
+            // the outputs of an instance depend on all of its inputs
+            Instance(e) => e.args.deps_trailer(context),
+
+            // lifted into cells in `LrfrpIR::from_ast`
+            Init(_) | Pre(_) => unreachable!(),
         }
     }
 }
 
 impl<'a> DepsTrailer<'a> for ExprCall {
     fn deps_trailer(&'a mut self, context: Context<'_, '_, '_, 'a>) {
-        context.insert_variable(&mut self.func.path);
+        // user-defined functions shadow the built-in combinators
+        let ident: &Ident = self.func.borrow();
+        self.builtin = Builtin::from_ident(ident).filter(|_| !context.is_defined(ident));
+        if self.builtin.is_none() {
+            context.insert_variable(&mut self.func.path);
+        }
         self.args.deps_trailer(context);
     }
 }
 
+impl<'a> DepsTrailer<'a> for ExprStruct {
+    fn deps_trailer(&'a mut self, context: Context<'_, '_, '_, 'a>) {
+        // the path names a type, not a variable
+        for field in self.fields.iter_mut() {
+            field.expr.deps_trailer(context);
+        }
+        if let Some(rest) = &mut self.rest {
+            rest.deps_trailer(context);
+        }
+    }
+}
+
 impl<'a> DepsTrailer<'a> for ExprPath {
     fn deps_trailer(&'a mut self, context: Context<'_, '_, '_, 'a>) {
         context.insert_variable(&mut self.path);
@@ -117,6 +158,23 @@ impl<'a> DepsTrailer<'a> for ExprBlock {
                 Stmt::Expr(e) => e.deps_trailer(context),
             }
         }
+        context.unscoped();
+    }
+}
+
+impl<'a> DepsTrailer<'a> for ExprMatch {
+    fn deps_trailer(&'a mut self, context: Context<'_, '_, '_, 'a>) {
+        self.expr.deps_trailer(context);
+        for arm in self.arms.iter_mut() {
+            // pattern variables are visible only in the guard and the body of the arm
+            context.scoped();
+            context.insert_local(&mut arm.pat);
+            if let Some((_, guard)) = &mut arm.guard {
+                guard.deps_trailer(context);
+            }
+            arm.body.deps_trailer(context);
+            context.unscoped();
+        }
     }
 }
 
@@ -0,0 +1,529 @@
+use super::types::{TypeLifted, Var};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use std::fmt;
+use syn::Ident;
+
+#[derive(Debug)]
+pub struct UndefinedVariableError(Ident);
+
+impl UndefinedVariableError {
+    pub fn new(ident: Var) -> Self {
+        UndefinedVariableError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for UndefinedVariableError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!("use of undefined variable: `{}`", token.to_string());
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct MultipleDefinitionError(Ident);
+
+impl MultipleDefinitionError {
+    pub fn new(ident: Var) -> Self {
+        MultipleDefinitionError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for MultipleDefinitionError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!("multiple definition: `{}`", token.to_string());
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct CellAsOutputError(Ident);
+
+impl CellAsOutputError {
+    pub fn new(ident: Var) -> Self {
+        CellAsOutputError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for CellAsOutputError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!("use delayed variable `{}` as output", token.to_string());
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct NotCalculatedError(Ident);
+
+impl NotCalculatedError {
+    pub fn new(ident: &Ident) -> Self {
+        NotCalculatedError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for NotCalculatedError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!("output variable `{}` not calculated", token.to_string());
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct NotProbeableError(Ident);
+
+impl NotProbeableError {
+    pub fn new(ident: &Ident) -> Self {
+        NotProbeableError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for NotProbeableError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!(
+            "probed variable `{}` is not a local signal or cell",
+            token.to_string()
+        );
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct LiftedTypeNotAllowedError(Ident, TypeLifted);
+
+impl LiftedTypeNotAllowedError {
+    pub fn new(var: Var, type_lifted: &TypeLifted) -> Self {
+        LiftedTypeNotAllowedError(var.clone(), type_lifted.clone())
+    }
+}
+
+impl Into<syn::Error> for LiftedTypeNotAllowedError {
+    fn into(self) -> syn::Error {
+        let token = self.0;
+        let ty = &self.1;
+        let message = format!(
+            "Variable `{}` has lifted type `{}`",
+            token.to_string(),
+            ty.to_string()
+        );
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+// Every definition in `cycle` depends on the next one, and the last one on the first.
+// Each pair holds the defined variable and the use of the next one in the definition.
+#[derive(Debug)]
+pub struct CyclicDependencyError {
+    cycle: Vec<(Ident, Ident)>,
+    between_cells: bool,
+}
+
+impl CyclicDependencyError {
+    pub fn new(cycle: Vec<(Var, Var)>) -> Self {
+        CyclicDependencyError {
+            cycle: cycle
+                .into_iter()
+                .map(|(defined, used)| (defined.clone(), used.clone()))
+                .collect(),
+            between_cells: false,
+        }
+    }
+
+    // the cycle is among the updates of cells rather than the definitions of signals
+    pub fn between_cells(mut self) -> Self {
+        self.between_cells = true;
+        self
+    }
+}
+
+impl Into<syn::Error> for CyclicDependencyError {
+    fn into(self) -> syn::Error {
+        let (head, _) = &self.cycle[0];
+        let path = self
+            .cycle
+            .iter()
+            .map(|(defined, _)| defined.to_string())
+            .chain(Some(head.to_string()))
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        let mut error =
+            syn::Error::new_spanned(head, format!("cyclic dependency found: `{}`", path));
+
+        for (defined, used) in self.cycle.iter() {
+            let message = if self.between_cells {
+                format!("the update of `{}` reads `{}` here", defined, used)
+            } else {
+                format!("`{}` depends on `{}` here", defined, used)
+            };
+            error.combine(syn::Error::new_spanned(used, message));
+        }
+
+        let (defined, used) = &self.cycle[self.cycle.len() - 1];
+        let hint = if self.between_cells {
+            format!(
+                "help: read `{}` through a signal such as `let {}_now = {};` in the update of `{}` to break the cycle",
+                used, used, used, defined,
+            )
+        } else {
+            format!(
+                "help: use `pre {}` or a cell defined with `delay` here to break the cycle",
+                used,
+            )
+        };
+        error.combine(syn::Error::new_spanned(used, hint));
+        error
+    }
+}
+
+#[derive(Debug)]
+pub struct MismatchedTypesError(Span, String, String);
+
+impl MismatchedTypesError {
+    pub fn new(span: Span, expected: &impl fmt::Display, found: &impl fmt::Display) -> Self {
+        MismatchedTypesError(span, expected.to_string(), found.to_string())
+    }
+}
+
+impl Into<syn::Error> for MismatchedTypesError {
+    fn into(self) -> syn::Error {
+        let message = format!(
+            "mismatched types: expected `{}`, found `{}`",
+            self.1, self.2
+        );
+        syn::Error::new(self.0, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidOperandError(Span, String, &'static str);
+
+impl InvalidOperandError {
+    pub fn new(span: Span, found: &impl fmt::Display, expected: &'static str) -> Self {
+        InvalidOperandError(span, found.to_string(), expected)
+    }
+}
+
+impl Into<syn::Error> for InvalidOperandError {
+    fn into(self) -> syn::Error {
+        let message = format!("expected {} type, found `{}`", self.2, self.1);
+        syn::Error::new(self.0, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct NotAFunctionError(Ident);
+
+impl NotAFunctionError {
+    pub fn new(ident: Var) -> Self {
+        NotAFunctionError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for NotAFunctionError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!("`{}` is not a function", token);
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct ArgumentCountError(&'static str, Ident, usize, usize);
+
+impl ArgumentCountError {
+    pub fn new(ident: Var, expected: usize, found: usize) -> Self {
+        ArgumentCountError("function", ident.clone(), expected, found)
+    }
+
+    pub fn variant(ident: Var, expected: usize, found: usize) -> Self {
+        ArgumentCountError("variant", ident.clone(), expected, found)
+    }
+}
+
+impl Into<syn::Error> for ArgumentCountError {
+    fn into(self) -> syn::Error {
+        let token = &self.1;
+        let message = format!(
+            "{} `{}` takes {} argument(s) but {} were supplied",
+            self.0, token, self.2, self.3
+        );
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownStructError(Ident);
+
+impl UnknownStructError {
+    pub fn new(ident: Var) -> Self {
+        UnknownStructError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for UnknownStructError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!("cannot find struct `{}`", token);
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct NoFieldError(Span, String, String);
+
+impl NoFieldError {
+    pub fn new(span: Span, ty: &impl fmt::Display, member: &impl fmt::Display) -> Self {
+        NoFieldError(span, ty.to_string(), member.to_string())
+    }
+}
+
+impl Into<syn::Error> for NoFieldError {
+    fn into(self) -> syn::Error {
+        let message = format!("no field `{}` on type `{}`", self.2, self.1);
+        syn::Error::new(self.0, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingFieldError(&'static str, Ident, Ident);
+
+impl MissingFieldError {
+    pub fn new(ident: Var, field: Var) -> Self {
+        MissingFieldError("initializer", ident.clone(), field.clone())
+    }
+
+    pub fn pattern(ident: Var, field: Var) -> Self {
+        MissingFieldError("pattern", ident.clone(), field.clone())
+    }
+}
+
+impl Into<syn::Error> for MissingFieldError {
+    fn into(self) -> syn::Error {
+        let token = &self.1;
+        let message = format!("missing field `{}` in {} of `{}`", self.2, self.0, token);
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+// `None` for the `Some` and `None` of events, which have no enum
+#[derive(Debug)]
+pub struct UnknownVariantError(Option<Ident>, Ident);
+
+impl UnknownVariantError {
+    pub fn new(ident: Var, variant: Var) -> Self {
+        UnknownVariantError(Some(ident.clone()), variant.clone())
+    }
+
+    pub fn event(variant: Var) -> Self {
+        UnknownVariantError(None, variant.clone())
+    }
+}
+
+impl Into<syn::Error> for UnknownVariantError {
+    fn into(self) -> syn::Error {
+        let token = &self.1;
+        let message = match &self.0 {
+            Some(ident) => format!("no variant `{}` in enum `{}`", token, ident),
+            None => format!(
+                "cannot find variant `{}`, expected `Some`, `None` or a variant qualified by its enum",
+                token
+            ),
+        };
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct NonExhaustivePatternsError(Span, Vec<String>);
+
+impl NonExhaustivePatternsError {
+    pub fn new(span: Span, missing: Vec<String>) -> Self {
+        NonExhaustivePatternsError(span, missing)
+    }
+}
+
+impl Into<syn::Error> for NonExhaustivePatternsError {
+    fn into(self) -> syn::Error {
+        let missing = self
+            .1
+            .iter()
+            .map(|pat| format!("`{}`", pat))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let message = format!("non-exhaustive patterns: {} not covered", missing);
+        syn::Error::new(self.0, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct UnknownEnumError(Ident);
+
+impl UnknownEnumError {
+    pub fn new(ident: Var) -> Self {
+        UnknownEnumError(ident.clone())
+    }
+}
+
+impl Into<syn::Error> for UnknownEnumError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!("cannot find enum `{}`", token);
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct NotIndexableError(Span, String);
+
+impl NotIndexableError {
+    pub fn new(span: Span, ty: &impl fmt::Display) -> Self {
+        NotIndexableError(span, ty.to_string())
+    }
+}
+
+impl Into<syn::Error> for NotIndexableError {
+    fn into(self) -> syn::Error {
+        let message = format!("cannot index into a value of type `{}`", self.1);
+        syn::Error::new(self.0, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct IndexOutOfBoundsError(Span, usize, u64);
+
+impl IndexOutOfBoundsError {
+    pub fn new(span: Span, len: usize, index: u64) -> Self {
+        IndexOutOfBoundsError(span, len, index)
+    }
+}
+
+impl Into<syn::Error> for IndexOutOfBoundsError {
+    fn into(self) -> syn::Error {
+        let message = format!(
+            "index out of bounds: the length is {} but the index is {}",
+            self.1, self.2
+        );
+        syn::Error::new(self.0, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct TemporalNotAllowedError(Span, &'static str, &'static str);
+
+impl TemporalNotAllowedError {
+    pub fn new(span: Span, operator: &'static str, place: &'static str) -> Self {
+        TemporalNotAllowedError(span, operator, place)
+    }
+}
+
+impl Into<syn::Error> for TemporalNotAllowedError {
+    fn into(self) -> syn::Error {
+        let message = format!("`{}` is not allowed in {}", self.1, self.2);
+        syn::Error::new(self.0, message)
+    }
+}
+
+#[derive(Debug)]
+pub struct LocalInTemporalError(Ident, &'static str);
+
+impl LocalInTemporalError {
+    pub fn new(ident: Var, operator: &'static str) -> Self {
+        LocalInTemporalError(ident.clone(), operator)
+    }
+}
+
+impl Into<syn::Error> for LocalInTemporalError {
+    fn into(self) -> syn::Error {
+        let token = &self.0;
+        let message = format!(
+            "`{}` cannot refer to the local variable `{}`",
+            self.1, token
+        );
+        syn::Error::new_spanned(token, message)
+    }
+}
+
+// Unused variables are warned about by using a deprecated constant at their spans,
+// since proc macros cannot emit warnings on stable.
+#[derive(Debug)]
+pub struct UnusedVariableWarning(Ident, &'static str);
+
+impl UnusedVariableWarning {
+    pub fn new(ident: Var, kind: &'static str) -> Self {
+        UnusedVariableWarning(ident.clone(), kind)
+    }
+
+    fn message(&self) -> String {
+        format!("unused {} `{}`", self.1, self.0)
+    }
+}
+
+impl ToTokens for UnusedVariableWarning {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = &self.0;
+        let function = format_ident!("__unused_{}_{}", self.1, ident);
+        let note = self.message();
+        tokens.extend(quote! {
+            #[allow(dead_code, non_snake_case)]
+            fn #function() {
+                #[deprecated(note = #note)]
+                #[allow(non_upper_case_globals)]
+                const #ident: () = ();
+                #ident
+            }
+        });
+    }
+}
+
+impl Into<syn::Error> for UnusedVariableWarning {
+    fn into(self) -> syn::Error {
+        syn::Error::new_spanned(&self.0, self.message())
+    }
+}
+
+// Accumulates the errors of a phase so that all of them are reported at once.
+#[derive(Debug, Default)]
+pub struct Errors(Vec<syn::Error>);
+
+impl Errors {
+    pub fn new() -> Self {
+        Errors(vec![])
+    }
+
+    pub fn push<E>(&mut self, e: E)
+    where
+        E: Into<syn::Error>,
+    {
+        self.0.push(e.into())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // keeps the error if any and returns the value otherwise
+    pub fn check<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.0.push(e);
+                None
+            }
+        }
+    }
+
+    pub fn finish(self) -> syn::Result<()> {
+        let mut iter = self.0.into_iter();
+        match iter.next() {
+            Some(head) => Err(iter.fold(head, |mut acc, error| {
+                acc.combine(error);
+                acc
+            })),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use super::error::Errors;
+use crate::ast::expressions::{Builtin, Expr, ExprInstance, ExprTuple};
+use crate::ast::patterns::Pat;
+use crate::ast::{ItemDeclaration, ItemFrpStmt, ItemMod};
+
+use std::borrow::Borrow;
+use std::collections::HashSet;
+
+use syn::punctuated::Punctuated;
+use syn::{Ident, Result};
+
+// Resolves instances of other programs defined by whole signals. Besides
+// `Module(..) with { .. }`, a call to an undeclared name starting with an uppercase
+// letter is an instance, since functions and built-in combinators are lowercase.
+// Each instance gets its own cell named after the module.
+pub fn resolve(
+    module: &ItemMod,
+    declarations: &[ItemDeclaration],
+    frp_stmts: &mut [ItemFrpStmt],
+    errors: &mut Errors,
+) {
+    let functions: HashSet<&Ident> = declarations
+        .iter()
+        .filter_map(|declaration| match declaration {
+            ItemDeclaration::Fn(item_fn) => Some(&item_fn.ident),
+            _ => None,
+        })
+        .collect();
+
+    let mut count = 0;
+    for frp_stmt in frp_stmts.iter_mut() {
+        let (expr, pat) = match frp_stmt {
+            ItemFrpStmt::Dependency(dependency) => (&mut dependency.expr, None),
+            ItemFrpStmt::Destructure(destructure) => {
+                (&mut destructure.expr, Some(&destructure.pat))
+            }
+            ItemFrpStmt::Arrow(_) => continue,
+        };
+
+        if let Expr::Call(call) = expr {
+            let ident: &Ident = call.func.borrow();
+            let is_instance = !call.func.path.is_qualified()
+                && !functions.contains(ident)
+                && Builtin::from_ident(ident).is_none()
+                && ident.to_string().starts_with(char::is_uppercase);
+            if is_instance {
+                let placeholder = Expr::Tuple(ExprTuple {
+                    paren_token: Default::default(),
+                    elems: Punctuated::new(),
+                });
+                let call = match std::mem::replace(expr, placeholder) {
+                    Expr::Call(call) => call,
+                    _ => unreachable!(),
+                };
+                *expr = Expr::Instance(ExprInstance::new(call, None));
+            }
+        }
+
+        let instance = match expr {
+            Expr::Instance(instance) => instance,
+            _ => continue,
+        };
+        if instance.module == module.name {
+            errors.push(syn::Error::new(
+                instance.module.span(),
+                "a program cannot instantiate itself",
+            ));
+            continue;
+        }
+        instance.cell = Ident::new(
+            &format!("__{}_{}", instance.module, count),
+            instance.module.span(),
+        );
+        count += 1;
+
+        match pat.map(outputs) {
+            Some(Ok(outputs)) => instance.outputs = Some(outputs),
+            Some(Err(error)) => errors.push(error),
+            None => {}
+        }
+    }
+}
+
+// the outputs of an instance are bound by a flat tuple pattern
+fn outputs(pat: &Pat) -> Result<usize> {
+    match pat {
+        Pat::Tuple(p) if p.dot2_token.is_none() => {
+            for pat in p.front.iter() {
+                match pat {
+                    Pat::Ident(p) if p.subpat.is_none() => {}
+                    Pat::Wild(_) => {}
+                    pat => {
+                        return Err(syn::Error::new_spanned(
+                            pat,
+                            "expected an identifier bound to an output of the instance",
+                        ))
+                    }
+                }
+            }
+            Ok(p.front.len())
+        }
+        pat => Err(syn::Error::new_spanned(
+            pat,
+            "expected a tuple of the outputs of the instance",
+        )),
+    }
+}
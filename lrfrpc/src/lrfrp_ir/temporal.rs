@@ -0,0 +1,375 @@
+use super::error::{Errors, LocalInTemporalError, TemporalNotAllowedError};
+
+use crate::ast::custom_keywords::pre;
+use crate::ast::expressions::{
+    ArrowExpr, Builtin, Expr, ExprCall, ExprIf, ExprInit, ExprLit, ExprPath, ExprPre, ExprTuple,
+};
+use crate::ast::literals::Lit;
+use crate::ast::path::Path;
+use crate::ast::patterns::Pat;
+use crate::ast::statements::Stmt;
+use crate::ast::types::{Type, TypeInfer, TypePath};
+use crate::ast::{FrpStmtArrow, FrpStmtDependency, ItemDeclaration, ItemFrpStmt};
+
+use std::borrow::Borrow;
+use std::collections::HashSet;
+
+use proc_macro2::Span;
+use syn::punctuated::Punctuated;
+use syn::{Ident, LitBool, Result};
+
+// Lifts `pre e`, `a -> b` and `hold(init, ev)` out of expressions. Every `pre e` becomes
+// a hidden cell updated with `e`, and `a -> b` becomes `if __first then a else b` where
+// `__first` is a hidden cell which is `True` only at the first instant. `hold(init, ev)`
+// becomes a hidden signal `__hold = hold(init -> pre __hold, ev)`, whose inner `hold` only
+// picks the value of `ev` if it occurs.
+pub fn lift(declarations: &mut [ItemDeclaration], frp_stmts: &mut Vec<ItemFrpStmt>) -> Result<()> {
+    let functions = declarations
+        .iter()
+        .filter_map(|declaration| match declaration {
+            ItemDeclaration::Fn(item_fn) => Some(item_fn.ident.clone()),
+            _ => None,
+        })
+        .collect();
+    let mut lifter = Lifter::new(functions);
+
+    for declaration in declarations.iter_mut() {
+        if let ItemDeclaration::Fn(item_fn) = declaration {
+            lifter.forbidden = Some("function declarations");
+            lifter.visit(&mut item_fn.expr);
+        }
+    }
+
+    for frp_stmt in frp_stmts.iter_mut() {
+        match frp_stmt {
+            ItemFrpStmt::Dependency(dependency) => match &mut dependency.expr {
+                Expr::Instance(instance) => {
+                    // arguments are given once when the instance is created
+                    if let Some(instance_args) = &mut instance.instance_args {
+                        lifter.forbidden = Some("arguments of instances");
+                        for field in instance_args.fields.iter_mut() {
+                            lifter.visit(&mut field.expr);
+                        }
+                    }
+                    lifter.forbidden = None;
+                    instance.args.iter_mut().for_each(|arg| lifter.visit(arg));
+                }
+                expr => {
+                    lifter.forbidden = None;
+                    lifter.visit(expr);
+                }
+            },
+            ItemFrpStmt::Arrow(arrow) => {
+                // initial values are calculated before any input arrives
+                if let Some(arrow_expr) = &mut arrow.arrow_expr {
+                    lifter.forbidden = Some("initial values of `delay`");
+                    lifter.visit(&mut arrow_expr.expr);
+                }
+                lifter.forbidden = None;
+                lifter.visit(&mut arrow.expr);
+            }
+            // desugared in `LrfrpIR::from_ast`
+            ItemFrpStmt::Destructure(_) => unreachable!(),
+        }
+    }
+
+    lifter.finish(frp_stmts)
+}
+
+struct Lifter {
+    // user-defined functions shadow `hold`
+    functions: HashSet<Ident>,
+    cells: Vec<FrpStmtArrow>,
+    signals: Vec<FrpStmtDependency>,
+    first: Option<Ident>,
+    // variables bound by blocks and match arms around the current expression
+    locals: Vec<Ident>,
+    // the number of the variables bound outside of each enclosing `pre` or `hold`
+    pre_scopes: Vec<(usize, &'static str)>,
+    forbidden: Option<&'static str>,
+    errors: Errors,
+}
+
+impl Lifter {
+    fn new(functions: HashSet<Ident>) -> Self {
+        Lifter {
+            functions,
+            cells: vec![],
+            signals: vec![],
+            first: None,
+            locals: vec![],
+            pre_scopes: vec![],
+            forbidden: None,
+            errors: Errors::new(),
+        }
+    }
+
+    fn visit(&mut self, expr: &mut Expr) {
+        self.visit_children(expr);
+
+        match expr {
+            Expr::Pre(e) => {
+                if let Some(place) = self.forbidden {
+                    self.errors
+                        .push(TemporalNotAllowedError::new(e.pre_token.span, "pre", place));
+                    return;
+                }
+                let ident = Ident::new(&format!("__pre_{}", self.cells.len()), e.pre_token.span);
+                let mut e = match std::mem::replace(expr, path_expr(&ident)) {
+                    Expr::Pre(e) => *e.expr,
+                    _ => unreachable!(),
+                };
+                // the cell is updated with `e` alone, where parentheses are unused
+                while let Expr::Paren(paren) = e {
+                    e = *paren.expr;
+                }
+                self.cells.push(hidden_cell(ident, None, e));
+            }
+            Expr::Init(e) => {
+                if let Some(place) = self.forbidden {
+                    let span = e.arrow_token.spans[0];
+                    self.errors
+                        .push(TemporalNotAllowedError::new(span, "->", place));
+                    return;
+                }
+                let first = self
+                    .first
+                    .get_or_insert_with(|| Ident::new("__first", Span::call_site()))
+                    .clone();
+                let e = match std::mem::replace(expr, unit_expr()) {
+                    Expr::Init(e) => e,
+                    _ => unreachable!(),
+                };
+                *expr = Expr::If(ExprIf {
+                    if_token: Default::default(),
+                    cond: Box::new(path_expr(&first)),
+                    then_token: Default::default(),
+                    then_branch: e.lhs,
+                    else_token: Default::default(),
+                    else_branch: e.rhs,
+                });
+            }
+            Expr::Call(e) if self.is_hold(e) => {
+                let func: &Ident = e.func.borrow();
+                if let Some(place) = self.forbidden {
+                    self.errors
+                        .push(TemporalNotAllowedError::new(func.span(), "hold", place));
+                    return;
+                }
+                let span = func.span();
+                let ident = Ident::new(&format!("__hold_{}", self.signals.len()), span);
+                let e = match std::mem::replace(expr, path_expr(&ident)) {
+                    Expr::Call(e) => e,
+                    _ => unreachable!(),
+                };
+
+                let mut args = e.args.into_iter();
+                let (init, ev) = match (args.next(), args.next()) {
+                    (Some(init), Some(ev)) => (init, ev),
+                    _ => unreachable!(),
+                };
+                let fallback = Expr::Init(ExprInit {
+                    lhs: Box::new(init),
+                    arrow_token: Default::default(),
+                    rhs: Box::new(Expr::Pre(ExprPre {
+                        pre_token: pre { span },
+                        expr: Box::new(path_expr(&ident)),
+                    })),
+                });
+                let mut value = Expr::Call(ExprCall {
+                    func: e.func,
+                    paren_token: e.paren_token,
+                    args: vec![fallback, ev].into_iter().collect(),
+                    builtin: Some(Builtin::Hold),
+                });
+                // lifts `->` and `pre` introduced above
+                self.visit(&mut value);
+                self.signals.push(FrpStmtDependency {
+                    let_token: Default::default(),
+                    path: Path::from(ident),
+                    eq_token: Default::default(),
+                    expr: value,
+                    semi_token: Default::default(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn is_hold(&self, e: &ExprCall) -> bool {
+        let ident: &Ident = e.func.borrow();
+        e.builtin.is_none()
+            && e.args.len() == 2
+            && Builtin::from_ident(ident) == Some(Builtin::Hold)
+            && !self.functions.contains(ident)
+    }
+
+    fn visit_children(&mut self, expr: &mut Expr) {
+        use Expr::*;
+        match expr {
+            Pre(ExprPre { expr, .. }) => {
+                self.pre_scopes.push((self.locals.len(), "pre"));
+                self.visit(expr);
+                self.pre_scopes.pop();
+            }
+            Init(ExprInit { lhs, rhs, .. }) => {
+                self.visit(lhs);
+                self.visit(rhs);
+            }
+            Path(e) => {
+                // the operand of `pre` is evaluated outside of the enclosing blocks
+                let ident: &Ident = e.path.borrow();
+                if let Some(&(bound, operator)) = self.pre_scopes.last() {
+                    if self.locals[..bound].contains(ident) {
+                        self.errors.push(LocalInTemporalError::new(ident, operator));
+                    }
+                }
+            }
+            Lit(_) => {}
+            Unary(e) => self.visit(&mut e.expr),
+            Binary(e) => {
+                self.visit(&mut e.lhs);
+                self.visit(&mut e.rhs);
+            }
+            Block(e) => {
+                let len = self.locals.len();
+                for stmt in e.stmts.iter_mut() {
+                    match stmt {
+                        Stmt::Local(local) => {
+                            self.visit(&mut local.expr);
+                            self.bind(&local.pat);
+                        }
+                        Stmt::Expr(e) => self.visit(e),
+                    }
+                }
+                self.locals.truncate(len);
+            }
+            // the arguments of `hold` are evaluated outside of the enclosing blocks as well
+            Call(e) if self.is_hold(e) => {
+                self.pre_scopes.push((self.locals.len(), "hold"));
+                e.args.iter_mut().for_each(|arg| self.visit(arg));
+                self.pre_scopes.pop();
+            }
+            Call(e) => e.args.iter_mut().for_each(|arg| self.visit(arg)),
+            Field(e) => self.visit(&mut e.base),
+            Cast(e) => self.visit(&mut e.expr),
+            If(e) => {
+                self.visit(&mut e.cond);
+                self.visit(&mut e.then_branch);
+                self.visit(&mut e.else_branch);
+            }
+            Index(e) => {
+                self.visit(&mut e.expr);
+                self.visit(&mut e.index);
+            }
+            Match(e) => {
+                self.visit(&mut e.expr);
+                for arm in e.arms.iter_mut() {
+                    let len = self.locals.len();
+                    self.bind(&arm.pat);
+                    if let Some((_, guard)) = &mut arm.guard {
+                        self.visit(guard);
+                    }
+                    self.visit(&mut arm.body);
+                    self.locals.truncate(len);
+                }
+            }
+            Paren(e) => self.visit(&mut e.expr),
+            Struct(e) => {
+                e.fields
+                    .iter_mut()
+                    .for_each(|field| self.visit(&mut field.expr));
+                if let Some(rest) = &mut e.rest {
+                    self.visit(rest);
+                }
+            }
+            Tuple(e) => e.elems.iter_mut().for_each(|e| self.visit(e)),
+            List(e) => e.elems.iter_mut().for_each(|e| self.visit(e)),
+            Repeat(e) => self.visit(&mut e.expr),
+            Type(e) => self.visit(&mut e.expr),
+            TypedExpr(e, _) => self.visit(e),
+            // whole signal definitions are handled in `lift`
+            Instance(e) => self.errors.push(syn::Error::new(
+                e.module.span(),
+                "programs can be instantiated only as whole signal definitions",
+            )),
+        }
+    }
+
+    fn bind(&mut self, pat: &Pat) {
+        match pat {
+            Pat::Ident(p) => {
+                self.locals.push(p.ident.clone());
+                if let Some((_, pat)) = &p.subpat {
+                    self.bind(pat);
+                }
+            }
+            Pat::TupleStruct(p) => p.pat.elems().for_each(|pat| self.bind(pat)),
+            Pat::Tuple(p) => p.elems().for_each(|pat| self.bind(pat)),
+            Pat::Struct(p) => p.fields.iter().for_each(|field| self.bind(&field.pat)),
+            Pat::Or(p) => p.cases.iter().for_each(|pat| self.bind(pat)),
+            _ => {}
+        }
+    }
+
+    fn finish(mut self, frp_stmts: &mut Vec<ItemFrpStmt>) -> Result<()> {
+        std::mem::take(&mut self.errors).finish()?;
+
+        if let Some(first) = self.first {
+            let ty = Type::Path(TypePath {
+                path: Path::from(Ident::new("bool", Span::call_site())),
+            });
+            let init = ArrowExpr {
+                delay_token: Default::default(),
+                count: None,
+                expr: Box::new(bool_expr(true)),
+            };
+            frp_stmts.push(ItemFrpStmt::Arrow(FrpStmtArrow {
+                ty,
+                ..hidden_cell(first, Some(init), bool_expr(false))
+            }));
+        }
+        frp_stmts.extend(self.cells.into_iter().map(ItemFrpStmt::Arrow));
+        frp_stmts.extend(self.signals.into_iter().map(ItemFrpStmt::Dependency));
+        Ok(())
+    }
+}
+
+// `let #ident: _ <- delay #init -< #expr;` whose type is inferred by the type checker
+fn hidden_cell(ident: Ident, init: Option<ArrowExpr>, expr: Expr) -> FrpStmtArrow {
+    FrpStmtArrow {
+        let_token: Default::default(),
+        path: Path::from(ident),
+        colon_token: Default::default(),
+        ty: Type::Infer(TypeInfer {
+            underscore_token: Default::default(),
+        }),
+        left_arrow_token: Default::default(),
+        arrow_expr: init,
+        rev_arrow_token: Default::default(),
+        expr,
+        semi_token: Default::default(),
+    }
+}
+
+fn path_expr(ident: &Ident) -> Expr {
+    Expr::Path(ExprPath {
+        path: Path::from(ident.clone()),
+    })
+}
+
+fn bool_expr(value: bool) -> Expr {
+    Expr::Lit(ExprLit {
+        lit: Lit::Bool(LitBool {
+            value,
+            span: Span::call_site(),
+        }),
+    })
+}
+
+fn unit_expr() -> Expr {
+    Expr::Tuple(ExprTuple {
+        paren_token: Default::default(),
+        elems: Punctuated::new(),
+    })
+}
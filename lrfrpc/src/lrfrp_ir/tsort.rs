@@ -5,7 +5,7 @@ use super::error::CyclicDependencyError;
 use super::types::Var;
 use std::fmt::Debug;
 
-use syn::Result;
+type Result<T> = std::result::Result<T, CyclicDependencyError>;
 
 #[derive(Debug, PartialEq)]
 enum SearchState {
@@ -48,10 +48,11 @@ where
     let mut all_nodes: Vec<_> = all_nodes.into_iter().collect();
     all_nodes.sort();
 
+    let mut stack = vec![];
     let result = all_nodes
         .into_iter()
         .try_fold::<_, _, Result<_>>(vec![], |mut acc, node| {
-            let v = dfs(&node, &dependencies, &mut search_states)?;
+            let v = dfs(&node, deps, &dependencies, &mut search_states, &mut stack)?;
             acc.extend(v);
             Ok(acc)
         })?;
@@ -61,8 +62,11 @@ where
 
 fn dfs<'a>(
     node: &Var<'a>,
+    deps: &'a HashMap<Var<'a>, HashSet<Var<'a>>>,
     dependencies: &HashMap<Var<'a>, HashSet<Var<'a>>>,
     search_states: &mut HashMap<Var<'a>, SearchState>,
+    // nodes being searched, each of which is used by the next one
+    stack: &mut Vec<Var<'a>>,
 ) -> Result<Vec<Var<'a>>> {
     let mut res = vec![];
 
@@ -74,16 +78,22 @@ fn dfs<'a>(
         Some(s) => match s {
             SearchState::Unsearched => *s = SearchState::Searching,
             SearchState::Searching => {
-                return Err(CyclicDependencyError::new(node).into());
+                let start = stack.iter().position(|n| n == node).unwrap_or(0);
+                return Err(cycle_error(&stack[start..], deps));
             }
             SearchState::Searched => return Ok(res),
         },
     }
 
-    for next in dependencies.get(node).into_iter().flatten() {
-        let v = dfs(next, dependencies, search_states)?;
+    // sorted so that the same cycle is reported every time
+    let mut nexts: Vec<_> = dependencies.get(node).into_iter().flatten().collect();
+    nexts.sort();
+    stack.push(*node);
+    for next in nexts {
+        let v = dfs(next, deps, dependencies, search_states, stack)?;
         res.extend(v);
     }
+    stack.pop();
 
     // unsearched -> ?
     // searching  -> searched
@@ -100,3 +110,26 @@ fn dfs<'a>(
     res.push(*node);
     Ok(res)
 }
+
+// `stack` follows the uses, so the definitions depend on each other in reverse order
+fn cycle_error<'a>(
+    stack: &[Var<'a>],
+    deps: &'a HashMap<Var<'a>, HashSet<Var<'a>>>,
+) -> CyclicDependencyError {
+    let mut defined: Vec<_> = stack.to_vec();
+    defined.reverse();
+    defined.rotate_right(1);
+
+    let cycle = defined
+        .iter()
+        .enumerate()
+        .map(|(i, node)| {
+            let next = defined[(i + 1) % defined.len()];
+            // prefer the identifiers in the definitions for their spans
+            let (defined, uses) = deps.get_key_value(node).unwrap_or_else(|| unreachable!());
+            let used = uses.get(next).unwrap_or_else(|| unreachable!());
+            (*defined, *used)
+        })
+        .collect();
+    CyclicDependencyError::new(cycle)
+}
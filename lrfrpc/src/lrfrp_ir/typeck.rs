@@ -0,0 +1,1435 @@
+use super::deps_check::OrderedStmts;
+use super::error::{
+    ArgumentCountError, Errors, IndexOutOfBoundsError, InvalidOperandError, MismatchedTypesError,
+    MissingFieldError, MultipleDefinitionError, NoFieldError, NonExhaustivePatternsError,
+    NotAFunctionError, NotIndexableError, UnknownEnumError, UnknownStructError,
+    UnknownVariantError,
+};
+use super::types::Type as VarType;
+use super::Property;
+use crate::ast::expressions::{
+    Arm, BinOp, Builtin, Expr, ExprBinary, ExprBlock, ExprCall, ExprIndex, ExprMatch, ExprStruct,
+    UnOp,
+};
+use crate::ast::literals::Lit;
+use crate::ast::path::{Path, PathArguments, PathSegment};
+use crate::ast::patterns::{Pat, PatStruct, PatTuple};
+use crate::ast::statements::Stmt;
+use crate::ast::types;
+use crate::ast::{
+    Field, ItemArgs, ItemDeclaration, ItemEnum, ItemFn, ItemIn, ItemOut, ItemProbe, ItemStruct,
+};
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Ident, Member, Result};
+
+const INT_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+];
+const FLOAT_TYPES: &[&str] = &["f32", "f64"];
+
+pub fn typeck(
+    input: &ItemIn,
+    output: &ItemOut,
+    args: &Option<ItemArgs>,
+    probe: &Option<ItemProbe>,
+    properties: &[Property],
+    declarations: &mut [ItemDeclaration],
+    body: &mut OrderedStmts,
+) -> Result<()> {
+    Typeck::new()
+        .initialize(input, output, args, declarations, body)
+        .typeck(probe, properties, declarations, body)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VarKind {
+    General,
+    Integer,
+    Float,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Ty {
+    Bool,
+    Int(&'static str),
+    Float(&'static str),
+    Tuple(Vec<Ty>),
+    // element type and length
+    Array(Box<Ty>, Box<Ty>),
+    // type-level length of arrays
+    Len(usize),
+    // `Event<T>`, lowered to `Option<T>`
+    Event(Box<Ty>),
+    // user-defined or opaque types, compared by name
+    Named(String),
+    Var(usize, VarKind),
+}
+
+impl Ty {
+    fn unit() -> Self {
+        Ty::Tuple(vec![])
+    }
+
+    fn is_numeric(&self) -> bool {
+        match self {
+            Ty::Int(_) | Ty::Float(_) => true,
+            Ty::Var(_, kind) => *kind != VarKind::General,
+            _ => false,
+        }
+    }
+
+    fn has_var(&self) -> bool {
+        match self {
+            Ty::Var(..) => true,
+            Ty::Tuple(elems) => elems.iter().any(Ty::has_var),
+            Ty::Array(elem, len) => elem.has_var() || len.has_var(),
+            Ty::Event(ty) => ty.has_var(),
+            _ => false,
+        }
+    }
+
+    fn is_integer(&self) -> bool {
+        matches!(self, Ty::Int(_) | Ty::Var(_, VarKind::Integer))
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use Ty::*;
+        match self {
+            Bool => write!(f, "bool"),
+            Int(name) | Float(name) => write!(f, "{}", name),
+            Tuple(elems) => {
+                write!(f, "(")?;
+                for (i, ty) in elems.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", ty)?;
+                }
+                if elems.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Array(elem, len) => write!(f, "[{}; {}]", elem, len),
+            Len(len) => write!(f, "{}", len),
+            Event(ty) => write!(f, "Event<{}>", ty),
+            Named(name) => write!(f, "{}", name),
+            Var(_, VarKind::General) => write!(f, "_"),
+            Var(_, VarKind::Integer) => write!(f, "{{integer}}"),
+            Var(_, VarKind::Float) => write!(f, "{{float}}"),
+        }
+    }
+}
+
+impl ToTokens for Ty {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        use Ty::*;
+        match self {
+            Bool => tokens.extend(quote! { bool }),
+            Int(name) | Float(name) => Ident::new(name, Span::call_site()).to_tokens(tokens),
+            Tuple(elems) if elems.len() == 1 => {
+                let elem = &elems[0];
+                tokens.extend(quote! { (#elem,) })
+            }
+            Tuple(elems) => tokens.extend(quote! { (#(#elems),*) }),
+            Array(elem, len) => tokens.extend(quote! { [#elem; #len] }),
+            Len(len) => Literal::usize_unsuffixed(*len).to_tokens(tokens),
+            Event(ty) => tokens.extend(quote! { Event<#ty> }),
+            Named(name) => tokens.extend(name.parse::<TokenStream>()),
+            Var(..) => tokens.extend(quote! { _ }),
+        }
+    }
+}
+
+struct MemberDisplay<'a>(&'a Member);
+
+impl fmt::Display for MemberDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Member::Named(ident) => write!(f, "{}", ident),
+            Member::Unnamed(index) => write!(f, "{}", index.index),
+        }
+    }
+}
+
+// a tuple pattern as a type, such as `(_, .., _)`
+struct TupleDisplay<'a>(&'a PatTuple);
+
+impl fmt::Display for TupleDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut elems = vec!["_"; self.0.front.len()];
+        if self.0.dot2_token.is_some() {
+            elems.push("..");
+        }
+        elems.extend(vec!["_"; self.0.back.len()]);
+        if elems.len() == 1 && self.0.dot2_token.is_none() {
+            write!(f, "(_,)")
+        } else {
+            write!(f, "({})", elems.join(", "))
+        }
+    }
+}
+
+// operand classes checked after all the constraints are solved
+#[derive(Clone, Copy, Debug)]
+enum Class {
+    Numeric,
+    Signed,
+    Integer,
+    Bitwise,
+    Primitive,
+}
+
+impl Class {
+    fn accepts(self, ty: &Ty) -> bool {
+        use Class::*;
+        match ty {
+            // unconstrained variables are left to rustc
+            Ty::Var(_, VarKind::General) => true,
+            ty => match self {
+                Numeric => ty.is_numeric(),
+                Signed => match ty {
+                    Ty::Int(name) => name.starts_with('i'),
+                    ty => ty.is_numeric(),
+                },
+                Integer => ty.is_integer(),
+                Bitwise => ty.is_integer() || *ty == Ty::Bool,
+                Primitive => ty.is_numeric() || *ty == Ty::Bool,
+            },
+        }
+    }
+
+    fn description(self) -> &'static str {
+        use Class::*;
+        match self {
+            Numeric => "numeric",
+            Signed => "signed numeric",
+            Integer => "integer",
+            Bitwise => "integer or boolean",
+            Primitive => "primitive",
+        }
+    }
+}
+
+enum VarState {
+    Unbound(VarKind),
+    Bound(Ty),
+}
+
+#[derive(Clone, Debug)]
+pub struct FnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Ty,
+}
+
+pub struct GlobalTypeEnv {
+    // Signal -> Type
+    signals: HashMap<Ident, Ty>,
+    // Function -> Signature
+    functions: HashMap<Ident, FnSig>,
+    // Type -> Member -> Type
+    definitions: HashMap<String, Vec<(Ident, Ty)>>,
+    // Enum -> Variant -> Fields
+    variants: HashMap<String, Vec<(Ident, Vec<Ty>)>>,
+}
+
+impl GlobalTypeEnv {
+    fn new() -> Self {
+        GlobalTypeEnv {
+            signals: HashMap::new(),
+            functions: HashMap::new(),
+            definitions: HashMap::new(),
+            variants: HashMap::new(),
+        }
+    }
+}
+
+pub struct LocalTypeEnv {
+    local: Vec<HashMap<Ident, Ty>>,
+}
+
+impl LocalTypeEnv {
+    fn new() -> Self {
+        LocalTypeEnv { local: vec![] }
+    }
+
+    fn scoped(&mut self) {
+        self.local.push(HashMap::new());
+    }
+
+    fn unscoped(&mut self) {
+        self.local.pop();
+    }
+
+    fn insert(&mut self, ident: &Ident, ty: Ty) {
+        if let Some(scope) = self.local.last_mut() {
+            scope.insert(ident.clone(), ty);
+        }
+    }
+
+    fn get(&self, ident: &Ident) -> Option<&Ty> {
+        self.local.iter().rev().find_map(|scope| scope.get(ident))
+    }
+}
+
+pub struct Typeck {
+    global: GlobalTypeEnv,
+    local: LocalTypeEnv,
+    // substitution of type variables
+    vars: Vec<VarState>,
+    obligations: Vec<(Ty, Class, Span)>,
+    // field accesses on values of unknown types
+    deferred: Vec<(Ty, Member, Ty, Span)>,
+    // constant indices checked against the inferred lengths
+    bounds: Vec<(Ty, u64, Span)>,
+    errors: Errors,
+}
+
+impl Typeck {
+    fn new() -> Self {
+        Typeck {
+            global: GlobalTypeEnv::new(),
+            local: LocalTypeEnv::new(),
+            vars: vec![],
+            obligations: vec![],
+            deferred: vec![],
+            bounds: vec![],
+            errors: Errors::new(),
+        }
+    }
+
+    fn initialize(
+        mut self,
+        input: &ItemIn,
+        output: &ItemOut,
+        args: &Option<ItemArgs>,
+        declarations: &[ItemDeclaration],
+        body: &OrderedStmts,
+    ) -> Self {
+        let fields = input
+            .fields
+            .iter()
+            .chain(output.fields.iter())
+            .chain(args.iter().flat_map(|args| args.fields.iter()));
+        for Field { ident, ty, .. } in fields {
+            let ty = self.lower(ty);
+            self.global.signals.insert(ident.clone(), ty);
+        }
+
+        for arrow in body.arrows.iter() {
+            let ty = self.lower(&arrow.ty);
+            let ident: &Ident = arrow.path.borrow();
+            self.global.signals.insert(ident.clone(), ty);
+        }
+
+        // local signals are inferred from their definitions
+        for dependency in body.dependencies.iter() {
+            let ident: &Ident = dependency.path.borrow();
+            if !self.global.signals.contains_key(ident) {
+                let ty = self.new_var(VarKind::General);
+                self.global.signals.insert(ident.clone(), ty);
+            }
+        }
+
+        for declaration in declarations.iter() {
+            match declaration {
+                ItemDeclaration::Struct(ItemStruct { ident, fields, .. }) => {
+                    let fields = fields
+                        .iter()
+                        .map(|Field { ident, ty, .. }| (ident.clone(), self.lower(ty)))
+                        .collect();
+                    if self
+                        .global
+                        .definitions
+                        .insert(ident.to_string(), fields)
+                        .is_some()
+                    {
+                        self.errors.push(MultipleDefinitionError::new(ident));
+                    }
+                }
+                ItemDeclaration::Enum(ItemEnum {
+                    ident, variants, ..
+                }) => {
+                    let variants = variants
+                        .iter()
+                        .map(|variant| {
+                            let fields = variant.fields.iter().map(|ty| self.lower(ty)).collect();
+                            (variant.ident.clone(), fields)
+                        })
+                        .collect();
+                    let name = ident.to_string();
+                    if self.global.definitions.contains_key(&name)
+                        || self.global.variants.insert(name, variants).is_some()
+                    {
+                        self.errors.push(MultipleDefinitionError::new(ident));
+                    }
+                }
+                ItemDeclaration::Fn(ItemFn {
+                    ident,
+                    inputs,
+                    output,
+                    ..
+                }) => {
+                    let sig = FnSig {
+                        inputs: inputs.iter().map(|arg| self.lower(&arg.ty)).collect(),
+                        output: self.lower(output),
+                    };
+                    self.global.functions.insert(ident.clone(), sig);
+                }
+            }
+        }
+
+        self
+    }
+
+    fn typeck(
+        mut self,
+        probe: &Option<ItemProbe>,
+        properties: &[Property],
+        declarations: &mut [ItemDeclaration],
+        body: &mut OrderedStmts,
+    ) -> Result<()> {
+        for declaration in declarations.iter() {
+            if let ItemDeclaration::Fn(item_fn) = declaration {
+                self.check_fn(item_fn);
+            }
+        }
+
+        for dependency in body.dependencies.iter() {
+            let ident: &Ident = dependency.path.borrow();
+            let expected = self.global.signals[ident].clone();
+            let found = self.check_expr(&dependency.expr);
+            self.expect(dependency.expr.span(), &expected, &found);
+        }
+
+        for arrow in body.arrows.iter() {
+            let ident: &Ident = arrow.path.borrow();
+            let expected = self.global.signals[ident].clone();
+            if let Some(arrow_expr) = &arrow.arrow_expr {
+                let init = self.check_expr(&arrow_expr.expr);
+                self.expect(arrow_expr.expr.span(), &expected, &init);
+            }
+            let found = self.check_expr(&arrow.expr);
+            self.expect(arrow.expr.span(), &expected, &found);
+        }
+
+        // the probed variables are checked to exist by `deps_check`
+        for Field { ident, ty, .. } in probe.iter().flat_map(|probe| probe.fields.iter()) {
+            let expected = self.lower(ty);
+            if let Some(found) = self.global.signals.get(ident).cloned() {
+                self.expect(ident.span(), &expected, &found);
+            }
+        }
+
+        // the hidden signals of properties are spanned by their conditions
+        for Property { signal, .. } in properties.iter() {
+            let found = self.global.signals[signal].clone();
+            self.expect(signal.span(), &Ty::Bool, &found);
+        }
+
+        self.check_deferred();
+        self.default_vars();
+        self.check_obligations();
+        self.check_bounds();
+
+        // write back inferred types of the cells synthesized from `pre`
+        for arrow in body.arrows.iter_mut() {
+            if let types::Type::Infer(_) = arrow.ty {
+                let ident: &Ident = arrow.path.borrow();
+                let ty = self.resolve(&self.global.signals[ident]);
+                if ty.has_var() {
+                    self.errors
+                        .push(syn::Error::new(ident.span(), "type annotations needed"));
+                } else if let Ok(ty) = syn::parse2::<types::Type>(ty.to_token_stream()) {
+                    arrow.ty = ty;
+                }
+            }
+        }
+
+        std::mem::take(&mut self.errors).finish()?;
+
+        // write back inferred types of local signals
+        for dependency in body.dependencies.iter_mut() {
+            let ident: &Ident = dependency.path.borrow();
+            let ty = self.resolve(&self.global.signals[ident]);
+            if !dependency.path.is_local() || ty.has_var() {
+                continue;
+            }
+            if let Ok(ty) = syn::parse2::<types::Type>(ty.to_token_stream()) {
+                dependency.path.typing(&VarType::from_inferred(&ty));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_fn(&mut self, item_fn: &ItemFn) {
+        let sig = self.global.functions[&item_fn.ident].clone();
+        self.local.scoped();
+        for (arg, ty) in item_fn.inputs.iter().zip(sig.inputs) {
+            self.check_pat(&arg.pat, &ty);
+        }
+        let found = self.check_expr(&item_fn.expr);
+        self.expect(item_fn.expr.span(), &sig.output, &found);
+        self.local.unscoped();
+    }
+
+    fn check_expr(&mut self, expr: &Expr) -> Ty {
+        use Expr::*;
+        match expr {
+            Lit(e) => match e.lit {
+                self::Lit::Int(_) => self.new_var(VarKind::Integer),
+                self::Lit::Float(_) => self.new_var(VarKind::Float),
+                self::Lit::Bool(_) => Ty::Bool,
+            },
+            Path(e) if e.path.is_qualified() => self.check_variant(&e.path, None),
+            Path(e) => {
+                let ident: &Ident = e.borrow();
+                match self.lookup(ident) {
+                    Some(ty) => ty,
+                    // undefined variables are reported by the dependency checker
+                    None => self.new_var(VarKind::General),
+                }
+            }
+            Paren(e) => self.check_expr(&e.expr),
+            Unary(e) => {
+                let ty = self.check_expr(&e.expr);
+                let class = match e.op {
+                    UnOp::Not(_) => Class::Bitwise,
+                    UnOp::Neg(_) => Class::Signed,
+                };
+                self.obligations.push((ty.clone(), class, e.op.span()));
+                ty
+            }
+            Binary(e) => self.check_binary(e),
+            If(e) => {
+                let cond = self.check_expr(&e.cond);
+                self.expect(e.cond.span(), &Ty::Bool, &cond);
+                let then_ty = self.check_expr(&e.then_branch);
+                let else_ty = self.check_expr(&e.else_branch);
+                self.expect(e.else_branch.span(), &then_ty, &else_ty);
+                then_ty
+            }
+            Block(e) => self.check_block(e),
+            Call(e) => self.check_call(e),
+            Cast(e) => {
+                let ty = self.check_expr(&e.expr);
+                self.obligations.push((ty, Class::Primitive, e.expr.span()));
+                self.lower(&e.ty)
+            }
+            Type(e) => {
+                let ty = self.check_expr(&e.expr);
+                let expected = self.lower(&e.ty);
+                self.expect(e.expr.span(), &expected, &ty);
+                expected
+            }
+            Struct(e) => self.check_struct(e),
+            Match(e) => self.check_match(e),
+            Tuple(e) => Ty::Tuple(e.elems.iter().map(|e| self.check_expr(e)).collect()),
+            Field(e) => {
+                let base = self.check_expr(&e.base);
+                self.check_field(&base, &e.member, e.base.span())
+            }
+            List(e) => {
+                let elem = self.new_var(VarKind::General);
+                for e in e.elems.iter() {
+                    let found = self.check_expr(e);
+                    self.expect(e.span(), &elem, &found);
+                }
+                Ty::Array(Box::new(elem), Box::new(Ty::Len(e.elems.len())))
+            }
+            Repeat(e) => {
+                let elem = self.check_expr(&e.expr);
+                Ty::Array(Box::new(elem), Box::new(Ty::Len(e.length())))
+            }
+            Index(e) => self.check_index(e),
+            TypedExpr(e, _) => self.check_expr(e),
+            // the types of the inputs and outputs are checked by rustc across modules
+            Instance(e) => {
+                for arg in e.args.iter() {
+                    self.check_expr(arg);
+                }
+                if let Some(instance_args) = &e.instance_args {
+                    for field in instance_args.fields.iter() {
+                        self.check_expr(&field.expr);
+                    }
+                }
+                match e.outputs {
+                    Some(outputs) => Ty::Tuple(
+                        (0..outputs)
+                            .map(|_| self.new_var(VarKind::General))
+                            .collect(),
+                    ),
+                    None => self.new_var(VarKind::General),
+                }
+            }
+            // lifted into cells in `LrfrpIR::from_ast`
+            Init(_) | Pre(_) => unreachable!(),
+        }
+    }
+
+    fn check_index(&mut self, e: &ExprIndex) -> Ty {
+        let base = self.check_expr(&e.expr);
+        let index = self.check_expr(&e.index);
+        self.expect(e.index.span(), &Ty::Int("usize"), &index);
+
+        let elem = self.new_var(VarKind::General);
+        let len = self.new_var(VarKind::General);
+        let array = Ty::Array(Box::new(elem.clone()), Box::new(len.clone()));
+        if !self.unify(&array, &base) {
+            let base = self.resolve(&base);
+            self.errors
+                .push(NotIndexableError::new(e.expr.span(), &base));
+            return elem;
+        }
+        if let Some(index) = e.constant() {
+            self.bounds.push((len, index, e.index.span()));
+        }
+        elem
+    }
+
+    fn check_binary(&mut self, e: &ExprBinary) -> Ty {
+        use BinOp::*;
+        let lhs = self.check_expr(&e.lhs);
+        let rhs = self.check_expr(&e.rhs);
+        let span = e.rhs.span();
+        match e.op {
+            Add(_) | Sub(_) | Mul(_) | Div(_) | Rem(_) => {
+                self.expect(span, &lhs, &rhs);
+                self.obligations
+                    .push((lhs.clone(), Class::Numeric, e.lhs.span()));
+                lhs
+            }
+            BitXor(_) | BitAnd(_) | BitOr(_) => {
+                self.expect(span, &lhs, &rhs);
+                self.obligations
+                    .push((lhs.clone(), Class::Bitwise, e.lhs.span()));
+                lhs
+            }
+            Shl(_) | Shr(_) => {
+                self.obligations
+                    .push((lhs.clone(), Class::Integer, e.lhs.span()));
+                self.obligations.push((rhs, Class::Integer, span));
+                lhs
+            }
+            Eq(_) | Lt(_) | Le(_) | Ne(_) | Ge(_) | Gt(_) => {
+                self.expect(span, &lhs, &rhs);
+                Ty::Bool
+            }
+            And(_) | Or(_) => {
+                self.expect(e.lhs.span(), &Ty::Bool, &lhs);
+                self.expect(span, &Ty::Bool, &rhs);
+                Ty::Bool
+            }
+        }
+    }
+
+    fn check_block(&mut self, e: &ExprBlock) -> Ty {
+        self.local.scoped();
+        let mut ty = Ty::unit();
+        for stmt in e.stmts.iter() {
+            match stmt {
+                Stmt::Local(local) => {
+                    let expr_ty = self.check_expr(&local.expr);
+                    self.check_pat(&local.pat, &expr_ty);
+                    ty = Ty::unit();
+                }
+                Stmt::Expr(expr) => ty = self.check_expr(expr),
+            }
+        }
+        self.local.unscoped();
+        ty
+    }
+
+    fn check_call(&mut self, e: &ExprCall) -> Ty {
+        if e.func.path.is_qualified() {
+            let args: Vec<_> = e.args.iter().collect();
+            return self.check_variant(&e.func.path, Some(&args));
+        }
+
+        if let Some(builtin) = e.builtin {
+            return self.check_builtin(builtin, e);
+        }
+
+        let ident: &Ident = e.func.borrow();
+        let arg_tys: Vec<_> = e.args.iter().map(|arg| self.check_expr(arg)).collect();
+        let sig = match self.global.functions.get(ident) {
+            Some(sig) => sig.clone(),
+            // undefined functions are reported by the dependency checker
+            None if self.lookup(ident).is_none() => return self.new_var(VarKind::General),
+            None => {
+                self.errors.push(NotAFunctionError::new(ident));
+                return self.new_var(VarKind::General);
+            }
+        };
+        if sig.inputs.len() != arg_tys.len() {
+            self.errors.push(ArgumentCountError::new(
+                ident,
+                sig.inputs.len(),
+                arg_tys.len(),
+            ));
+        } else {
+            for ((arg, expected), found) in e.args.iter().zip(sig.inputs.iter()).zip(arg_tys) {
+                self.expect(arg.span(), expected, &found);
+            }
+        }
+        sig.output
+    }
+
+    fn check_builtin(&mut self, builtin: Builtin, e: &ExprCall) -> Ty {
+        let ident: &Ident = e.func.borrow();
+        let args: Vec<_> = e.args.iter().collect();
+        if args.len() != builtin.arity() {
+            self.errors
+                .push(ArgumentCountError::new(ident, builtin.arity(), args.len()));
+            return self.new_var(VarKind::General);
+        }
+
+        let len = Box::new(self.new_var(VarKind::General));
+        match builtin {
+            Builtin::Map => {
+                let sig = self.function_arg(args[0], 1);
+                let xs = self.check_expr(args[1]);
+                let (input, output) = match sig {
+                    Some(sig) => (sig.inputs[0].clone(), sig.output),
+                    None => (
+                        self.new_var(VarKind::General),
+                        self.new_var(VarKind::General),
+                    ),
+                };
+                if let Ty::Event(_) = self.shallow_resolve(&xs) {
+                    self.expect(args[1].span(), &Ty::Event(Box::new(input)), &xs);
+                    return Ty::Event(Box::new(output));
+                }
+                self.expect(
+                    args[1].span(),
+                    &Ty::Array(Box::new(input), len.clone()),
+                    &xs,
+                );
+                Ty::Array(Box::new(output), len)
+            }
+            Builtin::Fold => {
+                let sig = self.function_arg(args[0], 2);
+                let init = self.check_expr(args[1]);
+                let xs = self.check_expr(args[2]);
+                let (acc, elem) = match sig {
+                    Some(sig) => {
+                        self.expect(args[0].span(), &sig.inputs[0], &sig.output);
+                        (sig.output, sig.inputs[1].clone())
+                    }
+                    None => (
+                        self.new_var(VarKind::General),
+                        self.new_var(VarKind::General),
+                    ),
+                };
+                self.expect(args[1].span(), &acc, &init);
+                self.expect(args[2].span(), &Ty::Array(Box::new(elem), len), &xs);
+                acc
+            }
+            Builtin::Zip => {
+                let xs = self.check_expr(args[0]);
+                let ys = self.check_expr(args[1]);
+                let x = self.new_var(VarKind::General);
+                let y = self.new_var(VarKind::General);
+                self.expect(
+                    args[0].span(),
+                    &Ty::Array(Box::new(x.clone()), len.clone()),
+                    &xs,
+                );
+                self.expect(
+                    args[1].span(),
+                    &Ty::Array(Box::new(y.clone()), len.clone()),
+                    &ys,
+                );
+                Ty::Array(Box::new(Ty::Tuple(vec![x, y])), len)
+            }
+            Builtin::When => {
+                let cond = self.check_expr(args[0]);
+                self.expect(args[0].span(), &Ty::Bool, &cond);
+                let value = self.check_expr(args[1]);
+                Ty::Event(Box::new(value))
+            }
+            Builtin::Hold => {
+                let init = self.check_expr(args[0]);
+                let ev = self.check_expr(args[1]);
+                self.expect(args[1].span(), &Ty::Event(Box::new(init.clone())), &ev);
+                init
+            }
+            Builtin::Merge => {
+                let a = self.check_expr(args[0]);
+                let b = self.check_expr(args[1]);
+                let ty = Ty::Event(Box::new(self.new_var(VarKind::General)));
+                self.expect(args[0].span(), &ty, &a);
+                self.expect(args[1].span(), &ty, &b);
+                ty
+            }
+            Builtin::Filter => {
+                let sig = self.function_arg(args[0], 1);
+                let ev = self.check_expr(args[1]);
+                let elem = match sig {
+                    Some(sig) => {
+                        self.expect(args[0].span(), &Ty::Bool, &sig.output);
+                        sig.inputs[0].clone()
+                    }
+                    None => self.new_var(VarKind::General),
+                };
+                let ty = Ty::Event(Box::new(elem));
+                self.expect(args[1].span(), &ty, &ev);
+                ty
+            }
+        }
+    }
+
+    // functions are passed to the combinators by name
+    fn function_arg(&mut self, arg: &Expr, arity: usize) -> Option<FnSig> {
+        let ident: &Ident = match arg {
+            Expr::Path(e) if !e.path.is_qualified() => e.borrow(),
+            arg => {
+                self.errors
+                    .push(syn::Error::new(arg.span(), "expected a function name"));
+                return None;
+            }
+        };
+        let sig = match self.global.functions.get(ident) {
+            Some(sig) if self.local.get(ident).is_none() => sig.clone(),
+            _ => {
+                self.errors.push(NotAFunctionError::new(ident));
+                return None;
+            }
+        };
+        if sig.inputs.len() != arity {
+            self.errors
+                .push(ArgumentCountError::new(ident, sig.inputs.len(), arity));
+            return None;
+        }
+        Some(sig)
+    }
+
+    fn check_struct(&mut self, e: &ExprStruct) -> Ty {
+        let ident: &Ident = e.path.borrow();
+        let definition = match self.global.definitions.get(&ident.to_string()) {
+            Some(definition) => definition.clone(),
+            None => {
+                self.errors.push(UnknownStructError::new(ident));
+                return self.new_var(VarKind::General);
+            }
+        };
+        let ty = Ty::Named(ident.to_string());
+
+        for field in e.fields.iter() {
+            let found = self.check_expr(&field.expr);
+            let expected = match &field.member {
+                Member::Named(member) => definition
+                    .iter()
+                    .find(|(field, _)| field == member)
+                    .map(|(_, ty)| ty.clone()),
+                Member::Unnamed(_) => None,
+            };
+            match expected {
+                Some(expected) => self.expect(field.expr.span(), &expected, &found),
+                None => self.errors.push(NoFieldError::new(
+                    member_span(&field.member),
+                    &ty,
+                    &MemberDisplay(&field.member),
+                )),
+            }
+        }
+
+        match &e.rest {
+            Some(rest) => {
+                let found = self.check_expr(rest);
+                self.expect(rest.span(), &ty, &found);
+            }
+            None => {
+                for (field, _) in definition.iter() {
+                    let initialized = e.fields.iter().any(|value| match &value.member {
+                        Member::Named(member) => member == field,
+                        Member::Unnamed(_) => false,
+                    });
+                    if !initialized {
+                        self.errors.push(MissingFieldError::new(ident, field));
+                    }
+                }
+            }
+        }
+
+        ty
+    }
+
+    fn check_field(&mut self, base: &Ty, member: &Member, span: Span) -> Ty {
+        let base = self.shallow_resolve(base);
+        let field = match (&base, member) {
+            (Ty::Tuple(elems), Member::Unnamed(index)) => elems.get(index.index as usize).cloned(),
+            (Ty::Named(name), Member::Named(member)) => self
+                .global
+                .definitions
+                .get(name)
+                .and_then(|definition| definition.iter().find(|(field, _)| field == member))
+                .map(|(_, ty)| ty.clone()),
+            // the type of the base is not known yet
+            (Ty::Var(_, VarKind::General), _) => {
+                let ty = self.new_var(VarKind::General);
+                self.deferred
+                    .push((base.clone(), member.clone(), ty.clone(), span));
+                return ty;
+            }
+            _ => None,
+        };
+        match field {
+            Some(ty) => ty,
+            None => {
+                self.errors
+                    .push(NoFieldError::new(span, &base, &MemberDisplay(member)));
+                self.new_var(VarKind::General)
+            }
+        }
+    }
+
+    // retries field accesses whose base type was inferred later
+    fn check_deferred(&mut self) {
+        loop {
+            let deferred = std::mem::take(&mut self.deferred);
+            let count = deferred.len();
+            for (base, member, ty, span) in deferred {
+                if let Ty::Var(_, VarKind::General) = self.shallow_resolve(&base) {
+                    self.deferred.push((base, member, ty, span));
+                    continue;
+                }
+                let found = self.check_field(&base, &member, span);
+                self.expect(span, &ty, &found);
+            }
+            if self.deferred.is_empty() || self.deferred.len() == count {
+                break;
+            }
+        }
+    }
+
+    // `Enum::Variant` or `Enum::Variant(args..)`
+    fn check_variant(&mut self, path: &Path, args: Option<&[&Expr]>) -> Ty {
+        let arg_tys: Vec<_> = args
+            .into_iter()
+            .flatten()
+            .map(|arg| self.check_expr(arg))
+            .collect();
+        let fields = match self.lookup_variant(path) {
+            Some(fields) => fields,
+            None => return self.new_var(VarKind::General),
+        };
+        let ident: &Ident = path.borrow();
+        if fields.len() != arg_tys.len() {
+            self.errors.push(ArgumentCountError::variant(
+                ident,
+                fields.len(),
+                arg_tys.len(),
+            ));
+        } else {
+            for ((arg, expected), found) in args.into_iter().flatten().zip(fields).zip(arg_tys) {
+                self.expect(arg.span(), &expected, &found);
+            }
+        }
+        self.variant_ty(path)
+    }
+
+    fn lookup_variant(&mut self, path: &Path) -> Option<Vec<Ty>> {
+        let (qualifier, variant) = match path {
+            Path::Qualified(qualifier, _, variant) => (&qualifier.ident, &variant.ident),
+            _ => return None,
+        };
+        let variants = match self.global.variants.get(&qualifier.to_string()) {
+            Some(variants) => variants,
+            None => {
+                self.errors.push(UnknownEnumError::new(qualifier));
+                return None;
+            }
+        };
+        match variants.iter().find(|(ident, _)| ident == variant) {
+            Some((_, fields)) => Some(fields.clone()),
+            None => {
+                self.errors
+                    .push(UnknownVariantError::new(qualifier, variant));
+                None
+            }
+        }
+    }
+
+    fn variant_ty(&self, path: &Path) -> Ty {
+        match path {
+            Path::Qualified(qualifier, _, _) => Ty::Named(qualifier.ident.to_string()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn check_match(&mut self, e: &ExprMatch) -> Ty {
+        let scrutinee = self.check_expr(&e.expr);
+        let ty = self.new_var(VarKind::General);
+        for arm in e.arms.iter() {
+            self.local.scoped();
+            self.check_pat(&arm.pat, &scrutinee);
+            if let Some((_, guard)) = &arm.guard {
+                let found = self.check_expr(guard);
+                self.expect(guard.span(), &Ty::Bool, &found);
+            }
+            let found = self.check_expr(&arm.body);
+            self.expect(arm.body.span(), &ty, &found);
+            self.local.unscoped();
+        }
+
+        let missing = self.missing_patterns(&scrutinee, &e.arms);
+        if !missing.is_empty() {
+            self.errors
+                .push(NonExhaustivePatternsError::new(e.match_token.span, missing));
+        }
+        ty
+    }
+
+    // returns the patterns not covered by the arms without guards
+    fn missing_patterns(&self, scrutinee: &Ty, arms: &[Arm]) -> Vec<String> {
+        let pats: Vec<_> = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .map(|arm| &arm.pat)
+            .collect();
+        if pats.iter().any(|pat| is_irrefutable(pat)) {
+            return vec![];
+        }
+
+        let candidates: Vec<String> = match self.shallow_resolve(scrutinee) {
+            Ty::Bool => vec!["True".to_string(), "False".to_string()],
+            Ty::Event(_) => vec!["Some(_)".to_string(), "None".to_string()],
+            Ty::Named(name) => match self.global.variants.get(&name) {
+                Some(variants) => variants
+                    .iter()
+                    .map(|(variant, fields)| match fields.len() {
+                        0 => format!("{}::{}", name, variant),
+                        n => format!("{}::{}({})", name, variant, vec!["_"; n].join(", ")),
+                    })
+                    .collect(),
+                None => vec!["_".to_string()],
+            },
+            // rustc checks the patterns of types which are not known yet
+            Ty::Var(_, VarKind::General) => vec![],
+            _ => vec!["_".to_string()],
+        };
+
+        candidates
+            .into_iter()
+            .filter(|candidate| !pats.iter().any(|pat| covers(pat, candidate)))
+            .collect()
+    }
+
+    fn check_pat(&mut self, pat: &Pat, ty: &Ty) {
+        match pat {
+            Pat::Wild(_) => {}
+            Pat::Ident(p) => {
+                self.local.insert(&p.ident, ty.clone());
+                if let Some((_, pat)) = &p.subpat {
+                    self.check_pat(pat, ty);
+                }
+            }
+            Pat::Lit(p) => {
+                let found = match p.lit {
+                    Lit::Int(_) => self.new_var(VarKind::Integer),
+                    Lit::Float(_) => self.new_var(VarKind::Float),
+                    Lit::Bool(_) => Ty::Bool,
+                };
+                self.expect(p.lit.span(), ty, &found);
+            }
+            Pat::Path(p) => {
+                let found = self.check_pat_variant(&p.path, None);
+                let ident: &Ident = p.borrow();
+                self.expect(ident.span(), ty, &found);
+            }
+            Pat::TupleStruct(p) => {
+                let found = self.check_pat_variant(&p.path, Some(&p.pat));
+                let ident: &Ident = p.path.borrow();
+                self.expect(ident.span(), ty, &found);
+            }
+            Pat::Tuple(p) => {
+                let elems = match self.shallow_resolve(ty) {
+                    Ty::Tuple(elems) => Some(elems),
+                    // the length is given by a pattern without `..`
+                    Ty::Var(_, VarKind::General) if p.dot2_token.is_none() => {
+                        let elems: Vec<_> = p
+                            .front
+                            .iter()
+                            .map(|_| self.new_var(VarKind::General))
+                            .collect();
+                        self.expect(p.paren_token.span, ty, &Ty::Tuple(elems.clone()));
+                        Some(elems)
+                    }
+                    Ty::Var(_, VarKind::General) => {
+                        self.errors.push(syn::Error::new(
+                            p.paren_token.span,
+                            "type annotations needed",
+                        ));
+                        None
+                    }
+                    found => {
+                        let found = self.resolve(&found);
+                        self.errors.push(MismatchedTypesError::new(
+                            p.paren_token.span,
+                            &found,
+                            &TupleDisplay(p),
+                        ));
+                        None
+                    }
+                };
+                match elems {
+                    Some(elems) => {
+                        if !self.check_pat_elems(p, &elems) {
+                            let expected = self.resolve(&Ty::Tuple(elems));
+                            self.errors.push(MismatchedTypesError::new(
+                                p.paren_token.span,
+                                &expected,
+                                &TupleDisplay(p),
+                            ));
+                        }
+                    }
+                    // the names are bound all the same
+                    None => {
+                        self.check_pat_elems(p, &[]);
+                    }
+                }
+            }
+            Pat::Struct(p) => {
+                let found = self.check_pat_struct(p);
+                let ident: &Ident = p.path.borrow();
+                self.expect(ident.span(), ty, &found);
+            }
+            Pat::Or(p) => {
+                for pat in p.cases.iter() {
+                    self.check_pat(pat, ty);
+                }
+            }
+            Pat::List(p) => self.errors.push(syn::Error::new(
+                p.bracket_token.span,
+                "array patterns are not supported",
+            )),
+        }
+    }
+
+    // checks the elements of `(front.., .., back..)` against `elems`, returning whether
+    // their number fits. Elements without types are checked against unknown types.
+    fn check_pat_elems(&mut self, p: &PatTuple, elems: &[Ty]) -> bool {
+        let count = p.elems().count();
+        let fits = if p.dot2_token.is_some() {
+            count <= elems.len()
+        } else {
+            count == elems.len()
+        };
+        let back = elems.len().saturating_sub(p.back.len());
+        let tys: Vec<_> = if fits {
+            elems[..p.front.len()]
+                .iter()
+                .chain(elems[back..].iter())
+                .cloned()
+                .collect()
+        } else {
+            (0..count).map(|_| self.new_var(VarKind::General)).collect()
+        };
+        for (pat, ty) in p.elems().zip(tys.iter()) {
+            self.check_pat(pat, ty);
+        }
+        fits
+    }
+
+    // `Enum::Variant(pats..)`, or `Some(pat)` and `None` of events
+    fn check_pat_variant(&mut self, path: &Path, pat: Option<&PatTuple>) -> Ty {
+        let ident: &Ident = path.borrow();
+        let (fields, ty) = if path.is_qualified() {
+            match self.lookup_variant(path) {
+                Some(fields) => (fields, self.variant_ty(path)),
+                None => return self.new_var(VarKind::General),
+            }
+        } else if ident == "Some" || ident == "None" {
+            let elem = self.new_var(VarKind::General);
+            let fields = if ident == "Some" {
+                vec![elem.clone()]
+            } else {
+                vec![]
+            };
+            (fields, Ty::Event(Box::new(elem)))
+        } else {
+            self.errors.push(UnknownVariantError::event(ident));
+            return self.new_var(VarKind::General);
+        };
+        let fits = match pat {
+            Some(pat) => self.check_pat_elems(pat, &fields),
+            None => fields.is_empty(),
+        };
+        if !fits {
+            let found = pat.map_or(0, |pat| pat.elems().count());
+            self.errors
+                .push(ArgumentCountError::variant(ident, fields.len(), found));
+        }
+        ty
+    }
+
+    // `Struct { field: pat, .. }`
+    fn check_pat_struct(&mut self, p: &PatStruct) -> Ty {
+        let ident: &Ident = p.path.borrow();
+        let definition = match self.global.definitions.get(&ident.to_string()) {
+            Some(definition) => definition.clone(),
+            None => {
+                self.errors.push(UnknownStructError::new(ident));
+                return self.new_var(VarKind::General);
+            }
+        };
+        let ty = Ty::Named(ident.to_string());
+
+        for field in p.fields.iter() {
+            let expected = match &field.member {
+                Member::Named(member) => definition
+                    .iter()
+                    .find(|(field, _)| field == member)
+                    .map(|(_, ty)| ty.clone()),
+                Member::Unnamed(_) => None,
+            };
+            let expected = match expected {
+                Some(expected) => expected,
+                None => {
+                    self.errors.push(NoFieldError::new(
+                        member_span(&field.member),
+                        &ty,
+                        &MemberDisplay(&field.member),
+                    ));
+                    self.new_var(VarKind::General)
+                }
+            };
+            self.check_pat(&field.pat, &expected);
+        }
+
+        if p.dot2_token.is_none() {
+            for (field, _) in definition.iter() {
+                let mentioned = p.fields.iter().any(|pat| match &pat.member {
+                    Member::Named(member) => member == field,
+                    Member::Unnamed(_) => false,
+                });
+                if !mentioned {
+                    self.errors.push(MissingFieldError::pattern(ident, field));
+                }
+            }
+        }
+
+        ty
+    }
+
+    fn lookup(&self, ident: &Ident) -> Option<Ty> {
+        self.local
+            .get(ident)
+            .or_else(|| self.global.signals.get(ident))
+            .cloned()
+    }
+
+    // converts a syntactic type into the type used by the checker
+    fn lower(&mut self, ty: &types::Type) -> Ty {
+        use types::Type::*;
+        match ty {
+            Path(p) => {
+                if let Some(ty) = event_arg(p) {
+                    return Ty::Event(Box::new(self.lower(ty)));
+                }
+                let name = p.to_string();
+                if name == "bool" {
+                    Ty::Bool
+                } else if let Some(name) = INT_TYPES.iter().find(|ty| **ty == name) {
+                    Ty::Int(name)
+                } else if let Some(name) = FLOAT_TYPES.iter().find(|ty| **ty == name) {
+                    Ty::Float(name)
+                } else {
+                    Ty::Named(name)
+                }
+            }
+            Tuple(t) => Ty::Tuple(t.elems.iter().map(|ty| self.lower(ty)).collect()),
+            Paren(t) => self.lower(&t.ty),
+            Infer(_) => self.new_var(VarKind::General),
+            List(t) => Ty::Array(Box::new(self.lower(&t.ty)), Box::new(Ty::Len(t.length()))),
+        }
+    }
+
+    fn new_var(&mut self, kind: VarKind) -> Ty {
+        self.vars.push(VarState::Unbound(kind));
+        Ty::Var(self.vars.len() - 1, kind)
+    }
+
+    fn shallow_resolve(&self, ty: &Ty) -> Ty {
+        let mut ty = ty.clone();
+        while let Ty::Var(id, _) = ty {
+            match &self.vars[id] {
+                VarState::Bound(bound) => ty = bound.clone(),
+                VarState::Unbound(kind) => return Ty::Var(id, *kind),
+            }
+        }
+        ty
+    }
+
+    fn resolve(&self, ty: &Ty) -> Ty {
+        match self.shallow_resolve(ty) {
+            Ty::Tuple(elems) => Ty::Tuple(elems.iter().map(|ty| self.resolve(ty)).collect()),
+            Ty::Array(elem, len) => {
+                Ty::Array(Box::new(self.resolve(&elem)), Box::new(self.resolve(&len)))
+            }
+            Ty::Event(ty) => Ty::Event(Box::new(self.resolve(&ty))),
+            ty => ty,
+        }
+    }
+
+    fn expect(&mut self, span: Span, expected: &Ty, found: &Ty) {
+        if !self.unify(expected, found) {
+            let expected = self.resolve(expected);
+            let found = self.resolve(found);
+            self.errors
+                .push(MismatchedTypesError::new(span, &expected, &found));
+        }
+    }
+
+    fn unify(&mut self, a: &Ty, b: &Ty) -> bool {
+        use Ty::*;
+        let a = self.shallow_resolve(a);
+        let b = self.shallow_resolve(b);
+        match (a, b) {
+            (Var(a, _), Var(b, _)) if a == b => true,
+            (Var(a, ka), Var(b, kb)) => match (ka, kb) {
+                (VarKind::General, _) => self.bind(a, Var(b, kb)),
+                (_, VarKind::General) => self.bind(b, Var(a, ka)),
+                (ka, kb) if ka == kb => self.bind(a, Var(b, kb)),
+                _ => false,
+            },
+            (Var(id, kind), ty) | (ty, Var(id, kind)) => {
+                let compatible = match kind {
+                    VarKind::General => true,
+                    VarKind::Integer => matches!(ty, Int(_)),
+                    VarKind::Float => matches!(ty, Float(_)),
+                };
+                compatible && !self.occurs(id, &ty) && self.bind(id, ty)
+            }
+            (Tuple(a), Tuple(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| self.unify(a, b))
+            }
+            (Array(a, m), Array(b, n)) => self.unify(&a, &b) && self.unify(&m, &n),
+            (Event(a), Event(b)) => self.unify(&a, &b),
+            (a, b) => a == b,
+        }
+    }
+
+    fn bind(&mut self, id: usize, ty: Ty) -> bool {
+        self.vars[id] = VarState::Bound(ty);
+        true
+    }
+
+    fn occurs(&self, id: usize, ty: &Ty) -> bool {
+        match self.shallow_resolve(ty) {
+            Ty::Var(other, _) => id == other,
+            Ty::Tuple(elems) => elems.iter().any(|ty| self.occurs(id, ty)),
+            Ty::Array(elem, len) => self.occurs(id, &elem) || self.occurs(id, &len),
+            Ty::Event(ty) => self.occurs(id, &ty),
+            _ => false,
+        }
+    }
+
+    // literals without any constraint fall back to the same defaults as rustc
+    fn default_vars(&mut self) {
+        for var in self.vars.iter_mut() {
+            match var {
+                VarState::Unbound(VarKind::Integer) => *var = VarState::Bound(Ty::Int("i32")),
+                VarState::Unbound(VarKind::Float) => *var = VarState::Bound(Ty::Float("f64")),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_obligations(&mut self) {
+        let obligations = std::mem::take(&mut self.obligations);
+        for (ty, class, span) in obligations {
+            let ty = self.resolve(&ty);
+            if !class.accepts(&ty) && !self.is_castable_enum(class, &ty) {
+                self.errors
+                    .push(InvalidOperandError::new(span, &ty, class.description()));
+            }
+        }
+    }
+
+    // enums whose variants have no fields are cast to integers as in Rust
+    fn is_castable_enum(&self, class: Class, ty: &Ty) -> bool {
+        match (class, ty) {
+            (Class::Primitive, Ty::Named(name)) => matches!(
+                self.global.variants.get(name),
+                Some(variants) if variants.iter().all(|(_, fields)| fields.is_empty())
+            ),
+            _ => false,
+        }
+    }
+
+    fn check_bounds(&mut self) {
+        let bounds = std::mem::take(&mut self.bounds);
+        for (len, index, span) in bounds {
+            if let Ty::Len(len) = self.resolve(&len) {
+                if index >= len as u64 {
+                    self.errors
+                        .push(IndexOutOfBoundsError::new(span, len, index));
+                }
+            }
+        }
+    }
+}
+
+// the argument of `Event<T>`
+fn event_arg(ty: &types::TypePath) -> Option<&types::Type> {
+    match &ty.path {
+        Path::Segment(PathSegment {
+            ident,
+            arguments: PathArguments::AngleBracketed(args),
+        }) if ident == "Event" && args.args.len() == 1 => args.args.first(),
+        _ => None,
+    }
+}
+
+fn member_span(member: &Member) -> Span {
+    match member {
+        Member::Named(ident) => ident.span(),
+        Member::Unnamed(index) => index.span,
+    }
+}
+
+fn is_irrefutable(pat: &Pat) -> bool {
+    match pat {
+        Pat::Wild(_) => true,
+        Pat::Ident(p) => match &p.subpat {
+            Some((_, pat)) => is_irrefutable(pat),
+            None => true,
+        },
+        Pat::Tuple(p) => p.elems().all(is_irrefutable),
+        Pat::Struct(p) => p.fields.iter().all(|field| is_irrefutable(&field.pat)),
+        Pat::Or(p) => p.cases.iter().any(is_irrefutable),
+        _ => false,
+    }
+}
+
+// whether `pat` matches every value described by `candidate`
+fn covers(pat: &Pat, candidate: &str) -> bool {
+    match pat {
+        Pat::Lit(p) => match &p.lit {
+            Lit::Bool(b) => candidate == if b.value { "True" } else { "False" },
+            _ => false,
+        },
+        Pat::Path(p) => candidate == p.path.to_token_stream().to_string().replace(' ', ""),
+        Pat::TupleStruct(p) => {
+            let path = p.path.to_token_stream().to_string().replace(' ', "");
+            candidate.starts_with(&format!("{}(", path)) && p.pat.elems().all(is_irrefutable)
+        }
+        Pat::Ident(p) => match &p.subpat {
+            Some((_, pat)) => covers(pat, candidate),
+            None => true,
+        },
+        Pat::Or(p) => p.cases.iter().any(|pat| covers(pat, candidate)),
+        pat => is_irrefutable(pat),
+    }
+}
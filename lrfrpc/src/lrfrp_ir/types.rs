@@ -1,4 +1,4 @@
-use super::error::{LiftedTypeNotAllowedError, UndefinedVariableError};
+use super::error::{Errors, LiftedTypeNotAllowedError, UndefinedVariableError};
 
 use std::borrow::Borrow;
 use std::cell::RefCell;
@@ -49,6 +49,12 @@ impl Type {
     pub fn from_local() -> Self {
         Type::Lifted(TypeLifted::Signal(TypeSignal::Local(MaybeType::Unresolved)))
     }
+
+    pub fn from_inferred(ty: &types::Type) -> Self {
+        Type::Lifted(TypeLifted::Signal(TypeSignal::Local(MaybeType::Resolved(
+            Box::new(ty.clone()),
+        ))))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -116,7 +122,7 @@ pub struct TyCtx<'a, 'b> {
     scope: usize,
     local: Vec<VarEnv>,
     deps: Dependency<'b>,
-    errors: Vec<syn::Error>,
+    errors: Errors,
     forbid_lifted: bool,
 }
 
@@ -127,7 +133,7 @@ impl<'a, 'b> TyCtx<'a, 'b> {
             scope: 0,
             local: vec![],
             deps: Dependency::new(),
-            errors: vec![],
+            errors: Errors::new(),
             forbid_lifted,
         };
         ty_ctx.scoped();
@@ -135,16 +141,8 @@ impl<'a, 'b> TyCtx<'a, 'b> {
     }
 
     pub fn try_get_deps(self) -> Result<Dependency<'b>> {
-        if !self.errors.is_empty() {
-            let mut iter = self.errors.into_iter();
-            let head = iter.next().unwrap_or_else(|| unreachable!());
-            Err(iter.fold(head, |mut acc, error| {
-                acc.combine(error);
-                acc
-            }))
-        } else {
-            Ok(self.deps)
-        }
+        self.errors.finish()?;
+        Ok(self.deps)
     }
 
     fn forbid_lifted(&self) -> bool {
@@ -155,7 +153,7 @@ impl<'a, 'b> TyCtx<'a, 'b> {
         use Pat::*;
         let current_local = &mut self.local[self.scope - 1];
         match pat {
-            Wild(_) => {}
+            Wild(_) | Lit(_) | Path(_) => {}
             Ident(p) => {
                 let ident = p.ident.clone();
                 current_local.insert(ident, Type::unresolved());
@@ -163,11 +161,37 @@ impl<'a, 'b> TyCtx<'a, 'b> {
                     self.insert_local(pat);
                 }
             }
-            _ => unimplemented!("insert local for pat"),
+            TupleStruct(p) => {
+                for pat in p.pat.elems_mut() {
+                    self.insert_local(pat);
+                }
+            }
+            Tuple(p) => {
+                for pat in p.elems_mut() {
+                    self.insert_local(pat);
+                }
+            }
+            Struct(p) => {
+                for field in p.fields.iter_mut() {
+                    self.insert_local(&mut field.pat);
+                }
+            }
+            Or(p) => {
+                for pat in p.cases.iter_mut() {
+                    self.insert_local(pat);
+                }
+            }
+            // rejected by the type checker
+            List(_) => {}
         }
     }
 
     fn insert_variable(&mut self, path: &'b mut Path) {
+        // enum variants are not variables
+        if path.is_qualified() {
+            return;
+        }
+
         // search local scope
         let key = Borrow::<Ident>::borrow(path).clone();
         for scope in (0..self.scope).rev() {
@@ -180,7 +204,7 @@ impl<'a, 'b> TyCtx<'a, 'b> {
         if let Some(ty) = self.global.get(&key) {
             match ty {
                 Type::Lifted(ty) if self.forbid_lifted() => {
-                    self.push_error(LiftedTypeNotAllowedError::new(&key, ty))
+                    self.errors.push(LiftedTypeNotAllowedError::new(&key, ty))
                 }
                 _ => {
                     path.typing(ty);
@@ -188,15 +212,12 @@ impl<'a, 'b> TyCtx<'a, 'b> {
                 }
             }
         } else {
-            self.push_error(UndefinedVariableError::new(&key))
+            self.errors.push(UndefinedVariableError::new(&key))
         }
     }
 
-    fn push_error<E>(&mut self, e: E)
-    where
-        E: Into<syn::Error>,
-    {
-        self.errors.push(e.into())
+    fn is_defined(&self, ident: &Ident) -> bool {
+        self.local.iter().any(|scope| scope.contains_key(ident)) || self.global.contains_key(ident)
     }
 
     fn scoped(&mut self) {
@@ -221,6 +242,10 @@ impl<'a, 'b, 'c> TyCtxRef<'a, 'b, 'c> {
         self.0.borrow_mut().scoped();
     }
 
+    pub fn unscoped(&self) {
+        self.0.borrow_mut().unscoped();
+    }
+
     pub fn insert_local(&self, pat: &'c mut Pat) {
         self.0.borrow_mut().insert_local(pat)
     }
@@ -228,6 +253,10 @@ impl<'a, 'b, 'c> TyCtxRef<'a, 'b, 'c> {
     pub fn insert_variable(&self, path: &'c mut Path) {
         self.0.borrow_mut().insert_variable(path)
     }
+
+    pub fn is_defined(&self, ident: &Ident) -> bool {
+        self.0.borrow().is_defined(ident)
+    }
 }
 
 impl Drop for TyCtxRef<'_, '_, '_> {
@@ -0,0 +1,114 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const USAGE: &str = "usage: lrfrpc <input.lrfrp> [-o <output>] [--emit rust|c|header]
+
+Compiles an lrfrp program into a Rust module. The output defaults to the input with
+the extension `.rs`, and `-o -` writes it to the standard output.
+
+With `--emit c`, a C source and a header are written instead. The output defaults to
+the input with the extension `.c`, and the header is written beside it with `.h`.
+
+With `--emit header`, the C header declaring the functions exported by an `#[ffi]`
+module is written instead. The output defaults to the input with the extension `.h`.";
+
+enum Emit {
+    Rust,
+    C,
+    Header,
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2)
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut input = None;
+    let mut output = None;
+    let mut emit = Emit::Rust;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "-o" => match args.next() {
+                Some(path) => output = Some(path),
+                None => fail("`-o` requires a path"),
+            },
+            "--emit" => match args.next().as_deref() {
+                Some("rust") => emit = Emit::Rust,
+                Some("c") => emit = Emit::C,
+                Some("header") => emit = Emit::Header,
+                _ => fail("`--emit` requires `rust`, `c` or `header`"),
+            },
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => fail(&format!("unexpected argument `{}`", arg)),
+        }
+    }
+    let input = input.unwrap_or_else(|| fail("no input file"));
+
+    let (code, extension) = match emit {
+        Emit::Rust => (lrfrpc::compile_file(&input), "rs"),
+        Emit::Header => (lrfrpc::compile_header_file(&input), "h"),
+        Emit::C => {
+            emit_c_files(&input, output.as_deref());
+            return;
+        }
+    };
+    let code = match code {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1)
+        }
+    };
+
+    match output.as_deref() {
+        Some("-") => print!("{}", code),
+        output => {
+            let output = output
+                .map(PathBuf::from)
+                .unwrap_or_else(|| input.with_extension(extension));
+            if let Err(e) = fs::write(&output, code) {
+                eprintln!("error: cannot write `{}`: {}", output.display(), e);
+                process::exit(1)
+            }
+        }
+    }
+}
+
+fn emit_c_files(input: &Path, output: Option<&str>) {
+    let source_path = match output {
+        Some("-") => fail("`-o -` cannot be used with `--emit c`"),
+        Some(path) => PathBuf::from(path),
+        None => input.with_extension("c"),
+    };
+    let header_path = source_path.with_extension("h");
+    let header_name = header_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let c_source = match lrfrpc::compile_c_file(input, &header_name) {
+        Ok(c_source) => c_source,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1)
+        }
+    };
+
+    for (path, code) in [
+        (&header_path, c_source.header),
+        (&source_path, c_source.source),
+    ] {
+        if let Err(e) = fs::write(path, code) {
+            eprintln!("error: cannot write `{}`: {}", path.display(), e);
+            process::exit(1)
+        }
+    }
+}
@@ -0,0 +1,235 @@
+// Checks the messages and the locations of the errors and the warnings `lrfrpc` reports
+// for programs.
+
+use lrfrpc::Diagnostic;
+
+type Located = (String, Option<(usize, usize)>);
+
+// the body follows a header of 3 lines
+fn source(body: &str) -> String {
+    format!("mod M;\nIn {{ x: i32 }}\nOut {{ y: i32 }}\n{}", body)
+}
+
+fn located(diagnostics: Vec<Diagnostic>) -> Vec<Located> {
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| (diagnostic.message, diagnostic.location))
+        .collect()
+}
+
+fn errors(body: &str) -> Vec<Located> {
+    located(lrfrpc::compile_str(&source(body)).unwrap_err())
+}
+
+fn warnings(body: &str) -> Vec<Located> {
+    located(lrfrpc::check_str(&source(body)).unwrap())
+}
+
+fn error(message: &str, line: usize, column: usize) -> Located {
+    (message.to_owned(), Some((line, column)))
+}
+
+#[test]
+fn type_errors_are_reported_with_undefined_variables() {
+    assert_eq!(
+        errors("let y = z + x;\nlet w = x && True;"),
+        [
+            error("use of undefined variable: `z`", 4, 9),
+            error("mismatched types: expected `bool`, found `i32`", 5, 9),
+        ]
+    );
+}
+
+#[test]
+fn type_errors_are_reported_with_cycles() {
+    assert_eq!(
+        errors("let y = a;\nlet a = y + 1;\nlet b = x && True;"),
+        [
+            error("cyclic dependency found: `a -> y -> a`", 5, 5),
+            error("`a` depends on `y` here", 5, 9),
+            error("`y` depends on `a` here", 4, 9),
+            error(
+                "help: use `pre a` or a cell defined with `delay` here to break the cycle",
+                4,
+                9
+            ),
+            error("mismatched types: expected `bool`, found `i32`", 6, 9),
+        ]
+    );
+}
+
+#[test]
+fn mismatched_types_are_reported() {
+    assert_eq!(
+        errors("let y = True;"),
+        [error(
+            "mismatched types: expected `i32`, found `bool`",
+            4,
+            9
+        )]
+    );
+    assert_eq!(
+        errors("let y = if x then 1 else 0;"),
+        [error(
+            "mismatched types: expected `bool`, found `i32`",
+            4,
+            12
+        )]
+    );
+    assert_eq!(
+        errors("let y = if -(x > 0) then 1 else 0;"),
+        [error("expected signed numeric type, found `bool`", 4, 12)]
+    );
+}
+
+#[test]
+fn function_calls_are_checked() {
+    assert_eq!(
+        errors("fn f(a: i32) -> i32 = a;\nlet y = f(True);"),
+        [error(
+            "mismatched types: expected `i32`, found `bool`",
+            5,
+            11
+        )]
+    );
+    assert_eq!(
+        errors("fn f(a: i32) -> i32 = a;\nlet y = f(x, x);"),
+        [error(
+            "function `f` takes 1 argument(s) but 2 were supplied",
+            5,
+            9
+        )]
+    );
+}
+
+#[test]
+fn struct_errors_are_reported() {
+    assert_eq!(
+        errors("let y = P { a: 1 }.a;"),
+        [error("cannot find struct `P`", 4, 9)]
+    );
+    assert_eq!(
+        errors("struct P { a: i32, b: i32 }\nlet y = P { a: 1, c: 2 }.a;"),
+        [
+            error("no field `c` on type `P`", 5, 19),
+            error("missing field `b` in initializer of `P`", 5, 9),
+        ]
+    );
+    assert_eq!(
+        errors("struct P { a: i32, b: i32 }\nlet p = P { a: 1, b: 2 };\nlet y = p.c;"),
+        [error("no field `c` on type `P`", 6, 9)]
+    );
+    assert_eq!(
+        errors("let y = x.a;"),
+        [error("no field `a` on type `i32`", 4, 9)]
+    );
+}
+
+#[test]
+fn enum_errors_are_reported() {
+    assert_eq!(
+        errors("let y = match Foo::A { _ => 0 };"),
+        [error("cannot find enum `Foo`", 4, 15)]
+    );
+    assert_eq!(
+        errors("enum Mode { On, Off }\nlet y = match Mode::Up { _ => 0 };"),
+        [error("no variant `Up` in enum `Mode`", 5, 21)]
+    );
+}
+
+#[test]
+fn non_exhaustive_matches_are_reported() {
+    assert_eq!(
+        errors(
+            "enum Mode { On, Off }\n\
+             let m = if x > 0 then Mode::On else Mode::Off;\n\
+             let y = match m { Mode::On => 1 };"
+        ),
+        [error(
+            "non-exhaustive patterns: `Mode::Off` not covered",
+            6,
+            9
+        )]
+    );
+}
+
+#[test]
+fn tuple_and_array_errors_are_reported() {
+    assert_eq!(
+        errors("let y = (x, x).2;"),
+        [error("no field `2` on type `(i32, i32)`", 4, 9)]
+    );
+    assert_eq!(
+        errors("let a = [x, x, x];\nlet y = a[3];"),
+        [error(
+            "index out of bounds: the length is 3 but the index is 3",
+            5,
+            11
+        )]
+    );
+    assert_eq!(
+        errors("let y = x[0];"),
+        [error("cannot index into a value of type `i32`", 4, 9)]
+    );
+}
+
+#[test]
+fn event_errors_are_reported() {
+    assert_eq!(
+        errors("let e = when(x > 0, x);\nlet y = e;"),
+        [error(
+            "mismatched types: expected `i32`, found `Event<i32>`",
+            5,
+            9
+        )]
+    );
+    assert_eq!(
+        errors("let y = hold(0, x);"),
+        [error(
+            "mismatched types: expected `Event<{integer}>`, found `i32`",
+            4,
+            17
+        )]
+    );
+}
+
+#[test]
+fn cycles_are_reported_with_their_paths() {
+    assert_eq!(
+        errors("let y = a;\nlet a = b;\nlet b = y;"),
+        [
+            error("cyclic dependency found: `a -> b -> y -> a`", 5, 5),
+            error("`a` depends on `b` here", 5, 9),
+            error("`b` depends on `y` here", 6, 9),
+            error("`y` depends on `a` here", 4, 9),
+            error(
+                "help: use `pre a` or a cell defined with `delay` here to break the cycle",
+                4,
+                9
+            ),
+        ]
+    );
+    assert_eq!(
+        errors("let y = x;\nlet c: i32 <- delay 0 -< d;\nlet d: i32 <- delay 0 -< c;"),
+        [
+            error("cyclic dependency found: `c -> d -> c`", 5, 5),
+            error("the update of `c` reads `d` here", 5, 26),
+            error("the update of `d` reads `c` here", 6, 26),
+            error(
+                "help: read `c` through a signal such as `let c_now = c;` in the update of `d` to break the cycle",
+                6,
+                26
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unused_variables_are_warned() {
+    assert_eq!(warnings("let y = x;"), []);
+    assert_eq!(
+        warnings("let y = x;\nlet w = x + 1;"),
+        [error("unused signal `w`", 5, 5)]
+    );
+    assert_eq!(warnings("let y = 1;"), [error("unused input `x`", 2, 6)]);
+}